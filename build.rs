@@ -0,0 +1,26 @@
+//! Captures the toolchain the crate is built with, see `src/plugin/abi.rs`, and compiles
+//! gRPC protos with `grpc` feature.
+
+use std::{env, process::Command};
+
+fn main() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_owned())
+        .unwrap_or_else(|| "unknown".to_owned());
+    let target = env::var("TARGET").unwrap_or_else(|_| "unknown".to_owned());
+
+    println!("cargo:rustc-env=EXEX_PLUGIN_RUSTC_VERSION={rustc_version}");
+    println!("cargo:rustc-env=EXEX_PLUGIN_TARGET={target}");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/exex_plugin.proto");
+        tonic_build::compile_protos("proto/exex_plugin.proto").expect("failed to compile protos");
+    }
+}
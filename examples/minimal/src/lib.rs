@@ -2,14 +2,52 @@
 //!
 //! Simply takes a notification's chain kind & range of block numbers
 //!     and store them to `OUT_PATH` json file, if it was either revert or commit.
+//!
+//! If the plugin is loaded with `{"output": "jsonl.gz"}` config, notifications are appended
+//!     to `OUT_GZ_PATH` gzip compressed JSONL file instead. Without an output in the config,
+//!     it falls back to `MINIMAL_EXEX_OUTPUT` environment variable set on load.
+//!
+//! If `MINIMAL_EXEX_SCHEMA_VERSION` is set on load, it's the plugin's schema version.
+//!
+//! If `MINIMAL_EXEX_ID` is set on the library's first load, it overrides the plugin's id,
+//!     so copies of the library can be loaded side by side.
+//!
+//! If `MINIMAL_EXEX_RESULT_OFFSET` is set on load, the plugin returns a committed tip plus
+//!     the offset as its result of each notification, e.g. to diverge a shadow of the plugin.
+//!
+//! If `MINIMAL_EXEX_FAIL` is set on load, the plugin fails every notification.
 
-use std::{future::Future, pin::Pin};
+use std::{
+    fs::OpenOptions,
+    future::Future,
+    io::Write,
+    pin::Pin,
+    sync::{Arc, OnceLock},
+};
 
 use eyre::Result;
-use reth_exex_plugin::{ExExNotification, ExExPlugin};
-use serde::Serialize;
+use flate2::{write::GzEncoder, Compression};
+use reth_exex_plugin::{ExExNotification, ExExPlugin, NodeInfo};
+use serde::{Deserialize, Serialize};
 
 const OUT_PATH: &str = "examples/minimal/assets/notifications.json";
+const OUT_GZ_PATH: &str = "examples/minimal/assets/notifications.jsonl.gz";
+/// Environment variable to select the [Output] of the plugin, if its config doesn't
+const OUTPUT_ENV: &str = "MINIMAL_EXEX_OUTPUT";
+/// Environment variable to override the plugin's id
+const ID_ENV: &str = "MINIMAL_EXEX_ID";
+
+/// Environment variable to set the plugin's schema version
+const SCHEMA_VERSION_ENV: &str = "MINIMAL_EXEX_SCHEMA_VERSION";
+
+/// Environment variable to set an offset of the plugin's results
+const RESULT_OFFSET_ENV: &str = "MINIMAL_EXEX_RESULT_OFFSET";
+
+/// Environment variable to fail every notification
+const FAIL_ENV: &str = "MINIMAL_EXEX_FAIL";
+
+/// Id of the plugin, fixed on the library's first load
+static ID: OnceLock<String> = OnceLock::new();
 
 #[derive(Serialize)]
 enum ProcessedExExNotification {
@@ -17,12 +55,60 @@ enum ProcessedExExNotification {
     Revert { from: u64, to: u64 },
 }
 
+/// Output format of processed notifications
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub(crate) enum Output {
+    /// The last notification in the [`OUT_PATH`] json file
+    #[default]
+    #[serde(rename = "json")]
+    Json,
+    /// All notifications appended to the [`OUT_GZ_PATH`] gzip compressed JSONL file
+    #[serde(rename = "jsonl.gz")]
+    JsonlGz,
+}
+
+impl Output {
+    /// Reads the output from [`OUTPUT_ENV`] environment variable, a fallback for a plugin
+    /// loaded without an output in its config
+    fn from_env() -> Self {
+        match std::env::var(OUTPUT_ENV).as_deref() {
+            Ok("jsonl.gz") => Self::JsonlGz,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Config of the plugin, passed on load, e.g. by `exex_loadPlugin` RPC
+#[derive(Debug, Deserialize)]
+struct MinimalConfig {
+    /// Output format of processed notifications, either `json` or `jsonl.gz`
+    output: Option<Output>,
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct MinimalExEx;
+pub(crate) struct MinimalExEx {
+    output: Output,
+    schema_version: u32,
+    result_offset: Option<u64>,
+    fail: bool,
+}
 
 impl ExExPlugin for MinimalExEx {
     fn id(&self) -> &'static str {
-        "MinimalExEx"
+        ID.get_or_init(|| std::env::var(ID_ENV).unwrap_or_else(|_| "MinimalExEx".to_owned()))
+    }
+
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Example usage of config hook, selecting the plugin's [Output]
+    fn on_config(&mut self, config: serde_json::Value) -> Result<()> {
+        let config: MinimalConfig = serde_json::from_value(config)?;
+        if let Some(output) = config.output {
+            self.output = output;
+        }
+        Ok(())
     }
 
     /// Example usage of loading hook
@@ -41,14 +127,18 @@ impl ExExPlugin for MinimalExEx {
     ///     and store them to `OUT_PATH` json file, if it was either revert or commit.
     fn handle_notification<'a: 'b, 'b>(
         &'a self,
-        notification: &'a ExExNotification,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
         Box::pin(async move {
-            match notification {
+            if self.fail {
+                eyre::bail!("failed on purpose");
+            }
+            match notification.as_ref() {
                 ExExNotification::ChainCommitted { new } => {
                     // received commit
                     let range = new.range();
-                    write_notification(ProcessedExExNotification::Commit {
+                    self.write_notification(ProcessedExExNotification::Commit {
                         from: *range.start(),
                         to: *range.end(),
                     })
@@ -56,7 +146,7 @@ impl ExExPlugin for MinimalExEx {
                 ExExNotification::ChainReverted { old } => {
                     // received revert
                     let range = old.range();
-                    write_notification(ProcessedExExNotification::Revert {
+                    self.write_notification(ProcessedExExNotification::Revert {
                         from: *range.start(),
                         to: *range.end(),
                     })
@@ -65,6 +155,44 @@ impl ExExPlugin for MinimalExEx {
             }
         })
     }
+
+    /// Example usage of notification results, returned only if an offset is set
+    fn handle_notification_with_result<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        Box::pin(async move {
+            let tip = notification.committed_chain().map(|chain| chain.tip().number);
+            self.handle_notification(notification, node_info).await?;
+            Ok(self
+                .result_offset
+                .zip(tip)
+                .map(|(offset, tip)| serde_json::json!({ "tip": tip + offset })))
+        })
+    }
+}
+
+impl MinimalExEx {
+    /// Plugin constructor, configured from environment variables on load
+    fn from_env() -> Self {
+        let schema_version = std::env::var(SCHEMA_VERSION_ENV)
+            .ok()
+            .and_then(|version| version.parse().ok())
+            .unwrap_or(0);
+        let result_offset =
+            std::env::var(RESULT_OFFSET_ENV).ok().and_then(|offset| offset.parse().ok());
+        let fail = std::env::var_os(FAIL_ENV).is_some();
+        Self { output: Output::from_env(), schema_version, result_offset, fail }
+    }
+
+    /// Writes a given [ProcessedExExNotification] to the plugin's [Output]
+    fn write_notification(&self, notification: ProcessedExExNotification) -> Result<()> {
+        match self.output {
+            Output::Json => write_notification(notification),
+            Output::JsonlGz => append_notification_gz(notification),
+        }
+    }
 }
 
 /// Writes a given [ProcessedExExNotification] in the [`OUT_PATH`]
@@ -72,18 +200,18 @@ fn write_notification(notification: ProcessedExExNotification) -> Result<()> {
     std::fs::write(OUT_PATH, serde_json::to_string_pretty(&notification)?).map_err(Into::into)
 }
 
-reth_exex_plugin::declare_exex_plugin!(MinimalExEx);
-
-/// Plugin constructor
+/// Appends a given [ProcessedExExNotification] line to the [`OUT_GZ_PATH`]
 ///
-/// # Safety
-///
-/// See [`ExExPlugin`] loading on [`reth_exex_plugin`] crate.
-/// Especeally, a manager declaration with method for plugin load.
-#[no_mangle]
-#[allow(improper_ctypes_definitions)]
-pub unsafe extern "C" fn __create_exex_plugin() -> *mut dyn ExExPlugin {
-    let plugin = MinimalExEx;
-    let plugin: Box<dyn ExExPlugin> = Box::new(plugin);
-    Box::into_raw(plugin)
+/// Every append is a separate gzip member, which is finished right away,
+///     so the file stays valid between notifications and is read as a whole by multi-member
+///     gzip decoders (e.g. `zcat`).
+fn append_notification_gz(notification: ProcessedExExNotification) -> Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(OUT_GZ_PATH)?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    serde_json::to_writer(&mut encoder, &notification)?;
+    encoder.write_all(b"\n")?;
+    encoder.finish()?.sync_data()?;
+    Ok(())
 }
+
+reth_exex_plugin::declare_exex_plugin!(MinimalExEx, MinimalExEx::from_env);
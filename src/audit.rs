@@ -0,0 +1,54 @@
+//! Audit trail of the [`ExExPluginManager`](crate::ExExPluginManager)'s committed progress.
+
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use eyre::Result;
+use reth::primitives::{BlockNumHash, B256};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::jsonl::{JsonlWriter, RecordSink};
+
+/// A `FinishedHeight` event emitted by the manager.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinishedHeightRecord {
+    /// Time of the emission, as a unix timestamp in milliseconds.
+    pub timestamp_ms: u64,
+    pub number: u64,
+    pub hash: B256,
+}
+
+impl FinishedHeightRecord {
+    /// Creates a record of a given height emitted at the current time.
+    pub fn new(height: BlockNumHash) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_millis() as u64)
+            .unwrap_or_default();
+        Self { timestamp_ms, number: height.number, hash: height.hash }
+    }
+}
+
+/// Append-only sink of emitted `FinishedHeight` events, see
+/// [`ExExPluginManager::with_finished_height_audit`](crate::ExExPluginManager::with_finished_height_audit).
+#[derive(Debug, Clone)]
+pub enum AuditSink {
+    /// JSONL file, a record is appended per line by a writer thread, which keeps the file open
+    /// and syncs it after every batch of records.
+    File(PathBuf),
+    /// Channel of records.
+    Channel(mpsc::UnboundedSender<FinishedHeightRecord>),
+}
+
+impl AuditSink {
+    /// Opens the sink, spawning a writer of the file if the sink is a file.
+    pub(crate) fn open(self) -> Result<RecordSink<FinishedHeightRecord>> {
+        Ok(match self {
+            Self::File(path) => RecordSink::File(JsonlWriter::spawn(path)?),
+            Self::Channel(tx) => RecordSink::Channel(tx),
+        })
+    }
+}
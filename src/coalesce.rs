@@ -0,0 +1,137 @@
+//! Coalescing of committed notifications during sync, see
+//! [`ExExPluginManager::with_coalescing`](crate::ExExPluginManager::with_coalescing).
+
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reth::{primitives::BlockNumHash, providers::Chain};
+use reth_exex::ExExNotification;
+use reth_tracing::tracing::warn;
+
+use crate::{notification::SequencedNotification, plugin::LoadedExExPlugin};
+
+/// [Capability](crate::ExExPlugin::capabilities) of plugins, which receive coalesced
+/// notifications during sync.
+pub const COALESCE_CAPABILITY: &str = "coalesce";
+
+/// Configuration of notifications coalescing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoalesceConfig {
+    /// Minimum age of a committed tip for the node to be considered behind the chain tip,
+    /// judged by the tip block's timestamp.
+    pub lag: Duration,
+    /// Maximum number of blocks merged into a single notification.
+    pub window: u64,
+}
+
+/// Merges consecutive commits into a single notification, while the node is behind.
+#[derive(Debug)]
+pub(crate) struct Coalescer {
+    config: CoalesceConfig,
+    /// Merged commits not dispatched yet.
+    pending: Option<Chain>,
+}
+
+impl Coalescer {
+    pub(crate) const fn new(config: CoalesceConfig) -> Self {
+        Self { config, pending: None }
+    }
+
+    /// Takes a received notification, given whether any plugin coalesces notifications.
+    ///
+    /// Returns notifications to dispatch to coalescing plugins, in order: a merged commit
+    /// once the window is full, or pending commits followed by the notification itself
+    /// once the node is near the chain tip or a non-commit notification arrives.
+    pub(crate) fn push(
+        &mut self,
+        notification: &Arc<ExExNotification>,
+        enabled: bool,
+    ) -> Vec<Arc<ExExNotification>> {
+        let mut dispatched = Vec::new();
+        let commit = match notification.as_ref() {
+            ExExNotification::ChainCommitted { new } if enabled && self.is_behind(new) => new,
+            _ => {
+                dispatched.extend(self.take_pending());
+                dispatched.push(notification.clone());
+                return dispatched;
+            }
+        };
+
+        let new = Chain::clone(commit);
+        self.pending = Some(match self.pending.take() {
+            None => new,
+            Some(mut pending) => match pending.append_chain(new.clone()) {
+                Ok(()) => pending,
+                Err(err) => {
+                    warn!(%err, "failed to coalesce notification, dispatching pending blocks");
+                    dispatched.push(committed(pending));
+                    new
+                }
+            },
+        });
+        if self.pending.as_ref().is_some_and(|pending| pending.len() as u64 >= self.config.window) {
+            dispatched.extend(self.take_pending());
+        }
+
+        dispatched
+    }
+
+    /// Returns the block before the pending commits, if any, i.e. the highest block
+    /// coalescing plugins have received.
+    pub(crate) fn pending_fork_block(&self) -> Option<BlockNumHash> {
+        self.pending.as_ref().map(Chain::fork_block)
+    }
+
+    /// Takes pending commits as a single notification.
+    fn take_pending(&mut self) -> Option<Arc<ExExNotification>> {
+        self.pending.take().map(committed)
+    }
+
+    /// Returns `true` if a committed chain's tip is older than the [lag](CoalesceConfig::lag).
+    fn is_behind(&self, chain: &Chain) -> bool {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        Duration::from_secs(chain.tip().timestamp) + self.config.lag < now
+    }
+}
+
+/// Commit notification of a given chain.
+fn committed(chain: Chain) -> Arc<ExExNotification> {
+    Arc::new(ExExNotification::ChainCommitted { new: Arc::new(chain) })
+}
+
+/// Notifications dispatched to plugins at once, coalesced for the plugins opted in.
+#[derive(Debug, Default)]
+pub(crate) struct DispatchBatch {
+    /// Received notifications, dispatched to plugins which don't coalesce.
+    received: Vec<SequencedNotification>,
+    /// Notifications dispatched to coalescing plugins, `None` without a coalescer.
+    coalesced: Option<Vec<SequencedNotification>>,
+}
+
+impl DispatchBatch {
+    /// Batch of a received notification, pushed to a coalescer, if any, given whether any
+    /// plugin coalesces notifications. Coalesced notifications take the received one's
+    /// sequence number.
+    pub(crate) fn new(
+        sequenced: SequencedNotification,
+        coalescer: Option<&mut Coalescer>,
+        enabled: bool,
+    ) -> Self {
+        let (notification, sequence) = &sequenced;
+        let coalesced = coalescer.map(|coalescer| {
+            let coalesced = coalescer.push(notification, enabled).into_iter();
+            coalesced.map(|coalesced| (coalesced, *sequence)).collect()
+        });
+        Self { received: vec![sequenced], coalesced }
+    }
+
+    /// Returns notifications dispatched to a given plugin.
+    pub(crate) fn notifications(&self, plugin: &LoadedExExPlugin) -> &[SequencedNotification] {
+        match &self.coalesced {
+            Some(coalesced) if plugin.coalesces() => coalesced,
+            _ => &self.received,
+        }
+    }
+}
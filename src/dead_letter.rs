@@ -0,0 +1,136 @@
+//! Dead letters of notifications no plugin could handle.
+
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use eyre::Result;
+use reth_tracing::tracing::{error, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::{
+    jsonl::{JsonlWriter, RecordSink},
+    notification::SequencedNotification,
+    plugin::LoadedExExPlugin,
+    NormalizedNotification,
+};
+
+/// A notification failed by all plugins it was dispatched to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetterRecord {
+    /// Time of the failure, as a unix timestamp in milliseconds.
+    pub timestamp_ms: u64,
+    /// [Sequence number](crate::NodeInfo::sequence) of the notification.
+    pub sequence: u64,
+    pub notification: NormalizedNotification,
+    /// Ids of the failed plugins, in dispatch order.
+    pub failing: Vec<String>,
+}
+
+/// Sink of [dead letters](DeadLetterRecord), see
+/// [`ExExPluginManager::with_dead_letter_sink`](crate::ExExPluginManager::with_dead_letter_sink).
+#[derive(Debug, Clone)]
+pub enum DeadLetterSink {
+    /// JSONL file, a record is appended per line by a writer thread, which keeps the file open
+    /// and syncs it after every batch of records.
+    File(PathBuf),
+    /// Channel of records.
+    Channel(mpsc::UnboundedSender<DeadLetterRecord>),
+}
+
+impl DeadLetterSink {
+    /// Opens the sink, spawning a writer of the file if the sink is a file.
+    pub(crate) fn open(self) -> Result<RecordSink<DeadLetterRecord>> {
+        Ok(match self {
+            Self::File(path) => RecordSink::File(JsonlWriter::spawn(path)?),
+            Self::Channel(tx) => RecordSink::Channel(tx),
+        })
+    }
+}
+
+/// Outcome of a notification dispatched to plugins.
+#[derive(Debug)]
+struct Outcome {
+    sequenced: SequencedNotification,
+    /// Number of plugins the notification was dispatched to.
+    dispatched: usize,
+    /// Ids of the failed plugins.
+    failing: Vec<String>,
+}
+
+/// Outcomes of notifications dispatched to plugins by their sequence numbers, collecting
+/// the ones all of them failed.
+#[derive(Debug, Default)]
+pub(crate) struct DeadLetters {
+    outcomes: BTreeMap<u64, Outcome>,
+}
+
+impl DeadLetters {
+    /// Records a dispatch of a notification to a plugin, if its failure counts towards a dead
+    /// letter: the notification is one of the batch's new ones, since a plugin's retries and
+    /// backlog are dead letters only on their first dispatch, and the plugin is a production
+    /// one out of its warmup.
+    ///
+    /// Returns `true` if the dispatch is recorded, so is its [failure](Self::failed).
+    pub(crate) fn dispatched(
+        &mut self,
+        plugin: &LoadedExExPlugin,
+        sequenced: &SequencedNotification,
+        new: &[SequencedNotification],
+        in_warmup: bool,
+    ) -> bool {
+        let is_new = new.iter().any(|(new, _)| Arc::ptr_eq(new, &sequenced.0));
+        if !is_new || plugin.shadow || in_warmup {
+            return false;
+        }
+        self.outcomes
+            .entry(sequenced.1)
+            .or_insert_with(|| Outcome {
+                sequenced: sequenced.clone(),
+                dispatched: 0,
+                failing: Vec::new(),
+            })
+            .dispatched += 1;
+        true
+    }
+
+    /// Records a failure of a dispatched notification by a plugin with a given id.
+    pub(crate) fn failed(&mut self, (_, sequence): &SequencedNotification, id: String) {
+        if let Some(outcome) = self.outcomes.get_mut(sequence) {
+            outcome.failing.push(id);
+        }
+    }
+
+    /// Records notifications failed by all plugins they were dispatched to in a given sink.
+    pub(crate) fn record(self, sink: &RecordSink<DeadLetterRecord>) {
+        for record in self.into_records() {
+            warn!(sequence = record.sequence, failing = ?record.failing, "All plugins failed notification");
+            if let Err(err) = sink.record(record) {
+                error!(%err, "failed to record dead letter");
+            }
+        }
+    }
+
+    /// Returns records of notifications failed by all plugins they were dispatched to.
+    fn into_records(self) -> impl Iterator<Item = DeadLetterRecord> {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_millis() as u64)
+            .unwrap_or_default();
+        self.outcomes
+            .into_values()
+            .filter(|outcome| outcome.failing.len() == outcome.dispatched)
+            .map(move |Outcome { sequenced: (notification, sequence), failing, .. }| {
+                DeadLetterRecord {
+                    timestamp_ms,
+                    sequence,
+                    notification: NormalizedNotification::from(notification.as_ref()),
+                    failing,
+                }
+            })
+    }
+}
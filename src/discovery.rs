@@ -0,0 +1,165 @@
+//! Discovery of ExEx plugin libraries available for loading.
+
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A plugin library found in a directory, described without registering it on manager.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscoveredPlugin {
+    /// Path of the plugin's library.
+    pub path: PathBuf,
+    /// Plugin id, if the library was opened.
+    pub id: Option<String>,
+    /// Plugin version, if the library was opened.
+    pub version: Option<String>,
+    /// Whether the plugin can be loaded to the manager.
+    pub loadable: bool,
+    /// The reason why the plugin can't be loaded.
+    pub error: Option<String>,
+}
+
+/// [Dependencies](crate::ExExPlugin::depends_on) of a plugin library, checked against
+/// the loaded plugins without registering it on manager.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyCheck {
+    /// Plugin id.
+    pub id: String,
+    /// Dependencies, which are loaded.
+    pub loaded: Vec<String>,
+    /// Dependencies, which aren't loaded.
+    pub missing: Vec<String>,
+}
+
+impl DependencyCheck {
+    /// Returns `true` if all dependencies are loaded.
+    pub fn is_satisfied(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Returns `true` if the path has the platform's dynamic library extension,
+/// i.e. `.so` on Linux or `.dylib` on MacOS.
+pub(crate) fn is_plugin_library(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+}
+
+/// ELF magic bytes.
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+/// Magic bytes of a static archive, e.g. `.a` or `.rlib`.
+const ARCHIVE_MAGIC: &[u8] = b"!<arch>\n";
+/// Magic bytes of 64-bit Mach-O, in little endian.
+const MACHO_64_MAGIC: &[u8] = &[0xcf, 0xfa, 0xed, 0xfe];
+/// Magic bytes of universal Mach-O.
+const MACHO_FAT_MAGIC: &[u8] = &[0xca, 0xfe, 0xba, 0xbe];
+/// Magic bytes of PE, i.e. a Windows DLL.
+const PE_MAGIC: &[u8] = b"MZ";
+
+/// ELF `e_type` of a shared object.
+const ELF_TYPE_DYN: u16 = 3;
+
+/// Checks that a file at the given path looks like a loadable shared library of the host,
+/// so common mistakes are reported with an actionable error, instead of a platform one
+/// of the dynamic loader.
+///
+/// Detects files which aren't shared libraries (e.g. text files or static archives),
+/// libraries built for another architecture, and files which aren't readable or
+/// (on Unix) executable.
+pub(crate) fn check_library_file(path: &Path) -> eyre::Result<()> {
+    let mut header = Vec::with_capacity(64);
+    File::open(path).and_then(|file| file.take(64).read_to_end(&mut header)).map_err(|err| {
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => {
+                eyre::format_err!("Permission denied: {} isn't readable.", path.display())
+            }
+            _ => eyre::format_err!("Failed to read exex plugin {}: {err}", path.display()),
+        }
+    })?;
+
+    let not_library = |what: &str| {
+        eyre::format_err!(
+            "Not a shared library: {} is {what}. Point to a `{}` file built with \
+             `crate-type = [\"dylib\"]`.",
+            path.display(),
+            std::env::consts::DLL_EXTENSION
+        )
+    };
+    if header.starts_with(ARCHIVE_MAGIC) {
+        return Err(not_library("a static archive"));
+    }
+
+    if header.starts_with(ELF_MAGIC) {
+        // `e_type` and `e_machine` follow the 16 bytes of identification, in the file's
+        // byte order, which is little endian on all supported targets
+        let field = |offset: usize| {
+            header.get(offset..offset + 2).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        };
+        if field(16) != Some(ELF_TYPE_DYN) {
+            return Err(not_library("an ELF file, but not a shared object"));
+        }
+        if let Some((machine, host)) = field(18).zip(elf_machine(std::env::consts::ARCH)) {
+            if machine != host {
+                eyre::bail!(
+                    "Architecture mismatch: {} is built for ELF machine {machine}, \
+                     but the node runs on `{}`.",
+                    path.display(),
+                    std::env::consts::ARCH
+                );
+            }
+        }
+    } else if header.starts_with(MACHO_64_MAGIC) {
+        let cpu = header.get(4..8).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+        if let Some((cpu, host)) = cpu.zip(macho_cpu(std::env::consts::ARCH)) {
+            if cpu != host {
+                eyre::bail!(
+                    "Architecture mismatch: {} is built for Mach-O CPU type {cpu:#x}, \
+                     but the node runs on `{}`.",
+                    path.display(),
+                    std::env::consts::ARCH
+                );
+            }
+        }
+    } else if !header.starts_with(MACHO_FAT_MAGIC) && !header.starts_with(PE_MAGIC) {
+        return Err(not_library("not a binary file"));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = std::fs::metadata(path)?.permissions().mode();
+        if mode & 0o111 == 0 {
+            eyre::bail!(
+                "Permission denied: {} isn't executable, run `chmod +x` on it.",
+                path.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// ELF `e_machine` of a given architecture.
+fn elf_machine(arch: &str) -> Option<u16> {
+    match arch {
+        "x86" => Some(3),
+        "arm" => Some(40),
+        "x86_64" => Some(62),
+        "aarch64" => Some(183),
+        "riscv64" => Some(243),
+        _ => None,
+    }
+}
+
+/// Mach-O CPU type of a given architecture.
+fn macho_cpu(arch: &str) -> Option<u32> {
+    match arch {
+        "x86_64" => Some(0x0100_0007),
+        "aarch64" => Some(0x0100_000c),
+        _ => None,
+    }
+}
@@ -0,0 +1,51 @@
+//! Divergence of shadow plugins' output from their production versions.
+
+use serde::{Deserialize, Serialize};
+
+use crate::NormalizedNotification;
+
+/// The first divergence of a [shadow](crate::ExExPluginManager::shadow_load) plugin's
+/// result from the production plugin's one, after which the shadow is quarantined,
+/// i.e. can't be [promoted](crate::ExExPluginManager::promote_shadow).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShadowDivergence {
+    /// Id of the production plugin.
+    pub id: String,
+    /// Number of the first diverging block, i.e. the tip of the notification's committed
+    /// chain, or the fork block of a revert.
+    pub block: u64,
+    /// Block ranges of the diverging notification.
+    pub notification: NormalizedNotification,
+    /// Result of the production plugin.
+    pub production: Option<serde_json::Value>,
+    /// Result of the shadow plugin.
+    pub shadow: Option<serde_json::Value>,
+}
+
+/// Returns `true` if results diverge beyond a given tolerance.
+///
+/// Numbers are compared within an absolute tolerance, all other values must be equal,
+/// recursively.
+pub(crate) fn diverges(
+    production: Option<&serde_json::Value>,
+    shadow: Option<&serde_json::Value>,
+    tolerance: f64,
+) -> bool {
+    use serde_json::Value;
+
+    match (production, shadow) {
+        (None, None) => false,
+        (Some(Value::Number(a)), Some(Value::Number(b))) => match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => (a - b).abs() > tolerance,
+            _ => a != b,
+        },
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            a.len() != b.len()
+                || a.iter().zip(b).any(|(a, b)| diverges(Some(a), Some(b), tolerance))
+        }
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            a.len() != b.len() || a.iter().any(|(key, a)| diverges(Some(a), b.get(key), tolerance))
+        }
+        (a, b) => a != b,
+    }
+}
@@ -0,0 +1,38 @@
+//! Events published by the [`ExExPluginManager`](crate::ExExPluginManager).
+
+use serde::{Deserialize, Serialize};
+
+use reth::primitives::B256;
+
+use crate::{NetworkLabel, NormalizedNotification};
+
+/// A plugin's failure to handle a notification, streamed by `exex_subscribePluginErrors` RPC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginErrorEvent {
+    /// Network of the node the plugin is loaded on.
+    pub network: NetworkLabel,
+    /// Id of the failed plugin, suffixed with `@shadow` for a
+    /// [shadow](crate::ExExPluginManager::shadow_load).
+    pub id: String,
+    /// Block ranges of the failed notification.
+    pub notification: NormalizedNotification,
+    /// Hash of the node's head after the failed notification.
+    pub head_hash: B256,
+    /// Error message.
+    pub error: String,
+}
+
+/// A notification reverting more blocks than the manager's
+/// [max reorg depth](crate::ExExPluginManager::with_max_reorg_depth), which isn't dispatched
+/// to plugins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeepReorgEvent {
+    /// Network of the node the reorg is rejected on.
+    pub network: NetworkLabel,
+    /// Block ranges of the rejected notification.
+    pub notification: NormalizedNotification,
+    /// Number of reverted blocks.
+    pub depth: u64,
+    /// Ids of plugins paused by the guard.
+    pub paused: Vec<String>,
+}
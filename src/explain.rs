@@ -0,0 +1,88 @@
+//! Explanation of which plugins a notification is dispatched to, see
+//! [`ExExPluginManager::explain_dispatch`](crate::ExExPluginManager::explain_dispatch).
+
+use reth::primitives::B256;
+use reth_tracing::tracing::{debug, trace};
+use serde::{Deserialize, Serialize};
+
+use crate::{notification::SequencedNotification, plugin::LoadedExExPlugin, BlockRange};
+
+/// Blocks of a hypothetical notification to explain, read from the node's database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DispatchBlocks {
+    /// Blocks by their numbers in a range.
+    Range(BlockRange),
+    /// Blocks by their hashes, which must be consecutive once sorted by number.
+    Hashes(Vec<B256>),
+}
+
+/// Reason a plugin doesn't receive a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterReason {
+    /// The plugin is paused, the notification is handled by its
+    /// [pause policy](crate::ExExPlugin::pause_policy).
+    Paused,
+    /// The plugin opted out of the notification's kind, either by its
+    /// [notification kinds](crate::ExExPluginManager::set_plugin_notification_kinds) or
+    /// its [subscription](crate::SubscriptionSpec::kinds).
+    KindOptOut,
+    /// The notification only commits blocks the plugin has already
+    /// [processed](crate::ExExPlugin::last_processed).
+    AlreadyProcessed,
+    /// The notification commits no transactions matching the
+    /// [transaction filter](crate::SubscriptionSpec::tx_filter) of the plugin's subscription.
+    TxFilter,
+    /// The notification's blocks are out of the plugin's
+    /// [subscribed blocks](crate::SubscriptionSpec::blocks).
+    BlockFilter,
+    /// The commit is throttled by the [minimum interval](crate::SubscriptionSpec::min_interval)
+    /// of the plugin's subscription.
+    RateLimit,
+    /// The plugin panicked on its [dedicated thread], so it's failed on every notification
+    /// without being called, before any other filter.
+    ///
+    /// [dedicated thread]: crate::ExExPluginManager::with_plugin_threads
+    Dead,
+}
+
+/// Whether a plugin receives a notification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginDispatch {
+    /// Id of the plugin, suffixed with `@shadow` for a
+    /// [shadow](crate::ExExPluginManager::shadow_load).
+    pub id: String,
+    pub receives: bool,
+    /// Reason the plugin doesn't receive the notification, `None` if it receives it.
+    pub reason: Option<FilterReason>,
+}
+
+/// Returns `true` if a notification is dispatched to a plugin, filtering it out otherwise for
+/// the same [reasons](FilterReason) explained by
+/// [`ExExPluginManager::explain_dispatch`](crate::ExExPluginManager::explain_dispatch).
+///
+/// A notification filtered out of a paused plugin is kept for a replay, if its
+/// [policy](crate::PausePolicy) allows.
+pub(crate) fn admits(plugin: &LoadedExExPlugin, sequenced: &SequencedNotification) -> bool {
+    match plugin.explain(&sequenced.0) {
+        // a dead plugin is failed on every notification without being called
+        None | Some(FilterReason::Dead) => true,
+        Some(FilterReason::Paused) => {
+            if plugin.keep_paused(sequenced) {
+                trace!(id = %plugin.id(), "Kept notification of paused plugin");
+            } else {
+                trace!(id = %plugin.id(), "Skipped notification of paused plugin");
+            }
+            false
+        }
+        Some(FilterReason::AlreadyProcessed) => {
+            debug!(id = %plugin.id(), "Skipped already processed notification");
+            false
+        }
+        Some(reason) => {
+            trace!(id = %plugin.id(), ?reason, "Skipped filtered out notification");
+            false
+        }
+    }
+}
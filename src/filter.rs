@@ -0,0 +1,61 @@
+//! Transaction content filter of plugins.
+
+use std::collections::HashSet;
+
+use reth::{
+    primitives::{Address, B256},
+    providers::Chain,
+};
+
+/// A filter of transactions a plugin is interested in.
+///
+/// Matches a transaction, if its `to` address is one of [`Self::to`], or any of its logs
+/// has one of [`Self::topics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxFilter {
+    /// Transaction recipients to match.
+    pub to: HashSet<Address>,
+    /// Log topics to match.
+    pub topics: HashSet<B256>,
+}
+
+impl TxFilter {
+    /// Adds a transaction recipient to match.
+    pub fn with_to(mut self, to: Address) -> Self {
+        self.to.insert(to);
+        self
+    }
+
+    /// Adds a log topic to match.
+    pub fn with_topic(mut self, topic: B256) -> Self {
+        self.topics.insert(topic);
+        self
+    }
+
+    /// Returns `true` if any transaction of the chain matches the filter.
+    ///
+    /// # Cost
+    ///
+    /// Unlike block range checks, iterates over all transactions of the chain's blocks and
+    /// all logs of their receipts.
+    pub fn matches_chain(&self, chain: &Chain) -> bool {
+        let matches_to = || {
+            chain.blocks_iter().any(|block| {
+                block
+                    .body
+                    .transactions
+                    .iter()
+                    .any(|tx| tx.to().is_some_and(|to| self.to.contains(&to)))
+            })
+        };
+        let matches_topics = || {
+            chain.range().any(|number| {
+                chain.execution_outcome().logs(number).is_some_and(|mut logs| {
+                    logs.any(|log| log.topics().iter().any(|topic| self.topics.contains(topic)))
+                })
+            })
+        };
+
+        (!self.to.is_empty() && matches_to()) || (!self.topics.is_empty() && matches_topics())
+    }
+}
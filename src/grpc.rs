@@ -0,0 +1,143 @@
+//! gRPC control interface of the ExEx plugin manager, see `proto/exex_plugin.proto`.
+//!
+//! A front-end alternative to [`ExExPluginRpc`](crate::ExExPluginRpc), which sends the same
+//! [`RpcRequest`]s to the manager.
+
+use std::path::PathBuf;
+
+use jsonrpsee::types::ErrorObjectOwned as RpcError;
+use tokio::sync::{mpsc, oneshot};
+use tonic::{Request, Response, Status};
+
+use reth_tracing::tracing::Level;
+
+use crate::{
+    rpc::{process_request_rx, send_request, ResponseTx},
+    sender::Sender,
+    RpcRequest,
+};
+
+/// Messages and services generated from `proto/exex_plugin.proto`.
+pub mod proto {
+    tonic::include_proto!("exex_plugin");
+}
+
+use proto::{
+    plugin_manager_server::{PluginManager, PluginManagerServer},
+    ListPluginsRequest, ListPluginsResponse, LoadPluginRequest, LoadPluginResponse,
+    ManagerStatusRequest, ManagerStatusResponse, ReloadPluginRequest, ReloadPluginResponse,
+    UnloadPluginRequest, UnloadPluginResponse,
+};
+
+/// ExEx manager gRPC service
+#[derive(Debug)]
+pub struct ExExPluginGrpc {
+    /// Request sender to ExEx plugin [manager](`crate::ExExPluginManager`).
+    pub tx: Sender<RpcRequest>,
+}
+
+impl ExExPluginGrpc {
+    pub fn new(tx: mpsc::UnboundedSender<RpcRequest>) -> Self {
+        Self { tx: Sender::new(tx) }
+    }
+
+    /// gRPC service over a bounded channel, which applies backpressure on requests
+    /// when the manager falls behind.
+    pub fn bounded(tx: mpsc::Sender<RpcRequest>) -> Self {
+        Self { tx: Sender::bounded(tx) }
+    }
+
+    /// Wrapper for [`PluginManager`] service to a [`PluginManagerServer`], e.g. to add it to
+    /// a [`tonic::transport::Server`].
+    pub fn into_service(self) -> PluginManagerServer<Self> {
+        PluginManagerServer::new(self)
+    }
+
+    /// Sends a request to the manager and awaits its response.
+    async fn request<T>(&self, req: impl FnOnce(ResponseTx<T>) -> RpcRequest) -> Result<T, Status> {
+        let (tx, rx) = oneshot::channel();
+        send_request(&self.tx, req(tx)).await.map_err(internal)?;
+        process_request_rx(rx).await.map_err(internal)
+    }
+}
+
+/// Helper to convert an RPC error of the manager into a gRPC status.
+fn internal(err: RpcError) -> Status {
+    Status::internal(err.message())
+}
+
+#[tonic::async_trait]
+impl PluginManager for ExExPluginGrpc {
+    async fn list_plugins(
+        &self,
+        _request: Request<ListPluginsRequest>,
+    ) -> Result<Response<ListPluginsResponse>, Status> {
+        let ids = self.request(|tx| RpcRequest::ListPlugins { tx }).await?;
+        Ok(Response::new(ListPluginsResponse { ids }))
+    }
+
+    async fn load_plugin(
+        &self,
+        request: Request<LoadPluginRequest>,
+    ) -> Result<Response<LoadPluginResponse>, Status> {
+        let LoadPluginRequest { plugin_path, log_level, config, expected_id } =
+            request.into_inner();
+        let log_level = log_level
+            .map(|level| level.parse::<Level>())
+            .transpose()
+            .map_err(|err| Status::invalid_argument(format!("invalid plugin log level: {err}")))?;
+        let config = config
+            .map(|config| serde_json::from_str(&config))
+            .transpose()
+            .map_err(|err| Status::invalid_argument(format!("invalid plugin config: {err}")))?;
+
+        let plugin_path = PathBuf::from(plugin_path);
+        let id = self
+            .request(|tx| RpcRequest::LoadPlugin {
+                plugin_path,
+                log_level,
+                config,
+                expected_id,
+                tx,
+            })
+            .await?;
+        Ok(Response::new(LoadPluginResponse { id }))
+    }
+
+    async fn unload_plugin(
+        &self,
+        request: Request<UnloadPluginRequest>,
+    ) -> Result<Response<UnloadPluginResponse>, Status> {
+        let UnloadPluginRequest { id } = request.into_inner();
+        self.request(|tx| RpcRequest::UnloadPlugin { id, tx }).await?;
+        Ok(Response::new(UnloadPluginResponse {}))
+    }
+
+    async fn reload_plugin(
+        &self,
+        request: Request<ReloadPluginRequest>,
+    ) -> Result<Response<ReloadPluginResponse>, Status> {
+        let ReloadPluginRequest { id, plugin_path, allow_id_change } = request.into_inner();
+        let plugin_path = plugin_path.map(PathBuf::from);
+        let id = self
+            .request(|tx| RpcRequest::ReloadPlugin { id, plugin_path, allow_id_change, tx })
+            .await?;
+        Ok(Response::new(ReloadPluginResponse { id }))
+    }
+
+    async fn manager_status(
+        &self,
+        _request: Request<ManagerStatusRequest>,
+    ) -> Result<Response<ManagerStatusResponse>, Status> {
+        let status = self.request(|tx| RpcRequest::ManagerStatus { tx }).await?;
+        Ok(Response::new(ManagerStatusResponse {
+            chain_id: status.network.chain_id,
+            network: status.network.name,
+            uptime_ms: status.uptime_ms,
+            head_number: status.head_number,
+            plugins: status.plugins as u64,
+            last_notification_ms: status.last_notification_ms,
+            last_rpc_request_ms: status.last_rpc_request_ms,
+        }))
+    }
+}
@@ -0,0 +1,135 @@
+//! Append-only JSONL records of the manager, written off the notification loop.
+
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use reth_tracing::tracing::error;
+use serde::Serialize;
+use tokio::sync::{mpsc, oneshot};
+
+/// Command of a [`JsonlWriter`]'s thread.
+#[derive(Debug)]
+enum Command {
+    /// Appends a line, including its trailing newline.
+    Line(Vec<u8>),
+    /// Answers once all previous lines are written and synced, with the first error since
+    /// the previous flush.
+    Flush(oneshot::Sender<Option<String>>),
+}
+
+/// Writer of JSON lines to a file, shared by the manager's file sinks.
+///
+/// Lines are appended by a blocking thread, which keeps the file open and syncs it after
+/// every batch of queued lines, so writes never block the notification loop. The file is
+/// created on the first line and reopened after a failed write. Failed writes are logged and
+/// reported by the next [flush](Self::flush).
+///
+/// The thread exits once all clones of the writer are dropped and the queued lines are written.
+#[derive(Debug, Clone)]
+pub(crate) struct JsonlWriter {
+    tx: mpsc::UnboundedSender<Command>,
+}
+
+impl JsonlWriter {
+    /// Spawns a writer thread of a file at a given path.
+    pub(crate) fn spawn(path: PathBuf) -> Result<Self> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        std::thread::Builder::new()
+            .name("exex-plugins-jsonl".to_owned())
+            .spawn(move || write_lines(path, rx))?;
+        Ok(Self { tx })
+    }
+
+    /// Queues a record to be appended as a line.
+    pub(crate) fn write<T: Serialize>(&self, record: &T) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.tx.send(Command::Line(line)).map_err(|_| eyre::format_err!("writer is closed"))
+    }
+
+    /// Waits until all queued lines are written and synced.
+    ///
+    /// Returns an error if any write failed since the previous flush.
+    pub(crate) async fn flush(&self) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.tx.send(Command::Flush(tx)).map_err(|_| eyre::format_err!("writer is closed"))?;
+        match rx.await? {
+            Some(err) => Err(eyre::format_err!("{err}")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Sink of the manager's records, either appended to a file or sent to a channel.
+#[derive(Debug)]
+pub(crate) enum RecordSink<T> {
+    File(JsonlWriter),
+    Channel(mpsc::UnboundedSender<T>),
+}
+
+impl<T: Serialize> RecordSink<T> {
+    /// Records a record, queueing it if the sink is a file.
+    pub(crate) fn record(&self, record: T) -> Result<()> {
+        match self {
+            Self::File(writer) => writer.write(&record),
+            Self::Channel(tx) => {
+                tx.send(record).map_err(|_| eyre::format_err!("record channel is closed"))
+            }
+        }
+    }
+
+    /// Waits until all records are written, see [`JsonlWriter::flush`].
+    pub(crate) async fn flush(&self) -> Result<()> {
+        match self {
+            Self::File(writer) => writer.flush().await,
+            Self::Channel(_) => Ok(()),
+        }
+    }
+}
+
+/// Writes queued lines until all senders are dropped.
+fn write_lines(path: PathBuf, mut rx: mpsc::UnboundedReceiver<Command>) {
+    let mut file = None;
+    let mut failed = None;
+    while let Some(command) = rx.blocking_recv() {
+        let mut lines = Vec::new();
+        let mut flushes = Vec::new();
+        let mut next = Some(command);
+        // batch everything queued, so the file is synced once per batch
+        while let Some(command) = next {
+            match command {
+                Command::Line(line) => lines.extend(line),
+                Command::Flush(tx) => flushes.push(tx),
+            }
+            next = rx.try_recv().ok();
+        }
+
+        if !lines.is_empty() {
+            if let Err(err) = append(&mut file, &path, &lines) {
+                error!(path = %path.display(), %err, "failed to write records");
+                file = None;
+                failed.get_or_insert_with(|| format!("Failed to write {}: {err}", path.display()));
+            }
+        }
+        if !flushes.is_empty() {
+            let failed = failed.take();
+            for tx in flushes {
+                let _ = tx.send(failed.clone());
+            }
+        }
+    }
+}
+
+/// Appends lines to the file and syncs it, opening it if needed.
+fn append(file: &mut Option<File>, path: &Path, lines: &[u8]) -> io::Result<()> {
+    let file = match file {
+        Some(file) => file,
+        None => file.insert(File::options().create(true).append(true).open(path)?),
+    };
+    file.write_all(lines)?;
+    file.sync_data()
+}
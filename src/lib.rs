@@ -3,10 +3,36 @@
 //!     on the dynamic libraries.
 
 mod plugin;
-pub use plugin::ExExPlugin;
+pub use plugin::{
+    plugin_span, CachingExExPlugin, ExExPlugin, ExportedStr, FullPluginStatus, HookProfile,
+    LogStream, PluginBuild, PluginDescriptor, PluginHealth, PluginHook, PluginInfo,
+    PluginLevelFilter, PluginLog, PluginLogLine, PluginMetadata, PluginProfile, PluginSigner,
+    PluginTasks, QueueFullPolicy, QueuedExExPlugin, RetryExExPlugin, RetryPolicy, ShardedSink,
+    WebhookConfig, WebhookExExPlugin, DEFAULT_PLUGIN_LOG_CAPACITY, DEFAULT_SHARD_SIZE,
+    EXEX_MANAGER_CONSTRUCTOR_FN_NAME, EXEX_PLUGIN_ABI_VERSION, EXEX_PLUGIN_DEPENDS_ON_SYMBOL,
+    EXEX_PLUGIN_DESCRIPTOR_FN_NAME, EXEX_PLUGIN_ID_SYMBOL, EXEX_PLUGIN_RUSTC_VERSION_SYMBOL,
+    EXEX_PLUGIN_TARGET_SYMBOL, WEBHOOK_EXEX_PLUGIN_ID,
+};
+#[cfg(unix)]
+pub use plugin::{SocketExExPlugin, SOCKET_EXEX_PLUGIN_ID};
 
 mod manager;
-pub use manager::{ExExPluginManager, EXEX_MANAGER_ID};
+pub use manager::{
+    ExExPluginManager, DEFAULT_BACKGROUND_LOAD_QUEUE_CAPACITY, DEFAULT_BACKGROUND_LOAD_TIMEOUT,
+    DEFAULT_HEALTH_TIMEOUT, DEFAULT_RETRY_INTERVAL, DEFAULT_RETRY_QUEUE_CAPACITY,
+    DEFAULT_RPC_FAIRNESS, EXEX_MANAGER_ID, ID_SUFFIX_SEPARATOR,
+};
+
+#[cfg(feature = "grpc")]
+mod grpc;
+#[cfg(feature = "grpc")]
+pub use grpc::{proto, ExExPluginGrpc};
+
+mod pause;
+pub use pause::PausePolicy;
+
+mod profile;
+pub use profile::{FieldCase, SerializationProfile};
 
 mod rpc;
 pub use rpc::{
@@ -15,7 +41,66 @@ pub use rpc::{
     RpcRequest, // TODO - it's only for tests
 };
 
-mod sender;
+pub mod sender;
+
+mod audit;
+pub use audit::{AuditSink, FinishedHeightRecord};
+
+mod dead_letter;
+pub use dead_letter::{DeadLetterRecord, DeadLetterSink};
+
+mod jsonl;
+
+mod explain;
+pub use explain::{DispatchBlocks, FilterReason, PluginDispatch};
+
+mod coalesce;
+pub use coalesce::{CoalesceConfig, COALESCE_CAPABILITY};
+
+mod divergence;
+pub use divergence::ShadowDivergence;
+
+mod discovery;
+pub use discovery::{DependencyCheck, DiscoveredPlugin};
+
+mod events;
+pub use events::{DeepReorgEvent, PluginErrorEvent};
+
+mod filter;
+pub use filter::TxFilter;
+
+mod manifest;
+pub use manifest::{ManifestAction, ManifestReport};
+
+mod metrics;
+pub use metrics::MetricsSnapshot;
+
+mod node;
+pub use node::{NetworkLabel, NodeInfo};
+
+mod notification;
+pub use notification::{BlockRange, ChainKind, NormalizedNotification};
+
+pub mod testing;
+
+mod sampling;
+pub use sampling::{ErrorLogSampler, DEFAULT_ERROR_LOG_INTERVAL};
+
+mod status;
+pub use status::{ManagerStatus, NotificationStats, Readiness, ServerInfo};
+
+mod subscription;
+pub use subscription::SubscriptionSpec;
+
+mod supervisor;
+pub use supervisor::RestartPolicy;
+
+mod state;
+pub use state::{ManagerState, PluginState};
 
 /// re-export for [`ExExNotification`] type
 pub use reth_exex::ExExNotification;
+
+/// re-export for [`CancellationToken`] type, passed to
+/// [`ExExPlugin::handle_notification_with_cancellation`]
+pub use tokio_util::sync::CancellationToken;
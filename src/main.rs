@@ -1,24 +1,131 @@
 //! ExEx plugin manager runner.
 
+use std::path::PathBuf;
+
+use clap::Parser;
 use tokio::sync::mpsc;
 
+use reth::{chainspec::EthereumChainSpecParser, cli::Cli};
 use reth_node_ethereum::EthereumNode;
 
-use reth_exex_plugin::{ExExPluginManager, ExExPluginRpc, ExExRpcPluginApiServer, EXEX_MANAGER_ID};
+use reth_exex_plugin::{
+    AuditSink, DeadLetterSink, ExExPluginManager, ExExPluginRpc, ExExRpcPluginApiServer,
+    RestartPolicy, DEFAULT_SHARD_SIZE, EXEX_MANAGER_ID,
+};
+
+/// ExEx plugin manager CLI arguments.
+#[derive(Debug, Clone, Default, clap::Args)]
+struct ExExPluginArgs {
+    /// File to persist the set of loaded ExEx plugins into and restore them from on startup.
+    #[arg(long = "exex-plugins.state-file", value_name = "PATH")]
+    state_file: Option<PathBuf>,
+    /// Manifest to converge loaded plugins to on `SIGHUP`, instead of reloading all of them.
+    #[cfg(all(unix, feature = "sighup"))]
+    #[arg(long = "exex-plugins.reload-manifest", value_name = "PATH")]
+    reload_manifest: Option<PathBuf>,
+    /// Refuse to load plugins built with other `rustc` version or target than the node.
+    #[arg(long = "exex-plugins.strict-build")]
+    strict_build: bool,
+    /// Advance the finished height only up to the finalized block.
+    #[arg(long = "exex-plugins.finalized-only")]
+    finalized_only: bool,
+    /// Append-only JSONL file to record every emitted finished height into.
+    #[arg(long = "exex-plugins.audit-log", value_name = "PATH")]
+    audit_log: Option<PathBuf>,
+    /// Append-only JSONL file to record notifications failed by all plugins into.
+    #[arg(long = "exex-plugins.dead-letter", value_name = "PATH")]
+    dead_letter: Option<PathBuf>,
+    /// Directory of plugins' output, sharded into files by block range.
+    #[arg(long = "exex-plugins.sharded-output", value_name = "DIR")]
+    sharded_output: Option<PathBuf>,
+    /// Number of blocks per shard of plugins' sharded output.
+    #[arg(
+        long = "exex-plugins.shard-size",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_SHARD_SIZE,
+        requires = "sharded_output"
+    )]
+    shard_size: u64,
+    /// Reject reverts and reorgs of more blocks than this, pausing plugins instead of
+    /// dispatching them.
+    #[arg(long = "exex-plugins.max-reorg-depth", value_name = "BLOCKS")]
+    max_reorg_depth: Option<u64>,
+    /// Ids of plugins which must be loaded for `exex_readiness` to report the manager ready.
+    #[arg(long = "exex-plugins.required", value_name = "ID", value_delimiter = ',')]
+    required: Vec<String>,
+    /// RPC methods allowed to be called, named without the `exex_` namespace, all if unset.
+    #[arg(long = "exex-plugins.rpc-methods", value_name = "METHOD", value_delimiter = ',')]
+    rpc_methods: Option<Vec<String>>,
+    /// Load plugins with colliding ids under suffixed ids, e.g. `MinimalExEx#2`.
+    #[arg(long = "exex-plugins.id-suffixes")]
+    id_suffixes: bool,
+    /// Run each plugin's notification handlers on its own OS thread, isolating its panics.
+    #[arg(long = "exex-plugins.plugin-threads")]
+    plugin_threads: bool,
+    /// Record timings of plugins' hooks, served by `exex_pluginProfile`.
+    #[arg(long = "exex-plugins.profiling")]
+    profiling: bool,
+    /// Address to serve the gRPC control interface of the manager on.
+    #[cfg(feature = "grpc")]
+    #[arg(long = "exex-plugins.grpc-addr", value_name = "ADDR")]
+    grpc_addr: Option<std::net::SocketAddr>,
+}
 
 fn main() -> eyre::Result<()> {
-    reth::cli::Cli::parse_args().run(|builder, _| async move {
+    Cli::<EthereumChainSpecParser, ExExPluginArgs>::parse().run(|builder, args| async move {
         // communication between manager & rpc module
         let (tx, rx) = mpsc::unbounded_channel();
 
+        #[cfg(feature = "grpc")]
+        if let Some(grpc_addr) = args.grpc_addr {
+            let service = reth_exex_plugin::ExExPluginGrpc::new(tx.clone()).into_service();
+            tokio::spawn(tonic::transport::Server::builder().add_service(service).serve(grpc_addr));
+        }
+
+        let mut rpc = ExExPluginRpc::new(tx);
+        if let Some(rpc_methods) = args.rpc_methods.clone() {
+            rpc = rpc.with_allowed_methods(rpc_methods);
+        }
+
         let handle = builder
             .node(EthereumNode::default())
             .extend_rpc_modules(move |ctx| {
-                ctx.modules.merge_configured(ExExPluginRpc::new(tx).into_rpc())?;
+                ctx.modules.merge_configured(rpc.into_rpc())?;
                 Ok(())
             })
-            .install_exex(EXEX_MANAGER_ID, |ctx| async move {
-                Ok(ExExPluginManager::new(ctx, rx).run())
+            .install_exex(EXEX_MANAGER_ID, move |ctx| async move {
+                let mut manager = ExExPluginManager::new(ctx, rx)
+                    .with_strict_build(args.strict_build)
+                    .with_finalized_only(args.finalized_only)
+                    .with_id_suffixes(args.id_suffixes)
+                    .with_profiling(args.profiling)
+                    .with_plugin_threads(args.plugin_threads)
+                    .with_required_plugins(args.required);
+                #[cfg(all(unix, feature = "sighup"))]
+                {
+                    manager = manager.with_reload_on_sighup()?;
+                    if let Some(reload_manifest) = args.reload_manifest {
+                        manager = manager.with_reload_manifest(reload_manifest);
+                    }
+                }
+                if let Some(audit_log) = args.audit_log {
+                    manager = manager.with_finished_height_audit(AuditSink::File(audit_log))?;
+                }
+                if let Some(dead_letter) = args.dead_letter {
+                    manager = manager.with_dead_letter_sink(DeadLetterSink::File(dead_letter))?;
+                }
+                if let Some(sharded_output) = args.sharded_output {
+                    manager = manager.with_sharded_output(sharded_output, args.shard_size);
+                }
+                if let Some(max_reorg_depth) = args.max_reorg_depth {
+                    manager = manager.with_max_reorg_depth(max_reorg_depth);
+                }
+                if let Some(state_file) = args.state_file {
+                    manager = manager.with_state_file(state_file);
+                    // SAFETY: the state file only contains plugins which were loaded before
+                    unsafe { manager.load_from_state_file() }.await;
+                }
+                Ok(manager.run_supervised(RestartPolicy::default()))
             })
             .launch()
             .await?;
@@ -2,27 +2,104 @@
 //!
 //! TODO - shared logger for plugins. Maybe around `RethTracer`
 
-use std::{collections::HashSet, path::Path, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
 
 use eyre::Result;
-use futures::StreamExt;
+use futures::{stream::FuturesUnordered, FutureExt, StreamExt};
+use jsonrpsee::core::RpcResult;
 use libloading::{Library, Symbol};
-use tokio::sync::mpsc;
+use tokio::{
+    sync::{broadcast, mpsc, oneshot, Semaphore},
+    task::{AbortHandle, JoinError, JoinHandle},
+    time::MissedTickBehavior,
+};
 
+use reth::{
+    args::RpcServerArgs,
+    chainspec::EthChainSpec,
+    primitives::{BlockHashOrNumber, BlockNumHash},
+    providers::{
+        BlockIdReader, BlockNumReader, BlockReader, Chain, ExecutionOutcome, ReceiptProvider,
+        TransactionVariant,
+    },
+    rpc::builder::RpcModuleSelection,
+};
 use reth_exex::{ExExContext, ExExEvent, ExExNotification};
 use reth_node_api::FullNodeComponents;
-use reth_tracing::tracing::{debug, error, info, trace};
+use reth_tracing::tracing::{debug, error, info, trace, warn, Level};
 
 use crate::{
+    coalesce::{Coalescer, DispatchBatch},
+    dead_letter::DeadLetters,
+    discovery::{check_library_file, is_plugin_library},
+    divergence::diverges,
+    explain::admits,
     format_rpc_err,
-    plugin::{LoadedExExPlugin, EXEX_MANAGER_CONSTRUCTOR_FN_NAME},
-    rpc::RpcRequest,
-    ExExPlugin,
+    jsonl::RecordSink,
+    notification::SequencedNotification,
+    pause::{has_pending_notifications, take_pending_notifications},
+    plugin::{
+        library_modified, LoadedExExPlugin, PluginDescriptor, PluginMetadata,
+        EXEX_MANAGER_CONSTRUCTOR_FN_NAME, EXEX_PLUGIN_DESCRIPTOR_FN_NAME, SHADOW_ID_SUFFIX,
+    },
+    rpc::{process_request_rx, ResponseTx, RpcRequest, RpcRequests},
+    sender::Receiver,
+    state::{ManagerState, PluginState},
+    supervisor::{panic_message, RestartPolicy},
+    AuditSink, ChainKind, CoalesceConfig, DeadLetterRecord, DeadLetterSink, DeepReorgEvent,
+    DependencyCheck, DiscoveredPlugin, DispatchBlocks, ExExPlugin, FinishedHeightRecord,
+    FullPluginStatus, ManagerStatus, ManifestAction, ManifestReport, MetricsSnapshot, NetworkLabel,
+    NodeInfo, NormalizedNotification, NotificationStats, PluginBuild, PluginDispatch,
+    PluginErrorEvent, PluginHealth, PluginInfo, PluginLog, PluginLogLine, PluginProfile,
+    PluginSigner, Readiness, ServerInfo, ShadowDivergence, ShardedSink, DEFAULT_ERROR_LOG_INTERVAL,
+    DEFAULT_PLUGIN_LOG_CAPACITY, EXEX_PLUGIN_ABI_VERSION,
 };
 
 /// Reserved ID for ExEx plugins manager.
 pub const EXEX_MANAGER_ID: &str = "ExExManager";
 
+/// Separator of a plugin's id and its instance number in a suffixed effective id,
+/// see [`ExExPluginManager::with_id_suffixes`].
+pub const ID_SUFFIX_SEPARATOR: char = '#';
+
+/// Capacity of the plugin errors channel, a lagging subscriber misses older errors.
+const PLUGIN_ERRORS_CAPACITY: usize = 1024;
+
+/// Capacity of the deep reorgs channel.
+const DEEP_REORGS_CAPACITY: usize = 16;
+
+/// Default timeout of a plugin's health check.
+pub const DEFAULT_HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default maximum number of notifications handled in a row, before pending RPC requests
+/// are served.
+pub const DEFAULT_RPC_FAIRNESS: usize = 16;
+
+/// Default maximum number of a required plugin's failed notifications pending a retry.
+pub const DEFAULT_RETRY_QUEUE_CAPACITY: usize = 64;
+
+/// Default interval of retries of required plugins' failed notifications.
+pub const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default maximum number of notifications queued for a plugin loading in background.
+pub const DEFAULT_BACKGROUND_LOAD_QUEUE_CAPACITY: usize = 1024;
+
+/// Default timeout of a plugin's background load.
+pub const DEFAULT_BACKGROUND_LOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A plugin's background load, resolving to its id and the initialized plugin.
+type BackgroundLoad = Pin<
+    Box<dyn Future<Output = (String, Result<Result<LoadedExExPlugin>, JoinError>)> + Send + Sync>,
+>;
+
 /// The `ExEx` plugins manager.
 ///
 /// Dynamically loads and unloads ExEx [plugins](`super::ExExPlugin`).
@@ -30,170 +107,2901 @@ pub struct ExExPluginManager<Node: FullNodeComponents> {
     /// This `ExEx` context
     ctx: ExExContext<Node>,
     /// Custom extended RPC [message](`RpcRequest`) receiver.
-    rpc_request_recv: mpsc::UnboundedReceiver<RpcRequest>,
+    rpc_request_recv: Receiver<RpcRequest>,
+    /// RPC requests handled or deferred during a notification dispatch, see
+    /// [`Self::handle_notification`].
+    rpc_requests: RpcRequests,
     /// A list of loaded plugins.
     plugins: HashSet<LoadedExExPlugin>,
+    /// Shadows of loaded plugins by their ids, see [`Self::shadow_load`].
+    shadows: HashMap<String, LoadedExExPlugin>,
+    /// Optional upper bound for a plugin library file size in bytes.
+    max_plugin_size: Option<u64>,
+    /// Optional file to persist the set of loaded plugins into.
+    state_file: Option<PathBuf>,
+    /// Optional directory of the manager's temporary plugin files, see [`Self::with_temp_dir`].
+    temp_dir: Option<PathBuf>,
+    /// Node's current head, updated on every notification.
+    head: BlockNumHash,
+    /// Sequence number of the next plugin load, keeps the load order of plugins.
+    next_load_seq: u64,
+    /// Timeout of a plugin's health check.
+    health_timeout: Duration,
+    /// Interval, within which repeated identical errors of a plugin are logged only once.
+    error_log_interval: Duration,
+    /// Whether plugins built with a toolchain other than the host's are refused to load.
+    strict_build: bool,
+    /// Whether `FinishedHeight` is clamped to the finalized block, see
+    /// [`Self::with_finalized_only`].
+    finalized_only: bool,
+    /// Optional sink of emitted `FinishedHeight` events, see
+    /// [`Self::with_finished_height_audit`].
+    audit: Option<RecordSink<FinishedHeightRecord>>,
+    /// Optional sink of notifications failed by all plugins, see [`Self::with_dead_letter_sink`].
+    dead_letter: Option<RecordSink<DeadLetterRecord>>,
+    /// Optional file to periodically export plugin metrics into, with the export interval.
+    metrics_export: Option<(PathBuf, Duration)>,
+    /// Optional trigger of plugins reload, see [`Self::with_reload_trigger`].
+    reload_trigger: Option<mpsc::Receiver<()>>,
+    /// Optional manifest applied on reload, see [`Self::with_reload_manifest`].
+    reload_manifest: Option<PathBuf>,
+    /// Maximum number of plugins reloaded at a time, see [`Self::with_reload_concurrency`].
+    reload_concurrency: usize,
+    /// Plugins initializing in background, see [`Self::spawn_register_plugin`].
+    loading: FuturesUnordered<BackgroundLoad>,
+    /// Pending background loads by plugin id.
+    pending_loads: HashMap<String, PendingLoad>,
+    /// Maximum number of notifications queued for a loading plugin, see
+    /// [`Self::with_background_load_limits`].
+    background_load_queue_capacity: usize,
+    /// Timeout of a background load, see [`Self::with_background_load_limits`].
+    background_load_timeout: Duration,
+    /// Publisher of plugins' failures, see [`Self::subscribe_plugin_errors`].
+    plugin_errors: broadcast::Sender<PluginErrorEvent>,
+    /// Optional coalescer of commits during sync, see [`Self::with_coalescing`].
+    coalescer: Option<Coalescer>,
+    /// Optional maximum number of blocks a dispatched notification reverts, see
+    /// [`Self::with_max_reorg_depth`].
+    max_reorg_depth: Option<u64>,
+    /// Ids of plugins required to be loaded for [readiness](Self::readiness), see
+    /// [`Self::with_required_plugins`].
+    required_plugins: Vec<String>,
+    /// Publisher of too deep reorgs, see [`Self::subscribe_deep_reorgs`].
+    deep_reorgs: broadcast::Sender<DeepReorgEvent>,
+    /// Maximum number of notifications handled in a row, see [`Self::with_rpc_fairness`].
+    rpc_fairness: usize,
+    /// Maximum number of a plugin's notifications pending a retry, see
+    /// [`Self::with_retry_queue`].
+    retry_queue_capacity: usize,
+    /// Interval of retries of failed notifications, see [`Self::with_retry_queue`].
+    retry_interval: Duration,
+    /// The node's finalized block, last observed on a notification.
+    finalized: Option<BlockNumHash>,
+    /// Sequence number of the last dispatched notification, see [`NodeInfo::sequence`].
+    sequence: u64,
+    /// The latest committed tip, which finished height is held back at.
+    held_tip: Option<BlockNumHash>,
+    /// Tolerance of numbers in shadow plugins' results, see [`Self::with_shadow_tolerance`].
+    shadow_tolerance: f64,
+    /// Whether colliding plugin ids are suffixed, see [`Self::with_id_suffixes`].
+    id_suffixes: bool,
+    /// Number of a plugin's latest log lines buffered, see [`Self::with_plugin_log_capacity`].
+    plugin_log_capacity: usize,
+    /// Whether plugins' hook timings are recorded, see [`Self::with_profiling`].
+    profiling: bool,
+    /// Whether each plugin runs on its own thread, see [`Self::with_plugin_threads`].
+    plugin_threads: bool,
+    /// Signing key handed to plugins, see [`Self::with_signing_key`].
+    signer: Option<PluginSigner>,
+    /// Directory and shard size of plugins' sharded output, see [`Self::with_sharded_output`].
+    sharded_output: Option<(PathBuf, u64)>,
+    /// Number of notifications handled since the last served RPC request.
+    notifications_in_row: usize,
+    /// Network of the node, captured on creation.
+    network: NetworkLabel,
+    /// Time the manager was created at.
+    started_at: Instant,
+    /// Time the manager last received a notification at.
+    last_notification_at: Option<Instant>,
+    /// Counters of received notifications.
+    notification_stats: NotificationStats,
+}
+
+/// A plugin load in progress.
+struct PendingLoad {
+    /// Load response sender.
+    tx: ResponseTx<String>,
+    /// Canonical path of the plugin's library, `None` for in-process plugins.
+    path: Option<PathBuf>,
+    /// Notifications received during the load, replayed to the plugin once it's initialized.
+    queued: VecDeque<SequencedNotification>,
+    /// Handle of the load's task.
+    abort: AbortHandle,
+    /// Whether the load was aborted, since too many notifications were queued.
+    overflowed: bool,
+}
+
+impl PendingLoad {
+    /// Queues a notification received during the load, aborting the load once the queue is full,
+    /// since the plugin would miss the dropped notifications.
+    fn queue(&mut self, id: &str, notification: SequencedNotification, capacity: usize) {
+        if self.overflowed {
+            return;
+        }
+        if self.queued.len() >= capacity {
+            error!(%id, capacity, "Too many notifications queued during ExEx plugin load, aborting it");
+            self.overflowed = true;
+            self.queued.clear();
+            self.abort.abort();
+            return;
+        }
+        self.queued.push_back(notification);
+    }
 }
 
 impl<Node: FullNodeComponents> ExExPluginManager<Node> {
-    pub fn new(
-        ctx: ExExContext<Node>,
-        rpc_request_recv: mpsc::UnboundedReceiver<RpcRequest>,
+    pub fn new(ctx: ExExContext<Node>, rpc_request_recv: impl Into<Receiver<RpcRequest>>) -> Self {
+        let head = BlockNumHash::new(ctx.head.number, ctx.head.hash);
+        let chain = ctx.config.chain.chain();
+        let network = NetworkLabel { chain_id: chain.id(), name: chain.to_string() };
+        Self {
+            ctx,
+            rpc_request_recv: rpc_request_recv.into(),
+            rpc_requests: RpcRequests::default(),
+            plugins: HashSet::default(),
+            shadows: HashMap::new(),
+            max_plugin_size: None,
+            state_file: None,
+            temp_dir: None,
+            head,
+            next_load_seq: 0,
+            health_timeout: DEFAULT_HEALTH_TIMEOUT,
+            error_log_interval: DEFAULT_ERROR_LOG_INTERVAL,
+            strict_build: false,
+            finalized_only: false,
+            audit: None,
+            dead_letter: None,
+            metrics_export: None,
+            reload_trigger: None,
+            reload_manifest: None,
+            reload_concurrency: 1,
+            loading: FuturesUnordered::new(),
+            pending_loads: HashMap::new(),
+            background_load_queue_capacity: DEFAULT_BACKGROUND_LOAD_QUEUE_CAPACITY,
+            background_load_timeout: DEFAULT_BACKGROUND_LOAD_TIMEOUT,
+            plugin_errors: broadcast::channel(PLUGIN_ERRORS_CAPACITY).0,
+            coalescer: None,
+            max_reorg_depth: None,
+            required_plugins: Vec::new(),
+            deep_reorgs: broadcast::channel(DEEP_REORGS_CAPACITY).0,
+            rpc_fairness: DEFAULT_RPC_FAIRNESS,
+            notifications_in_row: 0,
+            retry_queue_capacity: DEFAULT_RETRY_QUEUE_CAPACITY,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            finalized: None,
+            sequence: 0,
+            held_tip: None,
+            shadow_tolerance: 0.0,
+            id_suffixes: false,
+            signer: None,
+            sharded_output: None,
+            profiling: false,
+            plugin_threads: false,
+            plugin_log_capacity: DEFAULT_PLUGIN_LOG_CAPACITY,
+            network,
+            started_at: Instant::now(),
+            last_notification_at: None,
+            notification_stats: NotificationStats::default(),
+        }
+    }
+
+    /// Sets the maximum allowed size (in bytes) of a plugin library file.
+    ///
+    /// Checked against file metadata before the library is opened.
+    pub fn with_max_plugin_size(mut self, max_bytes: u64) -> Self {
+        self.max_plugin_size = Some(max_bytes);
+        self
+    }
+
+    /// Sets the file to persist the set of loaded plugins into on every load and unload.
+    ///
+    /// Plugins are restored from it by [`Self::load_from_state_file`].
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+
+    /// Sets the directory of temporary plugin files, e.g. libraries fetched or unpacked
+    /// before a load, which are [collected](Self::gc_temp_files) once no loaded plugin
+    /// references them.
+    ///
+    /// The directory must be dedicated to the manager, as all unreferenced files in it
+    /// are removed.
+    pub fn with_temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets the timeout of a plugin's [health check](ExExPlugin::health),
+    /// [`DEFAULT_HEALTH_TIMEOUT`] by default.
+    pub fn with_health_timeout(mut self, timeout: Duration) -> Self {
+        self.health_timeout = timeout;
+        self
+    }
+
+    /// Sets the interval, within which repeated identical notification errors of a plugin are
+    /// logged only once, [`DEFAULT_ERROR_LOG_INTERVAL`] by default.
+    ///
+    /// Errors suppressed in the interval are summarized with the next logged one.
+    pub fn with_error_log_interval(mut self, interval: Duration) -> Self {
+        self.error_log_interval = interval;
+        self
+    }
+
+    /// Sets whether plugins built with other `rustc` version or target triple than the host
+    /// are refused to load, see [`PluginBuild`].
+    ///
+    /// In lenient mode (the default), a mismatch or missing build metadata is only logged
+    /// as a warning.
+    pub fn with_strict_build(mut self, strict: bool) -> Self {
+        self.strict_build = strict;
+        self
+    }
+
+    /// Sets whether [`ExExEvent::FinishedHeight`] is advanced only up to the node's finalized
+    /// block, even if higher blocks were committed.
+    ///
+    /// So the node doesn't prune unfinalized data, which plugins might need on revert.
+    /// Until the node finalizes any block, no height is emitted.
+    pub fn with_finalized_only(mut self, finalized_only: bool) -> Self {
+        self.finalized_only = finalized_only;
+        self
+    }
+
+    /// Sets a sink, which records every emitted `FinishedHeight` event with a timestamp,
+    /// an independent audit trail of the ExEx's committed progress.
+    ///
+    /// A height is recorded before it's sent to the node. A file is written off the
+    /// notification loop, and [drained](Self::drain) with the plugins. Failed records are
+    /// logged and don't hold back the event.
+    ///
+    /// Returns an error if the writer of a file sink couldn't be spawned.
+    pub fn with_finished_height_audit(mut self, sink: AuditSink) -> Result<Self> {
+        self.audit = Some(sink.open()?);
+        Ok(self)
+    }
+
+    /// Sets a sink, which records every notification failed by all plugins it was dispatched
+    /// to, with ids of the failed plugins, so the notification can be reprocessed later.
+    ///
+    /// Only notifications newly received from the node are recorded, including ones queued for
+    /// a [retry](Self::with_retry_queue) of a required plugin. Failures during a plugin's
+    /// [warmup](ExExPlugin::warmup) and of shadows aren't counted, and a notification not
+    /// dispatched to any plugin isn't a dead letter. Failed records are logged and don't affect
+    /// notification handling. A file is written off the notification loop, and
+    /// [drained](Self::drain) with the plugins.
+    ///
+    /// Returns an error if the writer of a file sink couldn't be spawned.
+    pub fn with_dead_letter_sink(mut self, sink: DeadLetterSink) -> Result<Self> {
+        self.dead_letter = Some(sink.open()?);
+        Ok(self)
+    }
+
+    /// Enables coalescing of committed notifications for plugins declaring
+    /// [`COALESCE_CAPABILITY`], e.g. ones which only need the latest state.
+    ///
+    /// While the node is behind the chain tip, i.e. a committed tip is older than
+    /// the configured lag, consecutive commits are merged and dispatched to such plugins once
+    /// per window of blocks. Near the tip, every notification is dispatched as is. Other
+    /// plugins always receive every notification.
+    ///
+    /// # Correctness
+    ///
+    /// - Only commits are merged. Pending commits are dispatched before any revert or reorg, as
+    ///   well as before the first notification near the tip, so the order of blocks is kept.
+    /// - `FinishedHeight` is held back below the pending commits, so the node doesn't prune blocks
+    ///   coalescing plugins haven't received yet.
+    /// - Coalescing plugins receive ranges of blocks, so they must not rely on a notification per
+    ///   block, and their filters and subscriptions are evaluated on merged ranges.
+    pub fn with_coalescing(mut self, config: CoalesceConfig) -> Self {
+        self.coalescer = Some(Coalescer::new(config));
+        self
+    }
+
+    /// Sets the maximum number of blocks a notification may revert to be dispatched to plugins,
+    /// unbounded by default.
+    ///
+    /// A deeper revert or reorg is rejected: it's logged as an error and published to
+    /// [subscribers](Self::subscribe_deep_reorgs) instead, and no `FinishedHeight` is emitted
+    /// for it. Plugins, which would receive it, are paused, i.e. stop receiving any
+    /// notifications, so they don't build on top of blocks which aren't canonical anymore,
+    /// until an operator resumes them by [`Self::set_plugin_paused`].
+    pub fn with_max_reorg_depth(mut self, depth: u64) -> Self {
+        self.max_reorg_depth = Some(depth);
+        self
+    }
+
+    /// Sets a maximum number of notifications handled in a row, after which up to the same
+    /// number of pending RPC requests are served, [`DEFAULT_RPC_FAIRNESS`] by default.
+    ///
+    /// During a fast sync the node's notifications are always ready, so without the bound
+    /// RPC requests, e.g. `exex_loadPlugin`, may wait behind a long run of notifications.
+    /// The value is at least `1`.
+    pub fn with_rpc_fairness(mut self, notifications: usize) -> Self {
+        self.rpc_fairness = notifications.max(1);
+        self
+    }
+
+    /// Sets a capacity and an interval of the retry queue of each required plugin,
+    /// [`DEFAULT_RETRY_QUEUE_CAPACITY`] and [`DEFAULT_RETRY_INTERVAL`] by default.
+    ///
+    /// A notification failed by a [required](ExExPlugin::is_required) plugin holds back
+    /// the finished height, unless the plugin is [idempotent](ExExPlugin::is_idempotent),
+    /// and is queued with all following ones. Queued notifications are
+    /// retried in order on every interval, and before any new notification is dispatched to
+    /// the plugin, until the plugin succeeds, so the finished height is released without
+    /// waiting for the next notification. Notifications exceeding the capacity are dropped,
+    /// a zero capacity disables retries. The queue of a [muted](Self::set_plugin_blocking_muted)
+    /// plugin is discarded.
+    pub fn with_retry_queue(mut self, capacity: usize, interval: Duration) -> Self {
+        self.retry_queue_capacity = capacity;
+        self.retry_interval = interval;
+        self
+    }
+
+    /// Sets an absolute tolerance, within which numbers in a [shadow](Self::shadow_load)
+    /// plugin's results may differ from the production plugin's ones without a
+    /// [divergence](Self::shadow_divergence), `0` by default.
+    pub fn with_shadow_tolerance(mut self, tolerance: f64) -> Self {
+        self.shadow_tolerance = tolerance;
+        self
+    }
+
+    /// Sets whether a plugin, whose id collides with a loaded one, is registered under
+    /// a suffixed effective id, e.g. `MinimalExEx#2`, instead of failing to load.
+    ///
+    /// So several instances of the same plugin can be loaded without overriding their ids.
+    /// The effective id is used for all lookups, e.g. by RPC, while the plugin itself keeps
+    /// reporting its own id. Disabled by default.
+    pub fn with_id_suffixes(mut self, enabled: bool) -> Self {
+        self.id_suffixes = enabled;
+        self
+    }
+
+    /// Sets a number of a plugin's latest [log](ExExPlugin::on_log) lines buffered for
+    /// [`Self::plugin_logs`], [`DEFAULT_PLUGIN_LOG_CAPACITY`] by default.
+    ///
+    /// Older lines are dropped, so a chatty plugin doesn't grow the manager's memory.
+    pub fn with_plugin_log_capacity(mut self, capacity: usize) -> Self {
+        self.plugin_log_capacity = capacity;
+        self
+    }
+
+    /// Sets whether timings of plugins' hooks are recorded for [`Self::plugin_profile`],
+    /// i.e. of [`ExExPlugin::on_load`], [`ExExPlugin::handle_notification`],
+    /// [`ExExPlugin::on_unload`] and [`ExExPlugin::health`].
+    ///
+    /// Applies to plugins loaded afterwards. Disabled by default, so hooks aren't timed
+    /// unless performance is being tuned.
+    pub fn with_profiling(mut self, enabled: bool) -> Self {
+        self.profiling = enabled;
+        self
+    }
+
+    /// Sets whether each plugin loaded afterwards runs its notification handlers on its own
+    /// dedicated OS thread, named `exex-plugin-{id}`, for maximum isolation.
+    ///
+    /// The manager forwards a notification to the plugin's thread over a single-consumer
+    /// channel and awaits the thread's ack of the handled notification, so plugins are
+    /// still dispatched in order. A panic of the handler is caught on the thread and the
+    /// plugin is marked [dead](crate::PluginInfo::dead): it's failed on every following
+    /// notification without being called, while other plugins continue. Disabled by default.
+    ///
+    /// # Constraints
+    ///
+    /// The handler's future must be `Send`, as required by [`ExExPlugin`] already, and is
+    /// driven to completion by the manager's runtime handle on the plugin's thread, so it may
+    /// use timers and IO of the runtime, but is never moved to another thread. The forwarded
+    /// job owns the plugin, its library and the notification, so the plugin's thread never
+    /// borrows from the manager's task. Other hooks, e.g. [`ExExPlugin::on_load`] or
+    /// [`ExExPlugin::health`], are still called on the manager's task, and fail if the plugin
+    /// requires `&mut self` while its handler still runs. The plugin's
+    /// [worker pool](ExExPlugin::worker_threads), if any, is installed on the plugin's thread.
+    pub fn with_plugin_threads(mut self, enabled: bool) -> Self {
+        self.plugin_threads = enabled;
+        self
+    }
+
+    /// Sets an ed25519 secret key of the node, which plugins sign their outputs with via
+    /// a [`PluginSigner`] passed by [`ExExPlugin::on_signer`] hook.
+    ///
+    /// The public key is exposed by [`Self::server_info`], so consumers can verify signed
+    /// outputs.
+    pub fn with_signing_key(mut self, secret: [u8; 32]) -> Self {
+        self.signer = Some(PluginSigner::new(secret));
+        self
+    }
+
+    /// Sets a directory of plugins' output, sharded into files by block range, each shard
+    /// with a given number of blocks, e.g. [`DEFAULT_SHARD_SIZE`](crate::DEFAULT_SHARD_SIZE).
+    ///
+    /// Each plugin gets a [`ShardedSink`] into its own subdirectory, named by its id, passed by
+    /// [`ExExPlugin::on_sink`] hook. Records of reverted blocks are truncated before
+    /// a revert or a reorg is dispatched to the plugin.
+    pub fn with_sharded_output(mut self, dir: impl Into<PathBuf>, shard_size: u64) -> Self {
+        self.sharded_output = Some((dir.into(), shard_size));
+        self
+    }
+
+    /// Sets ids of plugins which must be loaded for the manager to be [ready](Self::readiness),
+    /// in addition to loaded [required](ExExPlugin::is_required) plugins.
+    pub fn with_required_plugins(
+        mut self,
+        ids: impl IntoIterator<Item = impl Into<String>>,
     ) -> Self {
-        Self { ctx, rpc_request_recv, plugins: HashSet::default() }
+        self.required_plugins = ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the file to periodically export a [`MetricsSnapshot`] of all plugins into,
+    /// every given `interval`.
+    ///
+    /// Failed exports are logged and don't affect notification handling.
+    pub fn with_metrics_export(mut self, path: impl Into<PathBuf>, interval: Duration) -> Self {
+        self.metrics_export = Some((path.into(), interval));
+        self
+    }
+
+    /// Sets a maximum number of notifications queued for a plugin
+    /// [loading in background](Self::spawn_register_plugin) and a timeout of the load,
+    /// [`DEFAULT_BACKGROUND_LOAD_QUEUE_CAPACITY`] and [`DEFAULT_BACKGROUND_LOAD_TIMEOUT`]
+    /// by default.
+    ///
+    /// A load exceeding either of them is aborted and answered with an error.
+    pub fn with_background_load_limits(mut self, capacity: usize, timeout: Duration) -> Self {
+        self.background_load_queue_capacity = capacity;
+        self.background_load_timeout = timeout;
+        self
+    }
+
+    /// Sets a trigger of plugins reload, e.g. by an operator's signal.
+    ///
+    /// On each message, the running manager applies the [reload
+    /// manifest](Self::with_reload_manifest), if one is set, or otherwise
+    /// [reloads all](Self::reload_all) library backed plugins. Failures are logged.
+    pub fn with_reload_trigger(mut self, trigger: mpsc::Receiver<()>) -> Self {
+        self.reload_trigger = Some(trigger);
+        self
+    }
+
+    /// Sets maximum number of plugins [reloaded](Self::reload_all) at a time, `1` by default.
+    ///
+    /// Each reload keeps both old and new libraries of the plugin loaded until it's done,
+    /// so the limit bounds a memory spike of reloading many plugins.
+    pub fn with_reload_concurrency(mut self, limit: usize) -> Self {
+        self.reload_concurrency = limit.max(1);
+        self
+    }
+
+    /// Sets the manifest applied on reload, see [`Self::apply_manifest`].
+    pub fn with_reload_manifest(mut self, path: impl Into<PathBuf>) -> Self {
+        self.reload_manifest = Some(path.into());
+        self
+    }
+
+    /// Triggers plugins reload on `SIGHUP` signal, see [`Self::with_reload_trigger`].
+    ///
+    /// Signals received during a reload are coalesced into a single following reload.
+    #[cfg(all(unix, feature = "sighup"))]
+    pub fn with_reload_on_sighup(self) -> Result<Self> {
+        let mut sighup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        let (tx, rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            while sighup.recv().await.is_some() {
+                info!("Received SIGHUP, reloading ExEx plugins");
+                if let Err(mpsc::error::TrySendError::Closed(_)) = tx.try_send(()) {
+                    break;
+                }
+            }
+        });
+
+        Ok(self.with_reload_trigger(rx))
     }
 
     /// Start a manager
+    ///
+    /// Once the node's notifications stream ends on its graceful shutdown, plugins are
+    /// [drained](Self::drain) and the manager returns.
     pub async fn run(mut self) -> Result<()> {
+        self.run_loop().await
+    }
+
+    /// Runs the manager, restarting it on an error or a panic according to a given
+    /// [`RestartPolicy`].
+    ///
+    /// On restart the state left by the terminated run is rebuilt: plugin loads in progress are
+    /// aborted and their requests are answered with an error, shadows are unloaded, and library
+    /// backed plugins are reloaded from the [state file](Self::with_state_file), if one is set,
+    /// so they start from fresh instances. In-process plugins can't be constructed again, so
+    /// they're kept. Once restarts are exhausted, the last error is returned.
+    ///
+    /// # Context
+    ///
+    /// The node hands an ExEx its context once, and its notifications stream can't be
+    /// subscribed to again, so the manager keeps its context, its RPC requests receiver and its
+    /// configuration across restarts. For the same reason, the node's head and the notifications
+    /// which are kept for a retry or a replay are kept too, since the node doesn't re-deliver
+    /// them until it restarts itself.
+    pub async fn run_supervised(mut self, policy: RestartPolicy) -> Result<()> {
+        let mut restarts = 0;
+        loop {
+            let err = match AssertUnwindSafe(self.run_loop()).catch_unwind().await {
+                Ok(res) => match res {
+                    Ok(()) => return Ok(()),
+                    Err(err) => err,
+                },
+                Err(panic) => eyre::format_err!("manager panicked: {}", panic_message(&*panic)),
+            };
+
+            let Some(backoff) = policy.backoff(restarts) else {
+                error!(%err, restarts, "ExEx plugin manager terminated, restarts are exhausted");
+                return Err(err);
+            };
+            restarts += 1;
+            warn!(%err, restarts, ?backoff, "ExEx plugin manager terminated, restarting");
+            tokio::time::sleep(backoff).await;
+            self.restart().await;
+        }
+    }
+
+    /// Rebuilds the manager's state left by its terminated run, see [`Self::run_supervised`].
+    async fn restart(&mut self) {
+        self.loading = FuturesUnordered::new();
+        for (id, pending) in self.pending_loads.drain() {
+            pending.abort.abort();
+            let err = format_rpc_err!("exex plugin manager restarted during load of {id}");
+            let _ = pending.tx.send(Err(err));
+        }
+        for (_, shadow) in self.shadows.drain() {
+            discard_plugin(shadow);
+        }
+        self.notifications_in_row = 0;
+
+        if self.state_file.is_some() {
+            let ids: Vec<_> = self
+                .plugins
+                .iter()
+                .filter(|plugin| plugin.path.is_some())
+                .map(|plugin| plugin.id().to_owned())
+                .collect();
+            for id in ids {
+                if let Err(err) = self.remove_plugin(&id) {
+                    error!(%id, %err, "failed to unload exex plugin on restart");
+                }
+            }
+            // SAFETY: the state file only contains plugins which were loaded before
+            unsafe { self.load_from_state_file() }.await;
+        }
+    }
+
+    async fn run_loop(&mut self) -> Result<()> {
+        let mut metrics_interval =
+            self.metrics_export.as_ref().map(|(_, interval)| tokio::time::interval(*interval));
+        let mut retry_interval = tokio::time::interval(self.retry_interval);
+        retry_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         loop {
+            // handle RPC requests deferred during the last dispatch, in arrival order
+            while let Some(req) = self.rpc_requests.next_deferred() {
+                self.handle_rpc_request(req).await;
+                self.rpc_requests.handled();
+            }
+
             tokio::select! {
                 // handle `ExExNotification` on list of loaded plugins
-                Some(notification_result) = self.ctx.notifications.next() => {
+                notification_result = self.ctx.notifications.next() => {
                     match notification_result {
-                        Ok(notification) => self.handle_notification(notification).await?,
-                        Err(err) => error!(err=%err, "on receive context exex notification"),
+                        Some(Ok(notification)) => {
+                            self.handle_notification(notification).await?;
+                            self.notifications_in_row += 1;
+                            if self.notifications_in_row >= self.rpc_fairness {
+                                self.serve_pending_rpc_requests().await;
+                            }
+                        }
+                        Some(Err(err)) => error!(err=%err, "on receive context exex notification"),
+                        // the node is shutting down
+                        None => {
+                            info!("ExEx notifications stream ended, draining ExEx plugins");
+                            return self.drain().await
+                        }
                     }
                 }
                 // handle RPC request to operate with plugins or load them
                 Some(req) = self.rpc_request_recv.recv() => {
-                    self.handle_rpc_request(req).await
+                    self.handle_rpc_request(req).await;
+                    self.rpc_requests.handled();
+                    self.notifications_in_row = 0;
+                },
+                // finish a background plugin load
+                Some((id, res)) = self.loading.next(), if !self.loading.is_empty() => {
+                    self.finish_load(id, res).await
+                },
+                // reload plugins on trigger
+                Some(()) = async { self.reload_trigger.as_mut().unwrap().recv().await }, if self.reload_trigger.is_some() => {
+                    self.reload().await
+                },
+                // retry failed notifications of required plugins
+                _ = retry_interval.tick(), if self.has_pending_retries() => {
+                    self.retry_pending_notifications().await?
+                },
+                // export plugin metrics snapshot
+                _ = async { metrics_interval.as_mut().unwrap().tick().await }, if metrics_interval.is_some() => {
+                    self.export_metrics()
                 },
             }
         }
     }
 
-    async fn handle_notification(&mut self, notification: ExExNotification) -> Result<()> {
-        for plugin in self.plugins.iter() {
-            if let Err(err) = plugin.handle_notification(&notification).await {
-                error!(id = %plugin.id(), %err, "failed to process notification")
+    /// Serves up to [`Self::with_rpc_fairness`] RPC requests already pending, so they aren't
+    /// starved by a run of notifications.
+    async fn serve_pending_rpc_requests(&mut self) {
+        self.notifications_in_row = 0;
+        for _ in 0..self.rpc_fairness {
+            let Some(req) = self.rpc_request_recv.try_recv() else { break };
+            self.handle_rpc_request(req).await;
+            self.rpc_requests.handled();
+        }
+    }
+
+    /// Reloads plugins on [trigger](Self::with_reload_trigger).
+    async fn reload(&mut self) {
+        match self.reload_manifest.clone() {
+            // SAFETY: the manifest is provided by the node operator
+            Some(manifest) => match unsafe { self.apply_manifest(&manifest) }.await {
+                Ok(reports) => debug!(?reports, "Reloaded ExEx plugins from manifest"),
+                Err(err) => error!(path=?manifest, %err, "failed to reload ExEx plugins"),
+            },
+            // SAFETY: plugins are reloaded from the libraries they were loaded from
+            None => {
+                unsafe { self.reload_all() }.await;
             }
-            info!(id = %plugin.id(), "Handled notification");
+        }
+    }
+
+    /// Handle [`ExExNotification`] on all loaded plugins and emit [`ExExEvent::FinishedHeight`]
+    /// for a committed chain.
+    ///
+    /// # Re-entrancy
+    ///
+    /// A plugin may call back into the manager over RPC while it's being dispatched.
+    /// Read-only requests, e.g. `exex_listPlugins`, are answered right away, while all other
+    /// ones, e.g. `exex_loadPlugin`, are deferred and handled by [`Self::run`] in arrival order
+    /// once the dispatch completes, so the set of plugins never changes mid-dispatch.
+    /// A plugin must not await responses of deferred requests in its handler.
+    ///
+    /// Handlers of [blocking](ExExPlugin::is_blocking) plugins, ones with a
+    /// [worker pool](ExExPlugin::worker_threads) or on their own
+    /// [threads](Self::with_plugin_threads) are awaited off the manager's task, so read-only
+    /// requests are answered while they run too. An async handler, which blocks its thread
+    /// without declaring it, stalls them.
+    pub async fn handle_notification(&mut self, notification: ExExNotification) -> Result<()> {
+        self.last_notification_at = Some(Instant::now());
+        // recorded before the dispatch, so the node's notifications are observable without
+        // any plugin loaded
+        self.notification_stats.record(&notification);
+        if let Some(committed) = notification.committed_chain() {
+            self.head = committed.tip().num_hash();
+        } else if let Some(reverted) = notification.reverted_chain() {
+            self.head = reverted.fork_block();
+        }
+        if self.reject_deep_reorg(&notification) {
+            return Ok(());
+        }
+        self.sequence += 1;
+        let node_info = self.node_info();
+        let notification = Arc::new(notification);
+        for (id, pending) in &mut self.pending_loads {
+            let sequenced = (notification.clone(), self.sequence);
+            pending.queue(id, sequenced, self.background_load_queue_capacity);
+        }
+
+        let sequenced = (notification.clone(), self.sequence);
+        let coalesce = self.plugins.iter().any(|plugin| plugin.coalesces());
+        let batch = DispatchBatch::new(sequenced, self.coalescer.as_mut(), coalesce);
+
+        let hold_finished_height = self.dispatch(&batch, node_info).await;
+        self.notify_finalized();
+        let tip = notification.committed_chain().map(|chain| chain.tip().num_hash());
+
+        if hold_finished_height {
+            warn!("Required plugin failed to process notification, holding back finished height");
+            self.held_tip = tip.or(self.held_tip);
+            return Ok(());
         }
 
-        if let Some(tip) = notification.committed_chain().map(|chain| chain.tip().num_hash_slow()) {
-            self.ctx.events.send(ExExEvent::FinishedHeight(tip))?;
-            info!(?tip, "Handled notification");
+        self.held_tip = None;
+        if let Some(tip) = tip {
+            self.emit_finished_height(tip)?;
         }
 
         Ok(())
     }
 
-    #[allow(unused_must_use)] // for oneshot send error
-    async fn handle_rpc_request(&mut self, req: RpcRequest) {
-        match req {
-            RpcRequest::ListPlugins { tx } => {
-                let res = Ok(self.plugins());
-                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
-            }
-            RpcRequest::LoadPlugin { plugin_path, tx } => {
-                let res = unsafe { self.load_plugin(plugin_path) }
-                    .await
-                    .map_err(|err| format_rpc_err!("failed to load exex plugin: {err:?}"));
-                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+    /// Dispatches a batch of notifications to all loaded plugins and their shadows, preceded by
+    /// notifications pending a [retry](Self::with_retry_queue) of each plugin.
+    ///
+    /// Each plugin's notifications are awaited one by one in chain order, upholding
+    /// the [ordering](ExExPlugin::handle_notification#ordering) guarantee.
+    ///
+    /// Returns `true` if the finished height must be held back, i.e. a required plugin,
+    /// which isn't idempotent, failed.
+    async fn dispatch(&mut self, batch: &DispatchBatch, node_info: NodeInfo) -> bool {
+        let mut hold_finished_height = false;
+        let canonical_head = self.canonical_head();
+        // each plugin is followed by its shadow, if any
+        let dispatched = self
+            .plugins
+            .iter()
+            .flat_map(|plugin| std::iter::once(plugin).chain(self.shadows.get(plugin.id())))
+            .collect::<Vec<_>>();
+        // results of the last production plugin, compared against its shadow's ones
+        let mut production_results = Vec::new();
+        let mut dead_letters = DeadLetters::default();
+        for plugin in dispatched {
+            if !plugin.shadow {
+                production_results.clear();
             }
-            RpcRequest::UnloadPlugin { id, tx } => {
-                let res = self
-                    .unload_plugin(&id)
-                    .map_err(|err| format_rpc_err!("failed to unload exex plugin: {err:?}"));
-                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            let notifications = batch.notifications(plugin);
+            let mut pending =
+                take_pending_notifications(plugin).chain(notifications.iter().cloned());
+            while let Some(sequenced) = pending.next() {
+                let (notification, sequence) = &sequenced;
+                if !admits(plugin, &sequenced) {
+                    continue;
+                }
+                let node_info = NodeInfo { sequence: *sequence, ..node_info };
+
+                let in_warmup = plugin.in_warmup();
+                let dead_letter =
+                    dead_letters.dispatched(plugin, &sequenced, notifications, in_warmup);
+                let dispatch = plugin.handle_notification(notification, &node_info);
+                tokio::pin!(dispatch);
+                let res = loop {
+                    tokio::select! {
+                        biased;
+                        res = &mut dispatch => break res,
+                        // the plugin may call back into the manager while being dispatched
+                        Some(req) = self.rpc_request_recv.recv() => {
+                            let unhandled = self.try_handle_read_request(req);
+                            self.rpc_requests.received_during_dispatch(plugin.id(), unhandled);
+                        }
+                    }
+                };
+                if let (Ok(_), Some(head)) = (&res, canonical_head) {
+                    plugin.notify_tip(notification, head);
+                }
+                match res {
+                    Ok(result) if plugin.shadow => {
+                        debug!(id = %plugin.display_id(), "Handled notification");
+                        let production = production_results
+                            .iter()
+                            .find(|(handled, _)| Arc::ptr_eq(handled, notification))
+                            .map(|(_, result)| result);
+                        if let Some(production) = production {
+                            self.compare_shadow_result(plugin, notification, production, result);
+                        }
+                    }
+                    Ok(result) => {
+                        info!(id = %plugin.id(), "Handled notification");
+                        production_results.push((notification.clone(), result));
+                    }
+                    Err(err) if in_warmup => {
+                        debug!(id = %plugin.id(), %err, "failed to process notification during warmup")
+                    }
+                    Err(err) => {
+                        if dead_letter {
+                            dead_letters.failed(&sequenced, plugin.id().to_owned());
+                        }
+                        plugin.record_failure(&err);
+                        plugin.log_failure(&err, self.error_log_interval);
+                        self.publish_plugin_error(plugin, notification, &err);
+                        if !plugin.shadow && plugin.blocks_finished_height() {
+                            // the failed and all following notifications are retried in order,
+                            // while the finished height advances past idempotent plugins
+                            hold_finished_height |= !plugin.is_idempotent();
+                            let retries = std::iter::once(sequenced.clone()).chain(pending);
+                            plugin.queue_retries(retries, self.retry_queue_capacity);
+                            break;
+                        }
+                    }
+                }
             }
         }
-    }
 
-    /// Returns a list of all plugin's ids.
-    pub fn plugins(&self) -> Vec<String> {
-        self.plugins.iter().map(|plugin| plugin.id().to_owned()).collect()
+        if let Some(sink) = &self.dead_letter {
+            dead_letters.record(sink);
+        }
+
+        hold_finished_height
     }
 
-    /// Load the ExEx [plugin](`super::ExExPlugin`) from a given path.
-    ///
-    /// Returns: Loaded exex plugin's id.
-    ///
-    /// # Safety
-    ///
-    /// The  [plugin](`super::ExExPlugin`) implementing library **must** contain a function with
-    /// name [`EXEX_MANAGER_CONSTRUCTOR_FN_NAME`]. Otherwise, behavior is undefined.
-    /// See also [`libloading::Library::get`] for more information on what
-    /// restrictions apply to [`EXEX_MANAGER_CONSTRUCTOR_FN_NAME`].
-    pub async unsafe fn load_plugin<P: AsRef<Path>>(&mut self, plugin_path: P) -> Result<String> {
-        type ExExPluginCreate = unsafe fn() -> *mut dyn ExExPlugin;
+    /// Returns `true` if any plugin has notifications pending a [retry](Self::with_retry_queue),
+    /// or ones kept during a pause pending a replay after the plugin is resumed.
+    fn has_pending_retries(&self) -> bool {
+        self.plugins.iter().any(has_pending_notifications)
+    }
 
-        let lib = Library::new(plugin_path.as_ref())
-            .map_err(|err| eyre::format_err!("Failed to find & load exex plugin: {err:?}"))?;
-        let constructor: Symbol<'_, ExExPluginCreate> =
-            lib.get(EXEX_MANAGER_CONSTRUCTOR_FN_NAME).map_err(|_| {
-                eyre::format_err!(
-                    "The `__create_exex_plugin` symbol wasn't found on exex plugin library."
-                )
-            })?;
+    /// Retries notifications pending a [retry](Self::with_retry_queue) and replays backlogs of
+    /// plugins, emitting the held back finished height once all of them succeed.
+    async fn retry_pending_notifications(&mut self) -> Result<()> {
+        let node_info = self.node_info();
+        if self.dispatch(&DispatchBatch::default(), node_info).await {
+            return Ok(());
+        }
 
-        let raw_plugin_ptr = constructor();
-        let mut plugin: Box<dyn ExExPlugin> = Box::from_raw(raw_plugin_ptr);
-        let id = plugin.id();
+        match self.held_tip.take() {
+            Some(tip) => {
+                info!("Retried notifications succeeded, releasing finished height");
+                self.emit_finished_height(tip)
+            }
+            None => Ok(()),
+        }
+    }
 
-        self.validate_plugin(id)?;
+    /// Emits [`ExExEvent::FinishedHeight`] for a given committed tip.
+    fn emit_finished_height(&mut self, tip: BlockNumHash) -> Result<()> {
+        // coalescing plugins haven't received pending blocks yet, and some plugins process
+        // notifications after their dispatch
+        let tip = self
+            .coalescer
+            .as_ref()
+            .and_then(Coalescer::pending_fork_block)
+            .into_iter()
+            .chain(self.plugins.iter().filter_map(|plugin| plugin.processed_height()))
+            .fold(tip, |tip, held| if held.number < tip.number { held } else { tip });
+        let Some(height) = self.finished_height(tip) else { return Ok(()) };
+        if let Some(audit) = &self.audit {
+            if let Err(err) = audit.record(FinishedHeightRecord::new(height)) {
+                error!(?height, %err, "failed to record finished height");
+            }
+        }
+        self.ctx.events.send(ExExEvent::FinishedHeight(height))?;
+        info!(?tip, ?height, "Handled notification");
 
-        trace!(id=%id, action="on_load", "calling");
-        plugin.on_load().await?;
+        Ok(())
+    }
 
-        self.plugins.insert(LoadedExExPlugin { plugin, lib: Arc::new(lib) });
+    /// Publishes a plugin's failure to handle a notification to
+    /// [subscribers](Self::subscribe_plugin_errors).
+    fn publish_plugin_error(
+        &self,
+        plugin: &LoadedExExPlugin,
+        notification: &ExExNotification,
+        err: &eyre::Report,
+    ) {
+        // no subscribers is not an error
+        let _ = self.plugin_errors.send(PluginErrorEvent {
+            network: self.network.clone(),
+            id: plugin.display_id(),
+            notification: NormalizedNotification::from(notification),
+            head_hash: self.head.hash,
+            error: err.to_string(),
+        });
+    }
 
-        debug!(id=%id, action="load", "ExEx plugin was loaded succesfully");
+    /// Compares a shadow plugin's result of a notification against the production plugin's one,
+    /// recording the first [divergence](Self::shadow_divergence) beyond the
+    /// [tolerance](Self::with_shadow_tolerance).
+    fn compare_shadow_result(
+        &self,
+        shadow: &LoadedExExPlugin,
+        notification: &ExExNotification,
+        production: &Option<serde_json::Value>,
+        result: Option<serde_json::Value>,
+    ) {
+        let mut divergence = shadow.divergence.lock().unwrap();
+        if divergence.is_some()
+            || !diverges(production.as_ref(), result.as_ref(), self.shadow_tolerance)
+        {
+            return;
+        }
 
-        Ok(id.to_owned())
+        let block = match (notification.committed_chain(), notification.reverted_chain()) {
+            (Some(committed), _) => committed.tip().number,
+            (None, Some(reverted)) => reverted.fork_block().number,
+            (None, None) => return,
+        };
+        warn!(id = %shadow.display_id(), block, "Shadow plugin diverged from production, quarantined");
+        *divergence = Some(ShadowDivergence {
+            id: shadow.id().to_owned(),
+            block,
+            notification: NormalizedNotification::from(notification),
+            production: production.clone(),
+            shadow: result,
+        });
     }
 
-    /// Unload the ExEx [plugin](`super::ExExPlugin`) by the given plugin id, if one exists on
-    /// manager.
-    pub fn unload_plugin(&mut self, id: &str) -> Result<()> {
-        debug!(id=%id, action="ExExPluginManager::unload_plugin", "unloading an ExEx plugin");
+    /// Checks a notification against the [max reorg depth](Self::with_max_reorg_depth).
+    ///
+    /// Returns `true` if the notification reverts too many blocks, after pausing affected
+    /// plugins and publishing the event.
+    fn reject_deep_reorg(&self, notification: &ExExNotification) -> bool {
+        let Some(max_depth) = self.max_reorg_depth else { return false };
+        let Some(reverted) = notification.reverted_chain() else { return false };
+        let depth = reverted.len() as u64;
+        if depth <= max_depth {
+            return false;
+        }
 
-        if let Some(mut plugin) = self.plugins.take(id) {
-            trace!(id=%id, action="ExExPlugin::on_unload", "calling");
-            plugin.on_unload()?;
+        let kind = ChainKind::from(notification);
+        let mut paused = Vec::new();
+        for plugin in self.plugins.iter().filter(|plugin| plugin.receives(kind)) {
+            plugin.pause();
+            paused.push(plugin.id().to_owned());
+        }
+        paused.sort();
+        error!(
+            depth,
+            max_depth,
+            range = ?reverted.range(),
+            ?paused,
+            "Rejected too deep reorg, not dispatching it to ExEx plugins"
+        );
+        let event = DeepReorgEvent {
+            network: self.network.clone(),
+            notification: NormalizedNotification::from(notification),
+            depth,
+            paused,
+        };
+        // no subscribers is not an error
+        let _ = self.deep_reorgs.send(event);
 
-            if Arc::strong_count(&plugin.lib) == 1 {
-                trace!(id=%id, action="ExExPlugin::on_unload", "closing library");
+        true
+    }
 
-                // Drop goes in declaration order of fields
-                // So, we can assume that plugin's box drops first.
-                // We don't need to call close method manually, just drop it.
-                drop(plugin);
+    /// Returns the node's canonical head as tracked by its provider, which notifications of
+    /// a backfill or a replay fall behind.
+    fn canonical_head(&self) -> Option<BlockNumHash> {
+        match self.ctx.provider().chain_info() {
+            Ok(info) => Some(BlockNumHash::new(info.best_number, info.best_hash)),
+            Err(err) => {
+                warn!(%err, "failed to read canonical head, skipping tip hooks");
+                None
             }
         }
-
-        debug!(id=%id, action="unload", "ExEx plugin was unloaded succesfully");
-
-        Ok(())
     }
 
-    /// Unload all ExEx [plugins](`super::ExExPlugin`) exists on manager.
-    pub fn unload_all(&mut self) {
-        info!("Start unload all ExEx plugins");
+    /// Fires plugins' [`ExExPlugin::on_finalized`] hook, if the node's finalized block advanced
+    /// since it was last observed.
+    fn notify_finalized(&mut self) {
+        let finalized = match self.ctx.provider().finalized_block_num_hash() {
+            Ok(Some(finalized)) => finalized,
+            Ok(None) => return,
+            Err(err) => {
+                warn!(%err, "failed to read finalized block");
+                return;
+            }
+        };
+        if self.finalized.is_some_and(|last| last.number >= finalized.number) {
+            return;
+        }
+        self.finalized = Some(finalized);
 
-        let unload_res: Result<()> =
-            self.plugins().iter().try_for_each(|name| self.unload_plugin(name));
-        if let Err(err) = unload_res {
-            error!(err=%err, "Error on unload plugins")
+        for plugin in self
+            .plugins
+            .iter()
+            .flat_map(|plugin| std::iter::once(plugin).chain(self.shadows.get(plugin.id())))
+        {
+            plugin.notify_finalized(finalized);
         }
     }
 
-    /// Validates [plugin](`super::ExExPlugin`) to being:
-    ///
-    /// - not presented on manager (TODO: ability to replace it)
-    /// - [id](`super::ExExPlugin::id`) is not equal to [`EXEX_MANAGER_ID`]
-    #[inline]
-    fn validate_plugin(&self, id: &'static str) -> Result<()> {
-        if self.plugins.contains(id) {
-            eyre::bail!("Plugin with id: `{id:?}` is already presented on manager.");
+    /// Returns the height to emit as finished for a given committed tip, clamped to the
+    /// finalized block in [finalized only](Self::with_finalized_only) mode.
+    fn finished_height(&self, tip: BlockNumHash) -> Option<BlockNumHash> {
+        if !self.finalized_only {
+            return Some(tip);
         }
 
-        if id == EXEX_MANAGER_ID {
-            eyre::bail!(
-                "`{EXEX_MANAGER_ID}` is reserved id for manager. Choose another id for plugin."
-            );
+        match self.ctx.provider().finalized_block_num_hash() {
+            Ok(Some(finalized)) if finalized.number < tip.number => Some(finalized),
+            Ok(Some(_)) => Some(tip),
+            Ok(None) => {
+                debug!(?tip, "No finalized block yet, holding back finished height");
+                None
+            }
+            Err(err) => {
+                warn!(?tip, %err, "failed to read finalized block, holding back finished height");
+                None
+            }
         }
+    }
 
-        Ok(())
+    /// Handles a read-only RPC request, which doesn't need exclusive access to the manager.
+    ///
+    /// Returns the request back, if it isn't a read-only one.
+    #[allow(unused_must_use)] // for oneshot send error
+    fn try_handle_read_request(&self, req: RpcRequest) -> Option<RpcRequest> {
+        match req {
+            RpcRequest::ListPlugins { tx } => {
+                let res = Ok(self.plugins());
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PluginCount { tx } => {
+                let res = Ok(self.len());
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::GetPluginInfo { id, tx } => {
+                let res = self
+                    .plugin_info(&id)
+                    .map_err(|err| format_rpc_err!("failed to get exex plugin info: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::Ping { tx } => {
+                tx.send(Ok(())).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PluginLatestResult { id, tx } => {
+                let res = self
+                    .plugin_latest_result(&id)
+                    .map_err(|err| format_rpc_err!("failed to get exex plugin result: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ExplainDispatch { blocks, kind, tx } => {
+                let res = self
+                    .explain_dispatch(&blocks, kind)
+                    .map_err(|err| format_rpc_err!("failed to explain dispatch: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PluginProfile { id, tx } => {
+                let res = self
+                    .plugin_profile(&id)
+                    .map_err(|err| format_rpc_err!("failed to get exex plugin profile: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PluginLogs { id, tail, tx } => {
+                let res = self
+                    .plugin_logs(&id, tail)
+                    .map_err(|err| format_rpc_err!("failed to get exex plugin logs: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::SubscribePluginLogs { id, tx } => {
+                let res = self.subscribe_plugin_logs(&id).map_err(|err| {
+                    format_rpc_err!("failed to subscribe to exex plugin logs: {err:?}")
+                });
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ShadowDivergence { id, tx } => {
+                let res = self.shadow_divergence(&id).map_err(|err| {
+                    format_rpc_err!("failed to get exex plugin shadow divergence: {err:?}")
+                });
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::FindPluginsByCapability { capability, tx } => {
+                let res = Ok(self.find_plugins_by_capability(&capability));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ServerInfo { tx } => {
+                tx.send(Ok(self.server_info()))
+                    .inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ManagerStatus { tx } => {
+                tx.send(Ok(self.status()))
+                    .inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PluginInflight { id, tx } => {
+                let res = self.plugin_inflight(&id).map_err(|err| {
+                    format_rpc_err!("failed to get exex plugin handlers in flight: {err:?}")
+                });
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::Snapshot { tx } => {
+                tx.send(Ok(self.snapshot()))
+                    .inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::SubscribePluginErrors { tx } => {
+                tx.send(Ok(self.subscribe_plugin_errors()))
+                    .inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PluginConfig { id, tx } => {
+                let res = self
+                    .plugin_config(&id)
+                    .map_err(|err| format_rpc_err!("failed to get exex plugin config: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            req => return Some(req),
+        }
+        None
+    }
+
+    #[allow(unused_must_use)] // for oneshot send error
+    async fn handle_rpc_request(&mut self, req: RpcRequest) {
+        let Some(req) = self.try_handle_read_request(req) else { return };
+
+        match req {
+            RpcRequest::LoadPlugin { plugin_path, log_level, config, expected_id, tx } => {
+                let opened = unsafe { self.open_plugin(&plugin_path, log_level) }
+                    .and_then(|loaded| check_expected_id(loaded, expected_id.as_deref()));
+                match opened {
+                    Ok(mut loaded) => {
+                        loaded.config = config;
+                        self.spawn_add_plugin(loaded, tx)
+                    }
+                    Err(err) => {
+                        let res = Err(format_rpc_err!("failed to load exex plugin: {err:?}"));
+                        tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+                    }
+                }
+            }
+            RpcRequest::LoadAndSubscribe { plugin_path, log_level, config, tx } => {
+                // subscribed before the load, so failures of notifications replayed to
+                // the plugin on load aren't missed
+                let errors = self.subscribe_plugin_errors();
+                let (load_tx, load_rx) = oneshot::channel();
+                match unsafe { self.open_plugin(&plugin_path, log_level) } {
+                    Ok(mut loaded) => {
+                        loaded.config = config;
+                        self.spawn_add_plugin(loaded, load_tx)
+                    }
+                    Err(err) => {
+                        let res = Err(format_rpc_err!("failed to load exex plugin: {err:?}"));
+                        load_tx.send(res);
+                    }
+                }
+                tokio::spawn(async move {
+                    let res = process_request_rx(load_rx).await.map(|id| (id, errors));
+                    tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+                });
+            }
+            RpcRequest::UnloadPlugin { id, tx } => {
+                let res = self
+                    .unload_plugin(&id)
+                    .map_err(|err| format_rpc_err!("failed to unload exex plugin: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::SetPluginPaused { id, paused, tx } => {
+                let res = self
+                    .set_plugin_paused(&id, paused)
+                    .map_err(|err| format_rpc_err!("failed to pause exex plugin: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::SetPluginBlockingMuted { id, muted, tx } => {
+                let res = self
+                    .set_plugin_blocking_muted(&id, muted)
+                    .map_err(|err| format_rpc_err!("failed to mute exex plugin: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ReloadPlugin { id, plugin_path, allow_id_change, tx } => {
+                let res = unsafe { self.reload_plugin(&id, plugin_path, allow_id_change) }
+                    .await
+                    .map_err(|err| format_rpc_err!("failed to reload exex plugin: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PluginCommand { id, command, params, tx } => {
+                let res = self
+                    .plugin_command(&id, command, params)
+                    .await
+                    .map_err(|err| format_rpc_err!("failed to run exex plugin command: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::DiscoverPlugins { dir, tx } => {
+                let res = unsafe { self.discover_plugins(dir) }
+                    .map_err(|err| format_rpc_err!("failed to discover exex plugins: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::SetPluginNotificationKinds { id, kinds, tx } => {
+                let res = self.set_plugin_notification_kinds(&id, &kinds).map_err(|err| {
+                    format_rpc_err!("failed to set exex plugin notification kinds: {err:?}")
+                });
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PluginHealthDetailed { id, tx } => {
+                let res = self
+                    .plugin_health(&id)
+                    .await
+                    .map_err(|err| format_rpc_err!("failed to check exex plugin health: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ApplyManifest { path, tx } => {
+                let res = unsafe { self.apply_manifest(&path) }.await.map_err(|err| {
+                    format_rpc_err!("failed to apply exex plugins manifest: {err:?}")
+                });
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::Restore { snapshot, tx } => {
+                let res = unsafe { self.restore(snapshot) }.await.map_err(|err| {
+                    format_rpc_err!("failed to restore exex plugins snapshot: {err:?}")
+                });
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::Readiness { tx } => {
+                tx.send(Ok(self.readiness().await))
+                    .inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PluginsFull { tx } => {
+                tx.send(Ok(self.plugins_full().await))
+                    .inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::CheckDependencies { plugin_path, tx } => {
+                let res = unsafe { self.check_dependencies(plugin_path) }.map_err(|err| {
+                    format_rpc_err!("failed to check exex plugin dependencies: {err:?}")
+                });
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ShadowLoad { id, new_path, tx } => {
+                let res = unsafe { self.shadow_load(&id, new_path) }
+                    .await
+                    .map_err(|err| format_rpc_err!("failed to shadow load exex plugin: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::PromoteShadow { id, tx } => {
+                let res = self.promote_shadow(&id).map_err(|err| {
+                    format_rpc_err!("failed to promote exex plugin shadow: {err:?}")
+                });
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ListPlugins { .. }
+            | RpcRequest::PluginCount { .. }
+            | RpcRequest::GetPluginInfo { .. }
+            | RpcRequest::Ping { .. }
+            | RpcRequest::PluginLatestResult { .. }
+            | RpcRequest::ShadowDivergence { .. }
+            | RpcRequest::FindPluginsByCapability { .. }
+            | RpcRequest::PluginConfig { .. }
+            | RpcRequest::ServerInfo { .. }
+            | RpcRequest::ManagerStatus { .. }
+            | RpcRequest::SubscribePluginErrors { .. }
+            | RpcRequest::PluginProfile { .. }
+            | RpcRequest::ExplainDispatch { .. }
+            | RpcRequest::PluginLogs { .. }
+            | RpcRequest::SubscribePluginLogs { .. }
+            | RpcRequest::Snapshot { .. }
+            | RpcRequest::PluginInflight { .. } => {
+                unreachable!("read-only requests are handled above")
+            }
+            RpcRequest::GcTempFiles { tx } => {
+                let res = self.gc_temp_files().map_err(|err| {
+                    format_rpc_err!("failed to collect exex plugin temp files: {err:?}")
+                });
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ResetPluginMetrics { id, tx } => {
+                let res = self
+                    .reset_plugin_metrics(&id)
+                    .map_err(|err| format_rpc_err!("failed to reset exex plugin metrics: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+            RpcRequest::ReconfigurePlugin { id, config, tx } => {
+                let res = self
+                    .reconfigure_plugin(&id, config)
+                    .map_err(|err| format_rpc_err!("failed to reconfigure exex plugin: {err:?}"));
+                tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+            }
+        }
+    }
+
+    /// Returns the node's network and its current head.
+    pub fn node_info(&self) -> NodeInfo {
+        NodeInfo {
+            chain_id: self.ctx.config.chain.chain().id(),
+            head_number: self.head.number,
+            head_hash: self.head.hash,
+            sequence: self.sequence,
+        }
+    }
+
+    /// Returns a label of the node's network, captured on the manager's creation.
+    pub fn network(&self) -> &NetworkLabel {
+        &self.network
+    }
+
+    /// Returns the manager's network, version and the plugin ABI version it loads.
+    pub fn server_info(&self) -> ServerInfo {
+        ServerInfo {
+            network: self.network.clone(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            abi_version: EXEX_PLUGIN_ABI_VERSION,
+            public_key: self.signer.as_ref().map(PluginSigner::public_key),
+        }
+    }
+
+    /// Returns the manager's uptime and last activity.
+    pub fn status(&self) -> ManagerStatus {
+        let elapsed_ms = |at: Instant| at.elapsed().as_millis() as u64;
+        ManagerStatus {
+            network: self.network.clone(),
+            uptime_ms: elapsed_ms(self.started_at),
+            head_number: self.head.number,
+            plugins: self.plugins.len(),
+            last_notification_ms: self.last_notification_at.map(elapsed_ms),
+            last_rpc_request_ms: self.rpc_requests.last_handled_at().map(elapsed_ms),
+            notifications: self.notification_stats,
+            sequence: self.sequence,
+        }
+    }
+
+    /// Returns a number of notification handlers of a plugin by the given id in flight.
+    ///
+    /// A count persistently above zero signals a hung handler.
+    pub fn plugin_inflight(&self, id: &str) -> Result<usize> {
+        Ok(self.plugin(id)?.inflight.load(Ordering::Relaxed))
+    }
+
+    /// Returns up to `tail` latest lines of a plugin's [log](ExExPlugin::on_log) by the given id,
+    /// all buffered ones if `None`, in the order they were written.
+    pub fn plugin_logs(&self, id: &str, tail: Option<usize>) -> Result<Vec<PluginLogLine>> {
+        Ok(self.plugin(id)?.log.tail(tail))
+    }
+
+    /// Returns percentiles of hook timings of a plugin by the given id.
+    ///
+    /// Fails if the plugin isn't [profiled](Self::with_profiling).
+    pub fn plugin_profile(&self, id: &str) -> Result<PluginProfile> {
+        match &self.plugin(id)?.profiler {
+            Some(profiler) => Ok(profiler.profile()),
+            None => eyre::bail!("Plugin with id: `{id:?}` isn't profiled."),
+        }
+    }
+
+    /// Explains which loaded plugins and shadows would receive a hypothetical notification of
+    /// a given kind of given blocks, and why the other ones wouldn't, in dispatch order.
+    ///
+    /// Runs the dispatch's filters without dispatching. The blocks and their receipts are read
+    /// from the node's database, so transaction filters are matched against them. A reorg
+    /// reverts and commits the same blocks. [Coalescing](Self::with_coalescing) isn't explained.
+    pub fn explain_dispatch(
+        &self,
+        blocks: &DispatchBlocks,
+        kind: ChainKind,
+    ) -> Result<Vec<PluginDispatch>> {
+        let chain = Arc::new(self.read_chain(blocks)?);
+        let notification = match kind {
+            ChainKind::Commit => ExExNotification::ChainCommitted { new: chain },
+            ChainKind::Revert => ExExNotification::ChainReverted { old: chain },
+            ChainKind::Reorg => ExExNotification::ChainReorged { old: chain.clone(), new: chain },
+        };
+
+        let dispatched = self
+            .plugins
+            .iter()
+            .flat_map(|plugin| std::iter::once(plugin).chain(self.shadows.get(plugin.id())));
+        Ok(dispatched
+            .map(|plugin| {
+                let reason = plugin.explain(&notification);
+                PluginDispatch { id: plugin.display_id(), receives: reason.is_none(), reason }
+            })
+            .collect())
+    }
+
+    /// Reads given blocks with their receipts from the node's database as a chain.
+    fn read_chain(&self, blocks: &DispatchBlocks) -> Result<Chain> {
+        let ids: Box<dyn Iterator<Item = BlockHashOrNumber>> = match blocks {
+            DispatchBlocks::Range(range) => {
+                eyre::ensure!(
+                    range.from <= range.to,
+                    "invalid block range: {}..={}",
+                    range.from,
+                    range.to
+                );
+                Box::new((range.from..=range.to).map(Into::into))
+            }
+            DispatchBlocks::Hashes(hashes) => Box::new(hashes.iter().copied().map(Into::into)),
+        };
+        let provider = self.ctx.provider();
+        let mut blocks = ids
+            .map(|id| {
+                let block = provider
+                    .sealed_block_with_senders(id, TransactionVariant::WithHash)?
+                    .ok_or_else(|| {
+                    eyre::eyre!("Block {id} isn't found in the node's database.")
+                })?;
+                let receipts = provider.receipts_by_block(id)?.unwrap_or_default();
+                Ok((block, receipts.into_iter().map(Some).collect::<Vec<_>>()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        blocks.sort_by_key(|(block, _)| block.number);
+        let Some(first_block) = blocks.first().map(|(block, _)| block.number) else {
+            eyre::bail!("No blocks given.");
+        };
+        eyre::ensure!(
+            blocks.windows(2).all(|pair| pair[1].0.number == pair[0].0.number + 1),
+            "Blocks aren't consecutive."
+        );
+
+        let (blocks, receipts): (Vec<_>, Vec<_>) = blocks.into_iter().unzip();
+        let execution_outcome =
+            ExecutionOutcome { receipts: receipts.into(), first_block, ..Default::default() };
+        Ok(Chain::new(blocks, execution_outcome, None))
+    }
+
+    /// Subscribes to new lines of a plugin's [log](ExExPlugin::on_log) by the given id.
+    ///
+    /// A subscriber lagging by more than the [log capacity](Self::with_plugin_log_capacity)
+    /// misses the older lines.
+    pub fn subscribe_plugin_logs(&self, id: &str) -> Result<broadcast::Receiver<PluginLogLine>> {
+        Ok(self.plugin(id)?.log.subscribe())
+    }
+
+    /// Subscribes to failures of plugins to handle notifications, excluding ones during
+    /// the plugins' [warmup](ExExPlugin::warmup).
+    ///
+    /// A subscriber lagging by more than 1024 errors misses the older ones.
+    pub fn subscribe_plugin_errors(&self) -> broadcast::Receiver<PluginErrorEvent> {
+        self.plugin_errors.subscribe()
+    }
+
+    /// Subscribes to notifications rejected as [too deep reorgs](Self::with_max_reorg_depth).
+    pub fn subscribe_deep_reorgs(&self) -> broadcast::Receiver<DeepReorgEvent> {
+        self.deep_reorgs.subscribe()
+    }
+
+    /// Returns a list of all plugin's ids.
+    pub fn plugins(&self) -> Vec<String> {
+        self.plugins.iter().map(|plugin| plugin.id().to_owned()).collect()
+    }
+
+    /// Calls a given function with every loaded plugin, in their load order, for read-only
+    /// inspection, e.g. of their versions and capabilities.
+    ///
+    /// Shadows aren't visited.
+    pub fn for_each_plugin(&self, mut f: impl FnMut(&dyn ExExPlugin)) {
+        let mut plugins = self.plugins.iter().collect::<Vec<_>>();
+        plugins.sort_by_key(|plugin| plugin.load_seq);
+        for plugin in plugins {
+            f(plugin.plugin.as_ref());
+        }
+    }
+
+    /// Returns ids of all plugins declaring a given [capability](ExExPlugin::capabilities),
+    /// in their load order.
+    pub fn find_plugins_by_capability(&self, capability: &str) -> Vec<String> {
+        let mut plugins = self
+            .plugins
+            .iter()
+            .filter(|plugin| plugin.capabilities().contains(&capability))
+            .collect::<Vec<_>>();
+        plugins.sort_by_key(|plugin| plugin.load_seq);
+        plugins.into_iter().map(|plugin| plugin.id().to_owned()).collect()
+    }
+
+    /// Returns information about the plugin by the given id.
+    pub fn plugin_info(&self, id: &str) -> Result<PluginInfo> {
+        self.plugin(id).map(PluginInfo::from)
+    }
+
+    /// Reads and zeroes metrics of the plugin by the given id, i.e. its counters of handled
+    /// and failed notifications, returning its information before the reset.
+    ///
+    /// So a dashboard computes rates over its polling intervals.
+    pub fn reset_plugin_metrics(&self, id: &str) -> Result<PluginInfo> {
+        self.plugin(id).map(LoadedExExPlugin::reset_metrics)
+    }
+
+    /// Returns information about all loaded plugins.
+    pub fn plugins_info(&self) -> Vec<PluginInfo> {
+        self.plugins.iter().map(PluginInfo::from).collect()
+    }
+
+    /// Returns a [`FullPluginStatus`] of every loaded plugin, ordered by load.
+    ///
+    /// Health checks of all plugins run concurrently, each bounded by the
+    /// [timeout](Self::with_health_timeout).
+    pub async fn plugins_full(&self) -> Vec<FullPluginStatus> {
+        let mut plugins = self.plugins.iter().collect::<Vec<_>>();
+        plugins.sort_by_key(|plugin| plugin.load_seq);
+        futures::future::join_all(plugins.into_iter().map(|plugin| async move {
+            let health = plugin.health(self.health_timeout).await;
+            FullPluginStatus {
+                info: PluginInfo::from(plugin),
+                inflight: plugin.inflight.load(Ordering::Relaxed),
+                last_error: plugin.last_error.lock().unwrap().clone(),
+                last_notification: *plugin.last_notification.lock().unwrap(),
+                latest_result: plugin.latest_result.lock().unwrap().clone(),
+                health,
+            }
+        }))
+        .await
+    }
+
+    /// Returns [`Readiness`] of the manager, which is ready only if all of its required plugins
+    /// are loaded, not paused, not failing and pass their health checks.
+    ///
+    /// Required plugins are [configured](Self::with_required_plugins) ones and loaded plugins
+    /// declared as [required](ExExPlugin::is_required). A plugin is failing while its last
+    /// notification has failed, i.e. while it holds back the finished height. Health checks
+    /// run concurrently, each bounded by the [timeout](Self::with_health_timeout).
+    pub async fn readiness(&self) -> Readiness {
+        let mut reasons = Vec::new();
+        for id in &self.required_plugins {
+            if self.pending_loads.contains_key(id) {
+                reasons.push(format!("{id} is loading"));
+            } else if !self.plugins.contains(id.as_str()) {
+                reasons.push(format!("{id} is not loaded"));
+            }
+        }
+
+        let mut required = self
+            .plugins
+            .iter()
+            .filter(|plugin| {
+                plugin.is_required() || self.required_plugins.iter().any(|id| id == plugin.id())
+            })
+            .collect::<Vec<_>>();
+        required.sort_by_key(|plugin| plugin.load_seq);
+        for plugin in &required {
+            if plugin.paused() {
+                reasons.push(format!("{} is paused", plugin.id()));
+            }
+            if plugin.failing.load(Ordering::Relaxed) {
+                let err = plugin.last_error.lock().unwrap().clone().unwrap_or_default();
+                reasons.push(format!("{} failed its last notification: {err}", plugin.id()));
+            }
+        }
+        let health = futures::future::join_all(
+            required.iter().map(|plugin| plugin.health(self.health_timeout)),
+        )
+        .await;
+        for (plugin, health) in required.iter().zip(health) {
+            if let Some(err) = health.error {
+                reasons.push(format!("{} is unhealthy: {err}", plugin.id()));
+            }
+        }
+
+        Readiness { ready: reasons.is_empty(), reasons }
+    }
+
+    /// Returns a number of failed notifications of the plugin by the given id,
+    /// excluding failures during the plugin's [warmup](ExExPlugin::warmup).
+    pub fn plugin_failures(&self, id: &str) -> Option<u64> {
+        self.plugins.get(id).map(|plugin| plugin.failures.load(Ordering::Relaxed))
+    }
+
+    /// Mutes (or unmutes) the plugin by the given id from holding back the finished height
+    /// on its failures. The plugin still handles notifications, unlike a paused one.
+    pub fn set_plugin_blocking_muted(&self, id: &str, muted: bool) -> Result<()> {
+        self.plugin(id)?.muted.store(muted, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Pauses (or resumes) the plugin by the given id, e.g. by `exex_pausePlugin` RPC.
+    ///
+    /// A paused plugin receives no notifications, which are handled by its
+    /// [pause policy](ExExPlugin::pause_policy) meanwhile, while its notification kinds are
+    /// kept, so it receives the same kinds once resumed.
+    pub fn set_plugin_paused(&self, id: &str, paused: bool) -> Result<()> {
+        let plugin = self.plugin(id)?;
+        if paused {
+            plugin.pause();
+        } else {
+            plugin.resume();
+        }
+        debug!(id=%id, paused, "Set ExEx plugin paused");
+        Ok(())
+    }
+
+    /// Returns the latest [result](ExExPlugin::handle_notification_with_result) of the plugin
+    /// by the given id, `None` if the plugin hasn't returned any result yet.
+    pub fn plugin_latest_result(&self, id: &str) -> Result<Option<serde_json::Value>> {
+        Ok(self.plugin(id)?.latest_result.lock().unwrap().clone())
+    }
+
+    /// Returns the config the plugin by the given id is loaded with, after its
+    /// [redaction](ExExPlugin::redact_config), `None` if the plugin is loaded without a config.
+    pub fn plugin_config(&self, id: &str) -> Result<Option<serde_json::Value>> {
+        let plugin = self.plugin(id)?;
+        Ok(plugin.config.clone().map(|config| plugin.redact_config(config)))
+    }
+
+    /// Runs a [health check](ExExPlugin::health) of the plugin by the given id.
+    ///
+    /// A check exceeding the [timeout](Self::with_health_timeout) is reported as unhealthy
+    /// with a `"timeout"` error.
+    pub async fn plugin_health(&self, id: &str) -> Result<PluginHealth> {
+        Ok(self.plugin(id)?.health(self.health_timeout).await)
+    }
+
+    /// Tells all loaded plugins to [flush](ExExPlugin::flush) their buffered output and
+    /// awaits completion, e.g. before the node exits.
+    ///
+    /// All plugins are flushed concurrently, even if some of them fail. Once flushed,
+    /// the plugins' [cancellation tokens](ExExPlugin::handle_notification_with_cancellation)
+    /// are cancelled. Records of the [audit](Self::with_finished_height_audit) and
+    /// [dead letters](Self::with_dead_letter_sink) are written and synced as well, their
+    /// failures are logged.
+    ///
+    /// Returns an error if any plugin failed to flush.
+    pub async fn drain(&mut self) -> Result<()> {
+        let results = futures::future::join_all(
+            self.plugins.iter().map(|plugin| async move { (plugin.id(), plugin.flush().await) }),
+        )
+        .await;
+        for plugin in self.plugins.iter().chain(self.shadows.values()) {
+            plugin.cancel.cancel();
+        }
+        if let Some(audit) = &self.audit {
+            if let Err(err) = audit.flush().await {
+                error!(%err, "failed to flush finished height audit");
+            }
+        }
+        if let Some(dead_letter) = &self.dead_letter {
+            if let Err(err) = dead_letter.flush().await {
+                error!(%err, "failed to flush dead letters");
+            }
+        }
+
+        let failed = results
+            .into_iter()
+            .filter_map(|(id, res)| {
+                let err = res.err()?;
+                error!(%id, %err, "failed to flush exex plugin");
+                Some(id)
+            })
+            .collect::<Vec<_>>();
+        if !failed.is_empty() {
+            eyre::bail!("Failed to flush exex plugins: {failed:?}");
+        }
+
+        debug!(plugins=%self.plugins.len(), "Drained ExEx plugins");
+
+        Ok(())
+    }
+
+    /// Invokes a [command](ExExPlugin::command) of the plugin by the given id.
+    pub async fn plugin_command(
+        &self,
+        id: &str,
+        command: String,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.plugin(id)?.command(command, params).await
+    }
+
+    /// [Reconfigures](ExExPlugin::reconfigure) the running plugin by the given id with
+    /// a given config, keeping its in-memory state.
+    ///
+    /// On success, the config replaces the one the plugin is loaded with, so it's persisted in
+    /// the [state file](Self::with_state_file) and passed again on reload. A panic of
+    /// the reconfiguration is returned as an error, keeping the plugin loaded with its previous
+    /// config.
+    pub fn reconfigure_plugin(&mut self, id: &str, config: serde_json::Value) -> Result<()> {
+        let plugin = self.plugins.get(id).ok_or_else(|| {
+            eyre::format_err!("Plugin with id: `{id:?}` is not presented on manager.")
+        })?;
+        plugin.reconfigure(config.clone())?;
+        if let Some(mut plugin) = self.plugins.take(id) {
+            plugin.config = Some(config);
+            self.plugins.insert(plugin);
+        }
+
+        debug!(id=%id, "Reconfigured ExEx plugin");
+        self.persist_state();
+        Ok(())
+    }
+
+    /// Sets [`ChainKind`]s of notifications the plugin by the given id receives.
+    ///
+    /// All kinds are received by default. Notifications of other kinds are skipped, even if
+    /// the plugin has no kinds, unlike ones received while it's [paused](Self::set_plugin_paused).
+    pub fn set_plugin_notification_kinds(&self, id: &str, kinds: &[ChainKind]) -> Result<()> {
+        self.plugin(id)?.set_notification_kinds(kinds);
+        Ok(())
+    }
+
+    /// Returns a number of loaded plugins.
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    /// Returns `true` if there are no loaded plugins.
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Load the ExEx [plugin](`super::ExExPlugin`) from a given path.
+    ///
+    /// Optional `log_level` is a preferred tracing level of the plugin's handlers span.
+    /// See [`crate::plugin_span`] for details and limitations.
+    ///
+    /// Returns: Loaded exex plugin's id.
+    ///
+    /// # Safety
+    ///
+    /// The  [plugin](`super::ExExPlugin`) implementing library **must** contain a function with
+    /// name [`EXEX_PLUGIN_DESCRIPTOR_FN_NAME`] returning a [`PluginDescriptor`], or the legacy
+    /// one with name [`EXEX_MANAGER_CONSTRUCTOR_FN_NAME`]. Otherwise, behavior is undefined.
+    /// See also [`libloading::Library::get`] for more information on what
+    /// restrictions apply to [`EXEX_MANAGER_CONSTRUCTOR_FN_NAME`].
+    pub async unsafe fn load_plugin<P: AsRef<Path>>(
+        &mut self,
+        plugin_path: P,
+        log_level: Option<Level>,
+    ) -> Result<String> {
+        let plugin = self.open_plugin(plugin_path.as_ref(), log_level)?;
+        self.add_plugin(plugin).await
+    }
+
+    /// Load the ExEx [plugin](`super::ExExPlugin`) like [`Self::load_plugin`], passing it
+    /// a given [config](ExExPlugin::on_config) before initialization.
+    ///
+    /// The config is persisted in the [state file](Self::with_state_file) and passed again to
+    /// the reloaded plugin.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    pub async unsafe fn load_plugin_with_config<P: AsRef<Path>>(
+        &mut self,
+        plugin_path: P,
+        log_level: Option<Level>,
+        config: serde_json::Value,
+    ) -> Result<String> {
+        let mut plugin = self.open_plugin(plugin_path.as_ref(), log_level)?;
+        plugin.config = Some(config);
+        self.add_plugin(plugin).await
+    }
+
+    /// Load the ExEx [plugin](`super::ExExPlugin`) like [`Self::load_plugin`], if it declares
+    /// a given expected id, e.g. to make sure a deployment pipeline loads the intended plugin.
+    ///
+    /// On mismatch the load is refused and the library is closed, without initializing
+    /// the plugin.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    pub async unsafe fn load_plugin_with_expected_id<P: AsRef<Path>>(
+        &mut self,
+        plugin_path: P,
+        log_level: Option<Level>,
+        expected_id: &str,
+    ) -> Result<String> {
+        let plugin = self.open_plugin(plugin_path.as_ref(), log_level)?;
+        let plugin = check_expected_id(plugin, Some(expected_id))?;
+        self.add_plugin(plugin).await
+    }
+
+    /// Reload the ExEx [plugin](`super::ExExPlugin`) by the given id from a given path,
+    /// or from the path it was loaded from.
+    ///
+    /// The new library is opened first and its plugin's id is compared to the reloaded one.
+    /// On mismatch the reload is refused, unless `allow_id_change` is set.
+    /// The old plugin is unloaded only once the new one is initialized, so it's kept
+    /// on any failure.
+    ///
+    /// Returns: Reloaded exex plugin's id.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    ///
+    /// Note that some platforms resolve a library at an already opened path to the opened one,
+    /// so a rebuilt library should rather be placed at a new path.
+    pub async unsafe fn reload_plugin(
+        &mut self,
+        id: &str,
+        plugin_path: Option<PathBuf>,
+        allow_id_change: bool,
+    ) -> Result<String> {
+        let mut plugin = self.open_reloaded_plugin(id, plugin_path, allow_id_change)?;
+
+        trace!(id=%plugin.id(), action="on_load", "calling");
+        plugin.load().await?;
+
+        let new_id = self.replace_plugin(id, plugin)?;
+        self.persist_state();
+
+        Ok(new_id)
+    }
+
+    /// [Reloads](Self::reload_plugin) all library backed plugins from the paths they were
+    /// loaded from, starting in their load order.
+    ///
+    /// At most [`Self::with_reload_concurrency`] plugins are reloaded at a time, so only as
+    /// many pairs of old and new libraries are loaded simultaneously.
+    /// Plugins failed to reload are logged and kept as they are.
+    ///
+    /// Returns: Reloaded exex plugin's ids.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    pub async unsafe fn reload_all(&mut self) -> Vec<String> {
+        let mut plugins =
+            self.plugins.iter().filter(|plugin| plugin.path.is_some()).collect::<Vec<_>>();
+        plugins.sort_by_key(|plugin| plugin.load_seq);
+        let mut ids =
+            plugins.into_iter().map(|plugin| plugin.id().to_owned()).collect::<VecDeque<_>>();
+
+        let permits = Arc::new(Semaphore::new(self.reload_concurrency));
+        let mut reloading = FuturesUnordered::new();
+        let mut reloaded = Vec::with_capacity(ids.len());
+        loop {
+            // start reloads while there are free permits
+            while !ids.is_empty() {
+                let Ok(permit) = permits.clone().try_acquire_owned() else { break };
+                let id = ids.pop_front().expect("not empty");
+                match self.open_reloaded_plugin(&id, None, false) {
+                    Ok(mut plugin) => {
+                        debug!(%id, in_flight=reloading.len() + 1, "reloading exex plugin");
+                        reloading.push(async move {
+                            let res = plugin.load().await.map(|_| plugin);
+                            (id, res, permit)
+                        });
+                    }
+                    Err(err) => error!(%id, %err, "failed to reload exex plugin"),
+                }
+            }
+
+            // the old library is released before the permit
+            let Some((id, res, _permit)) = reloading.next().await else { break };
+            match res.and_then(|plugin| self.replace_plugin(&id, plugin)) {
+                Ok(id) => reloaded.push(id),
+                Err(err) => error!(%id, %err, "failed to reload exex plugin"),
+            }
+        }
+        self.persist_state();
+
+        info!(plugins=?reloaded, "Reloaded ExEx plugins");
+
+        reloaded
+    }
+
+    /// Opens a new instance of the plugin by a given id from a given path or the one
+    /// it was loaded from, for a [reload](Self::reload_plugin).
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    unsafe fn open_reloaded_plugin(
+        &self,
+        id: &str,
+        plugin_path: Option<PathBuf>,
+        allow_id_change: bool,
+    ) -> Result<LoadedExExPlugin> {
+        let old = self.plugin(id)?;
+        let log_level = old.log_level;
+        let plugin_path = match plugin_path.or_else(|| old.path.clone()) {
+            Some(plugin_path) => plugin_path,
+            None => eyre::bail!("Plugin with id: `{id:?}` isn't backed by a library."),
+        };
+
+        let mut plugin = self.open_plugin(&plugin_path, log_level)?;
+        plugin.config = old.config.clone();
+        if plugin.reported_id() == old.reported_id() {
+            // a suffixed plugin keeps its effective id
+            plugin.effective_id = old.effective_id.clone();
+        }
+        self.prepare_load(&mut plugin);
+        // log subscribers keep receiving lines of the reloaded plugin
+        plugin.log = old.log.clone();
+        // timings keep accumulating, including the old instance's unload
+        if plugin.profiler.is_some() {
+            plugin.profiler = old.profiler.clone().or(plugin.profiler);
+        }
+        let new_id = plugin.id();
+        if new_id != id {
+            if !allow_id_change {
+                eyre::bail!(
+                    "Reloaded plugin has id: `{new_id:?}`, which doesn't match `{id:?}`. \
+                     Keeping the old plugin."
+                );
+            }
+            self.validate_plugin(new_id)?;
+        }
+        self.validate_node_rpc_namespaces(&plugin)?;
+
+        Ok(plugin)
+    }
+
+    /// Replaces the plugin by a given id with its loaded new instance.
+    ///
+    /// Returns: New exex plugin's id.
+    fn replace_plugin(&mut self, id: &str, plugin: LoadedExExPlugin) -> Result<String> {
+        let new_id = plugin.id().to_owned();
+        warn_schema_change(self.plugin(id)?, &plugin);
+        self.remove_plugin(id)?;
+        self.insert_plugin(plugin);
+
+        debug!(id=%id, new_id=%new_id, action="reload", "ExEx plugin was reloaded succesfully");
+
+        Ok(new_id)
+    }
+
+    /// Register an in-process ExEx [plugin](`super::ExExPlugin`), which isn't backed by
+    /// a dynamic library, e.g. one of the built-in plugins.
+    ///
+    /// Returns: Registered exex plugin's id.
+    pub async fn register_plugin(&mut self, plugin: Box<dyn ExExPlugin>) -> Result<String> {
+        self.add_plugin(LoadedExExPlugin::new(plugin, None, None, None)).await
+    }
+
+    /// Register an in-process ExEx [plugin](`super::ExExPlugin`) like
+    /// [`Self::register_plugin`], passing it a given [config](ExExPlugin::on_config)
+    /// before initialization.
+    ///
+    /// Returns: Registered exex plugin's id.
+    pub async fn register_plugin_with_config(
+        &mut self,
+        plugin: Box<dyn ExExPlugin>,
+        config: serde_json::Value,
+    ) -> Result<String> {
+        let mut loaded = LoadedExExPlugin::new(plugin, None, None, None);
+        loaded.config = Some(config);
+        self.add_plugin(loaded).await
+    }
+
+    /// Loads a new version of the plugin by the given id from a given path as its shadow,
+    /// e.g. to compare it against the production version before an upgrade.
+    ///
+    /// The shadow is initialized with the config of the production plugin and receives
+    /// the same notifications right after it. Its output is isolated: the shadow's failures
+    /// never hold back the finished height, and its handlers' span, results and
+    /// [information](Self::plugin_info) are tagged with a shadow id, i.e. the plugin's id
+    /// suffixed with `@shadow`. A previous shadow of the plugin is replaced.
+    ///
+    /// Returns: Shadow exex plugin's id.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    pub async unsafe fn shadow_load(
+        &mut self,
+        id: &str,
+        plugin_path: impl AsRef<Path>,
+    ) -> Result<String> {
+        let old = self.plugin(id)?;
+        if old.shadow {
+            eyre::bail!("Plugin with id: `{id:?}` is a shadow itself.");
+        }
+
+        let mut plugin = self.open_plugin(plugin_path.as_ref(), old.log_level)?;
+        plugin.config = old.config.clone();
+        plugin.shadow = true;
+        // prepared as a shadow, so it writes apart from the production plugin
+        self.prepare_load(&mut plugin);
+        if plugin.id() != id {
+            eyre::bail!("Shadow plugin has id: `{:?}`, which doesn't match `{id:?}`.", plugin.id());
+        }
+
+        trace!(id=%plugin.display_id(), action="on_load", "calling");
+        plugin.load().await?;
+
+        let shadow_id = plugin.display_id();
+        if let Some(previous) = self.shadows.insert(id.to_owned(), plugin) {
+            discard_plugin(previous);
+        }
+        debug!(id=%shadow_id, action="shadow_load", "ExEx plugin shadow was loaded succesfully");
+
+        Ok(shadow_id)
+    }
+
+    /// Returns the first divergence of the [shadow](Self::shadow_load) of the plugin by
+    /// the given id from the production plugin, `None` if the shadow hasn't diverged.
+    ///
+    /// Results of a notification are compared once both plugins handle it successfully.
+    pub fn shadow_divergence(&self, id: &str) -> Result<Option<ShadowDivergence>> {
+        self.plugin(id)?;
+        let Some(shadow) = self.shadows.get(id) else {
+            eyre::bail!("Plugin with id: `{id:?}` has no shadow.");
+        };
+        Ok(shadow.divergence.lock().unwrap().clone())
+    }
+
+    /// Promotes the [shadow](Self::shadow_load) of the plugin by the given id, replacing
+    /// the production plugin with it, unless the shadow [diverged](Self::shadow_divergence).
+    ///
+    /// Returns: Promoted exex plugin's id.
+    pub fn promote_shadow(&mut self, id: &str) -> Result<String> {
+        self.plugin(id)?;
+        if let Some(divergence) = self.shadow_divergence(id)? {
+            eyre::bail!(
+                "Shadow of plugin with id: `{id:?}` diverged at block {}, not promoting it.",
+                divergence.block
+            );
+        }
+        let Some(mut shadow) = self.shadows.remove(id) else {
+            eyre::bail!("Plugin with id: `{id:?}` has no shadow.");
+        };
+        shadow.shadow = false;
+
+        let id = self.replace_plugin(id, shadow)?;
+        self.persist_state();
+
+        Ok(id)
+    }
+
+    /// Scans a directory for plugin libraries of the platform and describes each of them,
+    /// without registering on manager.
+    ///
+    /// Libraries which fail to open are reported as not loadable, instead of aborting the scan.
+    ///
+    /// # Safety
+    ///
+    /// Each found library is opened and its plugin constructed, see [`Self::load_plugin`].
+    pub unsafe fn discover_plugins(&self, dir: impl AsRef<Path>) -> Result<Vec<DiscoveredPlugin>> {
+        let mut paths = std::fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.retain(|path| is_plugin_library(path));
+        paths.sort();
+
+        Ok(paths
+            .into_iter()
+            .map(|path| match self.open_plugin(&path, None) {
+                Ok(loaded) => {
+                    let error = self
+                        .validate_plugin(loaded.id())
+                        .and_then(|_| self.validate_node_rpc_namespaces(&loaded))
+                        .err()
+                        .map(|err| err.to_string());
+                    DiscoveredPlugin {
+                        path,
+                        id: Some(loaded.id().to_string()),
+                        version: Some(loaded.version().to_string()),
+                        loadable: error.is_none(),
+                        error,
+                    }
+                }
+                Err(err) => DiscoveredPlugin {
+                    path,
+                    id: None,
+                    version: None,
+                    loadable: false,
+                    error: Some(err.to_string()),
+                },
+            })
+            .collect())
+    }
+
+    /// Removes files of the [temp directory](Self::with_temp_dir), which aren't referenced
+    /// by any loaded, shadow or loading plugin, e.g. ones leaked by a crash.
+    ///
+    /// Returns: Number of removed files.
+    pub fn gc_temp_files(&self) -> Result<usize> {
+        let Some(dir) = &self.temp_dir else {
+            eyre::bail!("Temp directory isn't set on manager");
+        };
+        if !dir.exists() {
+            return Ok(0);
+        }
+
+        let referenced = self.referenced_temp_files();
+        let mut removed = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_file() || referenced.contains(&std::fs::canonicalize(&path)?) {
+                continue;
+            }
+            std::fs::remove_file(&path)
+                .map_err(|err| eyre::format_err!("Failed to remove {}: {err:?}", path.display()))?;
+            debug!(path=?path, "removed orphaned exex plugin temp file");
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Canonical paths of libraries of loaded, shadow and loading plugins, which are kept
+    /// by [`Self::gc_temp_files`].
+    fn referenced_temp_files(&self) -> HashSet<PathBuf> {
+        self.plugins
+            .iter()
+            .chain(self.shadows.values())
+            .filter_map(|plugin| plugin.canonical_path.clone())
+            .chain(self.pending_loads.values().filter_map(|pending| pending.path.clone()))
+            .collect()
+    }
+
+    /// Checks [dependencies](ExExPlugin::depends_on) of a plugin library at the given path
+    /// against the loaded plugins, without registering it on manager.
+    ///
+    /// The plugin isn't constructed, its id and dependencies are read from the
+    /// [metadata](crate::declare_exex_plugin_metadata) exported by the library, which is
+    /// closed right away.
+    ///
+    /// Returns an error, if the library doesn't export its metadata or the plugin can't be
+    /// loaded, e.g. its id is already taken.
+    ///
+    /// # Safety
+    ///
+    /// The library is opened, running its initialization routines, see [`Self::load_plugin`].
+    pub unsafe fn check_dependencies(
+        &self,
+        plugin_path: impl AsRef<Path>,
+    ) -> Result<DependencyCheck> {
+        let plugin_path = plugin_path.as_ref();
+        self.validate_plugin_size(plugin_path)?;
+        check_library_file(plugin_path)?;
+
+        let lib = Library::new(plugin_path)
+            .map_err(|err| eyre::format_err!("Failed to find & load exex plugin: {err:?}"))?;
+        self.check_plugin_build(&lib, plugin_path)?;
+        let PluginMetadata { id, depends_on } =
+            PluginMetadata::from_library(&lib).ok_or_else(|| {
+                eyre::format_err!("plugin {} doesn't export its metadata", plugin_path.display())
+            })?;
+        self.validate_plugin(&id)?;
+
+        let (loaded, missing) =
+            depends_on.into_iter().partition(|id| self.plugins.contains(id.as_str()));
+
+        Ok(DependencyCheck { id, loaded, missing })
+    }
+
+    /// Registers an in-process plugin like [`Self::register_plugin`], but initializes it
+    /// in background, so a long-running [`ExExPlugin::on_load`] doesn't stall notifications.
+    ///
+    /// The plugin is stored on manager by the [running](Self::run) manager once initialized.
+    /// RPC loads go through the same path.
+    ///
+    /// # Consistency
+    ///
+    /// Notifications received during the load are queued and replayed to the plugin in order,
+    /// before it receives any new ones, up to the [limits](Self::with_background_load_limits)
+    /// of the load. Meanwhile, `FinishedHeight` isn't held back by the loading plugin, so it may
+    /// advance past the queued notifications, which are lost for the plugin if the node exits
+    /// before the replay.
+    ///
+    /// Returns a receiver of the plugin's id, once it's initialized.
+    pub fn spawn_register_plugin(
+        &mut self,
+        plugin: Box<dyn ExExPlugin>,
+    ) -> oneshot::Receiver<RpcResult<String>> {
+        let (tx, rx) = oneshot::channel();
+        self.spawn_add_plugin(LoadedExExPlugin::new(plugin, None, None, None), tx);
+        rx
+    }
+
+    /// Validates a plugin and spawns its initialization, see [`Self::spawn_register_plugin`].
+    #[allow(unused_must_use)] // for oneshot send error
+    fn spawn_add_plugin(&mut self, mut loaded: LoadedExExPlugin, tx: ResponseTx<String>) {
+        self.resolve_id(&mut loaded);
+        if let Err(err) = self
+            .validate_plugin(loaded.id())
+            .and_then(|_| self.validate_node_rpc_namespaces(&loaded))
+        {
+            tx.send(Err(format_rpc_err!("failed to load exex plugin: {err:?}")))
+                .inspect_err(|err| error!("failed to send response: {err:?}"));
+            return;
+        }
+
+        self.prepare_load(&mut loaded);
+        let id = loaded.id().to_owned();
+        let path = loaded.canonical_path.clone();
+
+        trace!(id=%id, action="on_load", "spawning");
+        let timeout = self.background_load_timeout;
+        let load: JoinHandle<Result<LoadedExExPlugin>> = tokio::spawn(async move {
+            let load = AssertUnwindSafe(loaded.load()).catch_unwind();
+            let res = match tokio::time::timeout(timeout, load).await {
+                Ok(res) => res
+                    .map_err(|panic| {
+                        eyre::format_err!("plugin panicked on load: {}", panic_message(&*panic))
+                    })
+                    .and_then(|res| res),
+                Err(_) => Err(eyre::format_err!("plugin load timed out after {timeout:?}")),
+            };
+            res.map(|_| loaded)
+        });
+        let abort = load.abort_handle();
+        self.pending_loads.insert(
+            id.clone(),
+            PendingLoad { tx, path, queued: VecDeque::new(), abort, overflowed: false },
+        );
+        self.loading.push(Box::pin(load.map(move |res| (id, res))));
+    }
+
+    /// Stores a plugin initialized in background, replaying the queued notifications to it by
+    /// the dispatch, as if it was resumed from a pause.
+    #[allow(unused_must_use)] // for oneshot send error
+    async fn finish_load(&mut self, id: String, res: Result<Result<LoadedExExPlugin>, JoinError>) {
+        let Some(pending) = self.pending_loads.remove(&id) else { return };
+
+        let res = match res {
+            Ok(Ok(loaded)) if pending.overflowed => {
+                // completed before it was aborted
+                discard_plugin(loaded);
+                Err(format_rpc_err!("failed to load exex plugin: too many queued notifications"))
+            }
+            Err(_) if pending.overflowed => {
+                Err(format_rpc_err!("failed to load exex plugin: too many queued notifications"))
+            }
+            Ok(Ok(loaded)) => {
+                loaded.queue_backlog(pending.queued);
+                self.insert_plugin(loaded);
+                self.persist_state();
+                debug!(id=%id, action="load", "ExEx plugin was loaded succesfully");
+
+                if let Err(err) = self.retry_pending_notifications().await {
+                    error!(%id, %err, "failed to replay queued notifications");
+                }
+                Ok(id)
+            }
+            Ok(Err(err)) => Err(format_rpc_err!("failed to load exex plugin: {err:?}")),
+            Err(err) => Err(format_rpc_err!("exex plugin load task failed: {err}")),
+        };
+        pending.tx.send(res).inspect_err(|err| error!("failed to send response: {err:?}"));
+    }
+
+    /// Opens a plugin's library and constructs the plugin, without registering it on manager.
+    ///
+    /// The plugin isn't [prepared](Self::prepare_load) for load yet, since its effective id
+    /// isn't resolved.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    unsafe fn open_plugin(
+        &self,
+        plugin_path: &Path,
+        log_level: Option<Level>,
+    ) -> Result<LoadedExExPlugin> {
+        type ExExPluginCreate = unsafe fn() -> *mut dyn ExExPlugin;
+        type ExExPluginDescriptorCreate = unsafe extern "C" fn() -> PluginDescriptor;
+
+        self.validate_plugin_size(plugin_path)?;
+        check_library_file(plugin_path)?;
+
+        let lib = Library::new(plugin_path)
+            .map_err(|err| eyre::format_err!("Failed to find & load exex plugin: {err:?}"))?;
+        self.check_plugin_build(&lib, plugin_path)?;
+
+        let plugin = if let Ok(constructor) =
+            lib.get::<ExExPluginDescriptorCreate>(EXEX_PLUGIN_DESCRIPTOR_FN_NAME)
+        {
+            constructor()
+                .into_plugin()
+                .map_err(|err| eyre::format_err!("Refused to load exex plugin: {err}"))?
+        } else {
+            // legacy constructor without load metadata
+            let constructor: Symbol<'_, ExExPluginCreate> =
+                lib.get(EXEX_MANAGER_CONSTRUCTOR_FN_NAME).map_err(|_| {
+                    eyre::format_err!(
+                        "Neither `__create_exex_plugin_descriptor` nor `__create_exex_plugin` \
+                         symbol was found on exex plugin library."
+                    )
+                })?;
+            debug!(path=?plugin_path, "Exex plugin library exports a legacy constructor");
+
+            let raw_plugin_ptr = constructor();
+            Box::from_raw(raw_plugin_ptr)
+        };
+
+        Ok(LoadedExExPlugin::new(
+            plugin,
+            Some(Arc::new(lib)),
+            Some(plugin_path.to_path_buf()),
+            log_level,
+        ))
+    }
+
+    /// Checks the toolchain of a plugin's library against the host's one.
+    ///
+    /// # Safety
+    ///
+    /// See [`PluginBuild::from_library`].
+    unsafe fn check_plugin_build(&self, lib: &Library, plugin_path: &Path) -> Result<()> {
+        let res = match PluginBuild::from_library(lib) {
+            Some(build) => build.check(&PluginBuild::host()),
+            None => Err(eyre::format_err!("plugin doesn't export its build metadata")),
+        };
+
+        match res {
+            Err(err) if self.strict_build => {
+                Err(err.wrap_err(format!("refused to load plugin {}", plugin_path.display())))
+            }
+            Err(err) => {
+                warn!(path=?plugin_path, %err, "loading plugin with unverified build");
+                Ok(())
+            }
+            Ok(()) => Ok(()),
+        }
+    }
+
+    /// Validates, initializes and stores a plugin on manager.
+    async fn add_plugin(&mut self, mut loaded: LoadedExExPlugin) -> Result<String> {
+        self.resolve_id(&mut loaded);
+        let id = loaded.id().to_owned();
+
+        self.validate_plugin(&id)?;
+        self.validate_node_rpc_namespaces(&loaded)?;
+
+        self.prepare_load(&mut loaded);
+        trace!(id=%id, action="on_load", "calling");
+        loaded.load().await?;
+
+        self.insert_plugin(loaded);
+        self.persist_state();
+
+        debug!(id=%id, action="load", "ExEx plugin was loaded succesfully");
+
+        Ok(id)
+    }
+
+    /// Sets up what the manager hands to a plugin on [load](LoadedExExPlugin::load).
+    ///
+    /// Called once the plugin's effective id is resolved, since its sink is keyed by the id.
+    fn prepare_load(&self, loaded: &mut LoadedExExPlugin) {
+        loaded.signer = self.signer.clone();
+        loaded.log = PluginLog::new(self.plugin_log_capacity);
+        loaded.profiler = self.profiling.then(Default::default);
+        loaded.dedicated_thread = self.plugin_threads;
+        loaded.sink = self.plugin_sink(loaded);
+    }
+
+    /// Returns a sharded output of a plugin, if [configured](Self::with_sharded_output).
+    fn plugin_sink(&self, loaded: &LoadedExExPlugin) -> Option<ShardedSink> {
+        let (dir, shard_size) = self.sharded_output.as_ref()?;
+        Some(ShardedSink::new(dir.join(loaded.display_id()), *shard_size))
+    }
+
+    /// Suffixes the id of a plugin, which collides with a loaded or loading one, if
+    /// [enabled](Self::with_id_suffixes).
+    ///
+    /// The suffix is the lowest free instance number starting from `2`, e.g. `MinimalExEx#2`.
+    fn resolve_id(&self, loaded: &mut LoadedExExPlugin) {
+        let taken = |id: &str| self.plugins.contains(id) || self.pending_loads.contains_key(id);
+        self.resolve_id_among(loaded, taken);
+    }
+
+    /// Suffixes the id of a plugin, which is [taken](Self::resolve_id) by the given predicate.
+    fn resolve_id_among(&self, loaded: &mut LoadedExExPlugin, taken: impl Fn(&str) -> bool) {
+        if !self.id_suffixes {
+            return;
+        }
+        let id = loaded.reported_id();
+        if !taken(id) {
+            return;
+        }
+
+        let effective_id = (2..)
+            .map(|instance| format!("{id}{ID_SUFFIX_SEPARATOR}{instance}"))
+            .find(|effective_id| !taken(effective_id))
+            .expect("instance numbers are unbounded");
+        debug!(%id, %effective_id, "Suffixed colliding ExEx plugin id");
+        loaded.effective_id = Some(effective_id);
+    }
+
+    /// Stores an initialized plugin on manager, keeping its load order.
+    fn insert_plugin(&mut self, mut loaded: LoadedExExPlugin) {
+        loaded.load_seq = self.next_load_seq;
+        self.next_load_seq += 1;
+        self.plugins.insert(loaded);
+    }
+
+    /// Unload the ExEx [plugin](`super::ExExPlugin`) by the given plugin id, if one exists on
+    /// manager.
+    pub fn unload_plugin(&mut self, id: &str) -> Result<()> {
+        if let Some(shadow) = self.shadows.remove(id) {
+            discard_plugin(shadow);
+        }
+        let res = self.remove_plugin(id);
+        self.persist_state();
+        res
+    }
+
+    /// Unload all ExEx [plugins](`super::ExExPlugin`) exists on manager.
+    ///
+    /// Persisted state is kept untouched, so plugins are restored after the node restart.
+    ///
+    /// Plugins are unloaded in [reverse-topological](Self::unload_order) order.
+    pub fn unload_all(&mut self) {
+        info!("Start unload all ExEx plugins");
+
+        for (_, shadow) in self.shadows.drain() {
+            discard_plugin(shadow);
+        }
+
+        let unload_res: Result<()> =
+            self.unload_order().iter().try_for_each(|name| self.remove_plugin(name));
+        if let Err(err) = unload_res {
+            error!(err=%err, "Error on unload plugins")
+        }
+    }
+
+    /// Returns ids of loaded plugins in a safe teardown order.
+    ///
+    /// A plugin always precedes the plugins it [depends on](ExExPlugin::depends_on),
+    /// otherwise plugins are in reverse load order. Dependency cycles are broken
+    /// by the reverse load order as well.
+    pub fn unload_order(&self) -> Vec<String> {
+        let mut remaining: Vec<_> = self.plugins.iter().collect();
+        remaining.sort_by_key(|plugin| std::cmp::Reverse(plugin.load_seq));
+
+        let mut order = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let has_dependents = |id: &str| {
+                remaining
+                    .iter()
+                    .any(|plugin| plugin.id() != id && plugin.depends_on().contains(&id))
+            };
+            let next =
+                remaining.iter().position(|plugin| !has_dependents(plugin.id())).unwrap_or(0);
+            order.push(remaining.remove(next).id().to_owned());
+        }
+
+        order
+    }
+
+    /// Restores plugins from the [state file](Self::with_state_file), if one is set.
+    ///
+    /// Plugins are loaded in their [load order](ManagerState::load_order). A missing or
+    /// corrupted state file, as well as plugins failed to load, are logged and skipped.
+    ///
+    /// Returns: Restored exex plugin's ids.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    pub async unsafe fn load_from_state_file(&mut self) -> Vec<String> {
+        let Some(state_file) = self.state_file.clone() else { return Vec::new() };
+
+        let state = match ManagerState::read(&state_file) {
+            Ok(Some(state)) => state,
+            Ok(None) => {
+                debug!(path=?state_file, "ExEx plugins state file doesn't exist");
+                return Vec::new();
+            }
+            Err(err) => {
+                warn!(path=?state_file, %err, "failed to read ExEx plugins state file");
+                return Vec::new();
+            }
+        };
+
+        let mut ids = Vec::with_capacity(state.plugins.len());
+        for plugin in state.load_order() {
+            let log_level = plugin.log_level.as_deref().and_then(|level| level.parse().ok());
+            let res = match self.open_plugin(&plugin.path, log_level) {
+                Ok(mut loaded) => {
+                    loaded.priority = plugin.priority;
+                    loaded.config = plugin.config.clone();
+                    self.add_plugin(loaded).await
+                }
+                Err(err) => Err(err),
+            };
+            match res {
+                Ok(id) => ids.push(id),
+                Err(err) => error!(path=?plugin.path, %err, "failed to restore exex plugin"),
+            }
+        }
+
+        info!(plugins=?ids, "Restored ExEx plugins from state file");
+
+        ids
+    }
+
+    /// Converges the set of library backed plugins to a manifest at a given path.
+    ///
+    /// The manifest has the [state file](Self::with_state_file) format, and plugins are
+    /// initialized in its [load order](ManagerState::load_order). Plugins are matched
+    /// by canonical paths of their libraries: listed plugins which aren't loaded are added,
+    /// loaded ones which aren't listed are removed, and ones whose library was modified since
+    /// load or whose log level, priority or config has changed are reloaded. In-process plugins are
+    /// left intact.
+    ///
+    /// Plugins listed only for [other chains](PluginState::chains) than the node's one are
+    /// skipped, as if they weren't listed.
+    ///
+    /// All added and reloaded plugins are initialized before any loaded plugin is touched,
+    /// so on any failure the loaded plugins are kept as is.
+    ///
+    /// Returns an action taken on each plugin, ordered by plugin id.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    pub async unsafe fn apply_manifest(&mut self, path: &Path) -> Result<Vec<ManifestReport>> {
+        let Some(manifest) = ManagerState::read(path)? else {
+            eyre::bail!("Manifest {} doesn't exist.", path.display());
+        };
+
+        let reports = self
+            .converge(manifest)
+            .await
+            .map_err(|err| err.wrap_err("Failed to apply manifest."))?;
+        info!(?reports, "Applied ExEx plugins manifest");
+
+        Ok(reports)
+    }
+
+    /// Returns a snapshot of the manager's library backed plugins, i.e. their paths, log levels,
+    /// priorities and configs, to be [restored](Self::restore) later, e.g. on another manager.
+    pub fn snapshot(&self) -> ManagerState {
+        self.state()
+    }
+
+    /// Converges the set of library backed plugins to a given [snapshot](Self::snapshot),
+    /// the same way as [`Self::apply_manifest`] does.
+    ///
+    /// Either the whole snapshot is restored, or loaded plugins are kept as is.
+    ///
+    /// Returns an action taken on each plugin, ordered by plugin id.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    pub async unsafe fn restore(&mut self, snapshot: ManagerState) -> Result<Vec<ManifestReport>> {
+        let reports = self
+            .converge(snapshot)
+            .await
+            .map_err(|err| err.wrap_err("Failed to restore snapshot."))?;
+        info!(?reports, "Restored ExEx plugins snapshot");
+
+        Ok(reports)
+    }
+
+    /// Converges the set of library backed plugins to a given state, see [`Self::apply_manifest`].
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load_plugin`].
+    async unsafe fn converge(&mut self, manifest: ManagerState) -> Result<Vec<ManifestReport>> {
+        // Loaded library backed plugins by canonical paths of their libraries
+        let mut loaded = self
+            .plugins
+            .iter()
+            .filter_map(|plugin| {
+                let loaded = (
+                    plugin.id().to_owned(),
+                    plugin.path.clone()?,
+                    plugin.log_level,
+                    plugin.priority,
+                    plugin.config.clone(),
+                );
+                Some((plugin.canonical_path.clone()?, (loaded, plugin.modified)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut reports = Vec::new();
+        let mut to_load = Vec::new();
+        for entry in manifest.load_order() {
+            if !entry.loads_on(self.network.chain_id) {
+                info!(
+                    path = %entry.path.display(),
+                    chains = ?entry.chains,
+                    chain_id = self.network.chain_id,
+                    "Skipped ExEx plugin of manifest for other chains"
+                );
+                continue;
+            }
+            let log_level = entry
+                .log_level
+                .as_deref()
+                .map(str::parse)
+                .transpose()
+                .map_err(|err| eyre::format_err!("Invalid log level of plugin: {err}"))?;
+            let canonical_path = std::fs::canonicalize(&entry.path).map_err(|err| {
+                eyre::format_err!("Failed to resolve plugin {}: {err}", entry.path.display())
+            })?;
+
+            let path = entry.path.clone();
+            match loaded.remove(&canonical_path) {
+                None => to_load.push((path, log_level, entry.priority, entry.config.clone(), None)),
+                Some(((id, _, old_log_level, old_priority, old_config), modified))
+                    if old_log_level != log_level
+                        || old_priority != entry.priority
+                        || old_config != entry.config
+                        || modified != library_modified(&canonical_path) =>
+                {
+                    to_load.push((path, log_level, entry.priority, entry.config.clone(), Some(id)))
+                }
+                Some(((id, path, ..), _)) => {
+                    reports.push(ManifestReport { id, path, action: ManifestAction::Unchanged })
+                }
+            }
+        }
+        let to_remove =
+            loaded.into_values().map(|((id, path, ..), _)| (id, path)).collect::<Vec<_>>();
+
+        // Prepare all plugins first, so the loaded ones are kept on failure
+        let replaced = to_remove
+            .iter()
+            .map(|(id, _)| id.clone())
+            .chain(to_load.iter().filter_map(|(.., old_id)| old_id.clone()))
+            .collect::<HashSet<_>>();
+        let mut prepared: Vec<(LoadedExExPlugin, PathBuf, Option<String>)> = Vec::new();
+        for (plugin_path, log_level, priority, config, old_id) in to_load {
+            let res = async {
+                let mut plugin = self.open_plugin(&plugin_path, log_level)?;
+                plugin.priority = priority;
+                plugin.config = config;
+                // ids of replaced plugins are free, unlike the ones of plugins prepared before
+                self.resolve_id_among(&mut plugin, |id| {
+                    (self.plugins.contains(id) && !replaced.contains(id))
+                        || self.pending_loads.contains_key(id)
+                        || prepared.iter().any(|(prepared, ..)| prepared.id() == id)
+                });
+                self.prepare_load(&mut plugin);
+                let id = plugin.id();
+                if let Some((_, path, _)) =
+                    prepared.iter().find(|(prepared, ..)| prepared.id() == id)
+                {
+                    eyre::bail!(
+                        "Plugin with id: `{id:?}` is already presented on manager, loaded from {}.",
+                        path.display()
+                    );
+                }
+                if self.plugins.contains(id) && !replaced.contains(id) {
+                    return Err(self.duplicate_id_error(id));
+                }
+                self.validate_plugin_id(id)?;
+                self.validate_node_rpc_namespaces(&plugin)?;
+
+                trace!(id=%id, action="on_load", "calling");
+                plugin.load().await?;
+                Ok(plugin)
+            }
+            .await;
+
+            match res {
+                Ok(plugin) => prepared.push((plugin, plugin_path, old_id)),
+                Err(err) => {
+                    for (plugin, ..) in prepared {
+                        discard_plugin(plugin);
+                    }
+                    return Err(
+                        err.wrap_err(format!("Plugin {} wasn't loaded.", plugin_path.display()))
+                    );
+                }
+            }
+        }
+
+        for (id, path) in to_remove {
+            if let Err(err) = self.remove_plugin(&id) {
+                warn!(%id, %err, "failed to unload exex plugin cleanly");
+            }
+            reports.push(ManifestReport { id, path, action: ManifestAction::Removed });
+        }
+        for (plugin, path, old_id) in prepared {
+            let action = match old_id {
+                Some(old_id) => {
+                    if let Ok(old) = self.plugin(&old_id) {
+                        warn_schema_change(old, &plugin);
+                    }
+                    if let Err(err) = self.remove_plugin(&old_id) {
+                        warn!(id=%old_id, %err, "failed to unload exex plugin cleanly");
+                    }
+                    ManifestAction::Reloaded
+                }
+                None => ManifestAction::Added,
+            };
+            let id = plugin.id().to_owned();
+            self.insert_plugin(plugin);
+            reports.push(ManifestReport { id, path, action });
+        }
+        self.persist_state();
+
+        reports.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(reports)
+    }
+
+    /// Writes a [`MetricsSnapshot`] into the metrics export file, if one is set.
+    fn export_metrics(&self) {
+        let Some((path, _)) = &self.metrics_export else { return };
+
+        if let Err(err) = MetricsSnapshot::new(self.plugins_info()).write(path) {
+            warn!(?path, %err, "failed to export exex plugins metrics");
+        }
+    }
+
+    /// Returns a current state of the manager's library backed plugins, in their load order.
+    pub fn state(&self) -> ManagerState {
+        let mut plugins = self.plugins.iter().collect::<Vec<_>>();
+        plugins.sort_by_key(|plugin| plugin.load_seq);
+        let plugins = plugins
+            .into_iter()
+            .filter_map(|plugin| {
+                Some(PluginState {
+                    path: plugin.path.clone()?,
+                    log_level: plugin.log_level.map(|level| level.to_string()),
+                    priority: plugin.priority,
+                    config: plugin.config.clone(),
+                    chains: None,
+                })
+            })
+            .collect();
+
+        ManagerState { plugins }
+    }
+
+    /// Writes a current state into the [state file](Self::with_state_file), if one is set.
+    fn persist_state(&self) {
+        let Some(state_file) = &self.state_file else { return };
+
+        if let Err(err) = self.state().write(state_file) {
+            error!(path=?state_file, %err, "failed to persist ExEx plugins state");
+        }
+    }
+
+    /// Removes a plugin from manager and closes its library, once not used anymore.
+    fn remove_plugin(&mut self, id: &str) -> Result<()> {
+        debug!(id=%id, action="ExExPluginManager::unload_plugin", "unloading an ExEx plugin");
+
+        if let Some(mut plugin) = self.plugins.take(id) {
+            trace!(id=%id, action="ExExPlugin::on_unload", "calling");
+            let res = plugin.unload();
+            trace!(id=%id, action="ExExPlugin::on_unload", "aborting tasks");
+            close_plugin(plugin);
+            res?;
+        }
+
+        debug!(id=%id, action="unload", "ExEx plugin was unloaded succesfully");
+
+        Ok(())
+    }
+
+    /// Returns a loaded plugin by the given id.
+    #[inline]
+    fn plugin(&self, id: &str) -> Result<&LoadedExExPlugin> {
+        let shadow = || self.shadows.get(id.strip_suffix(SHADOW_ID_SUFFIX)?);
+        self.plugins.get(id).or_else(shadow).ok_or_else(|| {
+            eyre::format_err!("Plugin with id: `{id:?}` is not presented on manager.")
+        })
+    }
+
+    /// Validates [plugin](`super::ExExPlugin`) to being:
+    ///
+    /// - not presented on manager (TODO: ability to replace it)
+    /// - [id](`super::ExExPlugin::id`) is not equal to [`EXEX_MANAGER_ID`]
+    #[inline]
+    fn validate_plugin(&self, id: &str) -> Result<()> {
+        if self.plugins.contains(id) {
+            return Err(self.duplicate_id_error(id));
+        }
+
+        self.validate_plugin_id(id)
+    }
+
+    /// Error of a plugin, whose id is already taken by a loaded plugin, naming the library
+    /// the loaded one comes from.
+    fn duplicate_id_error(&self, id: &str) -> eyre::Report {
+        match self.plugins.get(id).and_then(|plugin| plugin.path.as_ref()) {
+            Some(path) => eyre::format_err!(
+                "Plugin with id: `{id:?}` is already presented on manager, loaded from {}.",
+                path.display()
+            ),
+            None => eyre::format_err!(
+                "Plugin with id: `{id:?}` is already presented on manager, registered in-process."
+            ),
+        }
+    }
+
+    /// Validates [plugin](`super::ExExPlugin`)'s id isn't reserved or being loaded.
+    fn validate_plugin_id(&self, id: &str) -> Result<()> {
+        if self.pending_loads.contains_key(id) {
+            eyre::bail!("Plugin with id: `{id:?}` is already presented on manager.");
+        }
+
+        if id == EXEX_MANAGER_ID {
+            eyre::bail!(
+                "`{EXEX_MANAGER_ID}` is reserved id for manager. Choose another id for plugin."
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Validates the node's RPC enables all namespaces a plugin
+    /// [requires](ExExPlugin::required_node_rpc_namespaces).
+    fn validate_node_rpc_namespaces(&self, plugin: &LoadedExExPlugin) -> Result<()> {
+        let required = plugin.required_node_rpc_namespaces();
+        if required.is_empty() {
+            return Ok(());
+        }
+
+        let enabled = enabled_rpc_namespaces(&self.ctx.config.rpc);
+        let missing: Vec<_> =
+            required.iter().filter(|namespace| !enabled.contains(**namespace)).collect();
+        if !missing.is_empty() {
+            eyre::bail!(
+                "Plugin requires node RPC namespaces {missing:?}, which aren't enabled over HTTP or WS."
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Validates [plugin](`super::ExExPlugin`) library file size to not exceed
+    /// the configured maximum, if one is set.
+    #[inline]
+    fn validate_plugin_size(&self, plugin_path: &Path) -> Result<()> {
+        let Some(max_bytes) = self.max_plugin_size else { return Ok(()) };
+
+        let size = std::fs::metadata(plugin_path)
+            .map_err(|err| eyre::format_err!("Failed to read exex plugin metadata: {err:?}"))?
+            .len();
+        if size > max_bytes {
+            eyre::bail!(
+                "Plugin library size is {size} bytes, which exceeds the maximum of {max_bytes} bytes."
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns an opened plugin, if it declares a given expected id, if any.
+///
+/// Otherwise, the plugin is dropped before its library is closed.
+fn check_expected_id(
+    loaded: LoadedExExPlugin,
+    expected_id: Option<&str>,
+) -> Result<LoadedExExPlugin> {
+    match expected_id {
+        Some(expected_id) if loaded.reported_id() != expected_id => eyre::bail!(
+            "Plugin declares id: `{:?}`, which doesn't match expected `{expected_id:?}`.",
+            loaded.reported_id()
+        ),
+        _ => Ok(loaded),
+    }
+}
+
+/// Returns namespaces of the node's RPC enabled over HTTP or WS, which serve
+/// the standard ones, unless configured otherwise.
+fn enabled_rpc_namespaces(rpc: &RpcServerArgs) -> HashSet<String> {
+    [(rpc.http, &rpc.http_api), (rpc.ws, &rpc.ws_api)]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .flat_map(|(_, api)| api.clone().unwrap_or(RpcModuleSelection::Standard).to_selection())
+        .map(|module| module.to_string())
+        .collect()
+}
+
+/// Unloads an initialized plugin, which wasn't stored on manager.
+fn discard_plugin(mut plugin: LoadedExExPlugin) {
+    if let Err(err) = plugin.unload() {
+        warn!(id=%plugin.id(), %err, "failed to unload exex plugin cleanly");
+    }
+    close_plugin(plugin);
+}
+
+/// Drops an unloaded plugin, aborting its tasks, and closes its library once the aborted tasks
+/// finish, since their code lives in it.
+fn close_plugin(plugin: LoadedExExPlugin) {
+    let id = plugin.id().to_owned();
+    let tasks = plugin.abort_tasks();
+    let lib = plugin.into_library();
+    match tasks.filter(|_| lib.is_some()) {
+        Some(tasks) => {
+            let tracker = tasks.tracker().clone();
+            tasks.handle().spawn(async move {
+                tracker.wait().await;
+                close_library(&id, lib);
+            });
+        }
+        None => close_library(&id, lib),
+    }
+}
+
+/// Closes a library of a dropped plugin, if it holds the last reference to it.
+///
+/// Otherwise, the library is kept open by its remaining holders, i.e. in-flight handler jobs
+/// run off the manager's task, and closed once the last of them completes.
+fn close_library(id: &str, lib: Option<Arc<Library>>) {
+    let Some(lib) = lib else { return };
+    match Arc::try_unwrap(lib) {
+        Ok(lib) => {
+            trace!(id=%id, action="ExExPlugin::on_unload", "closing library");
+            if let Err(err) = lib.close() {
+                warn!(id=%id, %err, "failed to close exex plugin library");
+            }
+        }
+        Err(_) => {
+            debug!(id=%id, "exex plugin library is kept open until its remaining references drop")
+        }
+    }
+}
+
+/// Warns operators on a storage migration, if a reloaded plugin declares other
+/// [schema version](ExExPlugin::schema_version) than its previous instance.
+fn warn_schema_change(old: &LoadedExExPlugin, new: &LoadedExExPlugin) {
+    let (old_version, new_version) = (old.plugin.schema_version(), new.plugin.schema_version());
+    if old_version != new_version {
+        warn!(
+            id=%new.id(),
+            old_schema_version=old_version,
+            new_schema_version=new_version,
+            "Reloaded exex plugin changed its schema version"
+        );
     }
 }
@@ -0,0 +1,30 @@
+//! Declarative management of the plugin set, see
+//! [`ExExPluginManager::apply_manifest`](crate::ExExPluginManager::apply_manifest).
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Action taken on a plugin to converge to a manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestAction {
+    /// Plugin is listed in the manifest, but wasn't loaded.
+    Added,
+    /// Plugin was loaded, but isn't listed in the manifest.
+    Removed,
+    /// Plugin's library or log level has changed.
+    Reloaded,
+    /// Plugin is loaded as listed in the manifest.
+    Unchanged,
+}
+
+/// Action taken on a plugin by the manifest apply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestReport {
+    /// Id of the plugin, the new one for reloaded plugins.
+    pub id: String,
+    /// Path of the plugin's library.
+    pub path: PathBuf,
+    pub action: ManifestAction,
+}
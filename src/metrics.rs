@@ -0,0 +1,41 @@
+//! Snapshot of plugin metrics, periodically exported by the
+//! [`ExExPluginManager`](crate::ExExPluginManager).
+
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::PluginInfo;
+
+/// Aggregated metrics of all loaded plugins at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// Time of the snapshot, as a unix timestamp in seconds.
+    pub timestamp: u64,
+    pub plugins: Vec<PluginInfo>,
+}
+
+impl MetricsSnapshot {
+    /// Creates a snapshot of given plugins at the current time.
+    pub fn new(plugins: Vec<PluginInfo>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+        Self { timestamp, plugins }
+    }
+
+    /// Writes a snapshot into the given file.
+    ///
+    /// Writes into a temporary file first, so readers never observe a half-written snapshot.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+}
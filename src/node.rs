@@ -0,0 +1,32 @@
+//! Node information shared with plugins.
+
+use reth::primitives::B256;
+use serde::{Deserialize, Serialize};
+
+/// Lightweight information about the node, which is passed to plugins along with
+/// every notification, so they don't need to re-derive the network context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// Chain id of the node's network.
+    pub chain_id: u64,
+    /// Block number of the node's current head.
+    pub head_number: u64,
+    /// Block hash of the node's current head.
+    pub head_hash: B256,
+    /// Sequence number of the passed notification, increasing by one with every notification
+    /// the manager dispatches, regardless of block numbers, so it survives reorgs.
+    ///
+    /// A plugin detects gaps, e.g. notifications skipped during a pause, or duplicates, e.g.
+    /// a retried notification, which keeps its sequence number. Starts from `1`.
+    pub sequence: u64,
+}
+
+/// Label of the network the manager is attached to, which tells apart responses and events
+/// of managers of different nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkLabel {
+    /// Chain id of the node's network.
+    pub chain_id: u64,
+    /// Name of the node's network, e.g. `mainnet`, or its chain id if the network isn't named.
+    pub name: String,
+}
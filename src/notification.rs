@@ -0,0 +1,78 @@
+//! Serializable representation of [`ExExNotification`].
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use reth::providers::Chain;
+use reth_exex::ExExNotification;
+
+/// A dispatched notification with its [sequence number](crate::NodeInfo::sequence).
+pub(crate) type SequencedNotification = (Arc<ExExNotification>, u64);
+
+/// Kind of a chain change the [`ExExNotification`] carries.
+///
+/// Serialized in lowercase, i.e. `"commit"`, `"revert"` or `"reorg"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainKind {
+    Commit,
+    Revert,
+    Reorg,
+}
+
+impl ChainKind {
+    /// All chain kinds.
+    pub const ALL: [Self; 3] = [Self::Commit, Self::Revert, Self::Reorg];
+
+    /// Bit of the kind in a set of kinds, packed into `u8`.
+    pub(crate) const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl From<&ExExNotification> for ChainKind {
+    fn from(notification: &ExExNotification) -> Self {
+        match notification {
+            ExExNotification::ChainCommitted { .. } => Self::Commit,
+            ExExNotification::ChainReverted { .. } => Self::Revert,
+            ExExNotification::ChainReorged { .. } => Self::Reorg,
+        }
+    }
+}
+
+/// An inclusive range of block numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockRange {
+    pub from: u64,
+    pub to: u64,
+}
+
+/// A plain, serializable view on [`ExExNotification`] which only keeps
+/// reverted and committed block ranges of the notification's chains.
+///
+/// Suitable for interop with consumers outside of the node process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedNotification {
+    /// Kind of the notification's chain change.
+    pub kind: ChainKind,
+    /// Range of reverted blocks, if the notification reverts a chain.
+    pub reverted: Option<BlockRange>,
+    /// Range of committed blocks, if the notification commits a chain.
+    pub committed: Option<BlockRange>,
+}
+
+impl From<&ExExNotification> for NormalizedNotification {
+    fn from(notification: &ExExNotification) -> Self {
+        let range = |chain: &Chain| {
+            let range = chain.range();
+            BlockRange { from: *range.start(), to: *range.end() }
+        };
+
+        Self {
+            kind: ChainKind::from(notification),
+            reverted: notification.reverted_chain().as_deref().map(range),
+            committed: notification.committed_chain().as_deref().map(range),
+        }
+    }
+}
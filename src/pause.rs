@@ -0,0 +1,97 @@
+//! Notifications of paused plugins, see
+//! [`ExExPlugin::pause_policy`](crate::ExExPlugin::pause_policy).
+
+use std::{collections::VecDeque, sync::Arc};
+
+use reth::providers::Chain;
+use reth_exex::ExExNotification;
+use reth_tracing::tracing::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{notification::SequencedNotification, plugin::LoadedExExPlugin};
+
+/// Policy of notifications received while a plugin is
+/// [paused](crate::ExExPluginManager::set_plugin_paused).
+///
+/// Kept notifications are replayed to the plugin in order once it's resumed, before any
+/// new ones, still filtered by the plugin's
+/// [notification kinds](crate::ExExPluginManager::set_plugin_notification_kinds).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PausePolicy {
+    /// Notifications are skipped, so the plugin never receives them.
+    #[default]
+    Skip,
+    /// Up to `capacity` notifications are kept, later ones are skipped.
+    Buffer { capacity: usize },
+    /// All notifications are kept, with consecutive commits merged into a single one,
+    /// so the resumed plugin catches up from its last processed block at once.
+    Backfill,
+}
+
+impl PausePolicy {
+    /// Keeps a notification received during a pause in a plugin's backlog.
+    ///
+    /// A merged notification takes the sequence number of the last one merged into it.
+    /// Returns `false` if the notification is skipped.
+    pub(crate) fn keep(
+        &self,
+        backlog: &mut VecDeque<SequencedNotification>,
+        (notification, sequence): &SequencedNotification,
+    ) -> bool {
+        match self {
+            Self::Skip => false,
+            Self::Buffer { capacity } if backlog.len() >= *capacity => false,
+            Self::Buffer { .. } => {
+                backlog.push_back((notification.clone(), *sequence));
+                true
+            }
+            Self::Backfill => {
+                let last = backlog.back().map(|(last, _)| last.as_ref());
+                let merged = match (last, notification.as_ref()) {
+                    (
+                        Some(ExExNotification::ChainCommitted { new: pending }),
+                        ExExNotification::ChainCommitted { new },
+                    ) => {
+                        let mut pending = Chain::clone(pending);
+                        pending.append_chain(Chain::clone(new)).is_ok().then_some(pending)
+                    }
+                    _ => None,
+                };
+                match merged {
+                    Some(chain) => {
+                        let merged = ExExNotification::ChainCommitted { new: Arc::new(chain) };
+                        *backlog.back_mut().expect("merged into the last") =
+                            (Arc::new(merged), *sequence);
+                    }
+                    None => backlog.push_back((notification.clone(), *sequence)),
+                }
+                true
+            }
+        }
+    }
+}
+
+/// Takes notifications dispatched to a plugin before new ones, in order: ones pending
+/// a [retry](crate::ExExPluginManager::with_retry_queue), followed by ones kept during a pause,
+/// once the plugin is resumed.
+///
+/// Retries of a paused plugin are kept until it's resumed, preceding its backlog, while
+/// retries of a muted plugin are discarded.
+pub(crate) fn take_pending_notifications(
+    plugin: &LoadedExExPlugin,
+) -> impl Iterator<Item = SequencedNotification> {
+    let mut retries = if plugin.paused() { VecDeque::new() } else { plugin.take_retries() };
+    if !retries.is_empty() && !plugin.blocks_finished_height() {
+        debug!(id = %plugin.id(), retries = retries.len(), "Discarded retries of muted plugin");
+        retries.clear();
+    }
+    retries.into_iter().chain(plugin.take_paused_backlog())
+}
+
+/// Returns `true` if a plugin has notifications pending a retry, or ones kept during a pause
+/// pending a replay after it's resumed.
+pub(crate) fn has_pending_notifications(plugin: &LoadedExExPlugin) -> bool {
+    !plugin.retries.lock().unwrap().is_empty()
+        || (!plugin.paused() && !plugin.paused_backlog.lock().unwrap().is_empty())
+}
@@ -0,0 +1,135 @@
+//! Build metadata of plugins, checked against the host's on load, and their static metadata.
+
+use std::ffi::{c_char, CStr};
+
+use libloading::Library;
+
+/// Name of the exported static with the `rustc` version a plugin was built with.
+pub const EXEX_PLUGIN_RUSTC_VERSION_SYMBOL: &[u8] = b"__EXEX_PLUGIN_RUSTC_VERSION";
+
+/// Name of the exported static with the target triple a plugin was built for.
+pub const EXEX_PLUGIN_TARGET_SYMBOL: &[u8] = b"__EXEX_PLUGIN_TARGET";
+
+/// Name of the exported static with a plugin's id.
+pub const EXEX_PLUGIN_ID_SYMBOL: &[u8] = b"__EXEX_PLUGIN_ID";
+
+/// Name of the exported static with a plugin's dependencies, each followed by a comma.
+pub const EXEX_PLUGIN_DEPENDS_ON_SYMBOL: &[u8] = b"__EXEX_PLUGIN_DEPENDS_ON";
+
+/// A NUL-terminated string, exported by a plugin's library
+/// with [`declare_exex_plugin!`](crate::declare_exex_plugin) or
+/// [`declare_exex_plugin_metadata!`](crate::declare_exex_plugin_metadata).
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct ExportedStr(*const c_char);
+
+// SAFETY: points to an immutable static string.
+unsafe impl Sync for ExportedStr {}
+
+impl ExportedStr {
+    /// `rustc` version the crate is built with.
+    pub const RUSTC_VERSION: Self = Self::new(concat!(env!("EXEX_PLUGIN_RUSTC_VERSION"), "\0"));
+
+    /// Target triple the crate is built for.
+    pub const TARGET: Self = Self::new(concat!(env!("EXEX_PLUGIN_TARGET"), "\0"));
+
+    /// Exports a NUL-terminated string, failing to compile a static which isn't.
+    pub const fn new(nul_terminated: &'static str) -> Self {
+        match CStr::from_bytes_with_nul(nul_terminated.as_bytes()) {
+            Ok(s) => Self(s.as_ptr()),
+            Err(_) => panic!("string must be NUL-terminated"),
+        }
+    }
+}
+
+/// Toolchain a plugin's library or the host was built with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginBuild {
+    /// Output of `rustc --version`.
+    pub rustc_version: String,
+    /// Target triple.
+    pub target: String,
+}
+
+impl PluginBuild {
+    /// Toolchain of the host.
+    pub fn host() -> Self {
+        Self {
+            rustc_version: env!("EXEX_PLUGIN_RUSTC_VERSION").to_owned(),
+            target: env!("EXEX_PLUGIN_TARGET").to_owned(),
+        }
+    }
+
+    /// Reads a toolchain exported by the plugin's library, if any.
+    ///
+    /// # Safety
+    ///
+    /// The exported symbols, if present, **must** be [`ExportedStr`] statics.
+    pub unsafe fn from_library(lib: &Library) -> Option<Self> {
+        Some(Self {
+            rustc_version: read_exported(lib, EXEX_PLUGIN_RUSTC_VERSION_SYMBOL)?,
+            target: read_exported(lib, EXEX_PLUGIN_TARGET_SYMBOL)?,
+        })
+    }
+
+    /// Checks the plugin's toolchain is the same as the host's one.
+    ///
+    /// Returns an error describing a mismatch.
+    pub fn check(&self, host: &Self) -> eyre::Result<()> {
+        if self.target != host.target {
+            eyre::bail!(
+                "plugin target `{}` doesn't match host target `{}`",
+                self.target,
+                host.target
+            );
+        }
+        if self.rustc_version != host.rustc_version {
+            eyre::bail!(
+                "plugin is built with `{}`, but host with `{}`",
+                self.rustc_version,
+                host.rustc_version
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Id and dependencies a plugin's library exports with
+/// [`declare_exex_plugin_metadata!`](crate::declare_exex_plugin_metadata), read without
+/// constructing the plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginMetadata {
+    /// Plugin id.
+    pub id: String,
+    /// Ids of plugins, which the plugin [depends on](crate::ExExPlugin::depends_on).
+    pub depends_on: Vec<String>,
+}
+
+impl PluginMetadata {
+    /// Reads metadata exported by the plugin's library, if any.
+    ///
+    /// # Safety
+    ///
+    /// The exported symbols, if present, **must** be [`ExportedStr`] statics.
+    pub unsafe fn from_library(lib: &Library) -> Option<Self> {
+        let depends_on = read_exported(lib, EXEX_PLUGIN_DEPENDS_ON_SYMBOL)?;
+        Some(Self {
+            id: read_exported(lib, EXEX_PLUGIN_ID_SYMBOL)?,
+            depends_on: depends_on
+                .split(',')
+                .filter(|id| !id.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+        })
+    }
+}
+
+/// Reads an [`ExportedStr`] static of the library, if it's exported.
+///
+/// # Safety
+///
+/// The exported symbol, if present, **must** be an [`ExportedStr`] static.
+unsafe fn read_exported(lib: &Library, symbol: &[u8]) -> Option<String> {
+    let exported = lib.get::<*const ExportedStr>(symbol).ok()?;
+    Some(CStr::from_ptr((**exported).0).to_string_lossy().into_owned())
+}
@@ -0,0 +1,170 @@
+//! Reusable read-through cache plugin
+
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    future::Future,
+    ops::RangeInclusive,
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
+
+use eyre::Result;
+use serde::Serialize;
+
+use reth::{
+    primitives::SealedBlockWithSenders,
+    providers::{Chain, ExecutionOutcome},
+};
+use reth_exex::ExExNotification;
+
+use crate::{ExExPlugin, NodeInfo};
+
+/// Function which indexes a committed block into a cached value.
+type IndexFn<T> = dyn Fn(&SealedBlockWithSenders, &ExecutionOutcome) -> T + Send + Sync;
+
+/// A plugin which indexes committed blocks into an in-memory cache keyed by block number.
+///
+/// Reverted blocks are evicted from the cache, so it always reflects the canonical chain.
+/// If a capacity is set, the lowest blocks are evicted once it's exceeded.
+///
+/// The cache can be queried directly with [`Self::get`], or through the `get` [command](
+/// ExExPlugin::command) with `{"number": <block number>}` params, which returns
+/// the cached value or `null`.
+///
+/// # Example
+///
+/// ```rust
+/// use reth_exex_plugin::CachingExExPlugin;
+///
+/// // Caches a number of transactions of each block
+/// let plugin = CachingExExPlugin::new("TxCountExEx", |block, _| block.body.transactions.len())
+///     .with_capacity(1024);
+/// ```
+pub struct CachingExExPlugin<T> {
+    id: &'static str,
+    index: Box<IndexFn<T>>,
+    capacity: Option<usize>,
+    cache: RwLock<BTreeMap<u64, T>>,
+}
+
+impl<T> CachingExExPlugin<T> {
+    /// Creates a caching plugin with a given id, which indexes blocks with a given function.
+    pub fn new(
+        id: &'static str,
+        index: impl Fn(&SealedBlockWithSenders, &ExecutionOutcome) -> T + Send + Sync + 'static,
+    ) -> Self {
+        Self { id, index: Box::new(index), capacity: None, cache: RwLock::default() }
+    }
+
+    /// Sets maximum number of cached blocks.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Returns a cached value of a given block, if any.
+    pub fn get(&self, number: u64) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.cache.read().unwrap().get(&number).cloned()
+    }
+
+    /// Returns cached values of blocks in a given range, ordered by block number.
+    pub fn range(&self, range: RangeInclusive<u64>) -> Vec<(u64, T)>
+    where
+        T: Clone,
+    {
+        self.cache
+            .read()
+            .unwrap()
+            .range(range)
+            .map(|(number, value)| (*number, value.clone()))
+            .collect()
+    }
+
+    /// Returns a number of cached blocks.
+    pub fn len(&self) -> usize {
+        self.cache.read().unwrap().len()
+    }
+
+    /// Returns `true` if no blocks are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Evicts blocks of a reverted chain and caches blocks of a committed one.
+    fn apply(&self, reverted: Option<&Chain>, committed: Option<&Chain>) {
+        let mut cache = self.cache.write().unwrap();
+
+        if let Some(reverted) = reverted {
+            let range = reverted.range();
+            cache.retain(|number, _| !range.contains(number));
+        }
+
+        if let Some(committed) = committed {
+            for block in committed.blocks_iter() {
+                cache.insert(block.number, (self.index)(block, committed.execution_outcome()));
+            }
+        }
+
+        if let Some(capacity) = self.capacity {
+            while cache.len() > capacity {
+                cache.pop_first();
+            }
+        }
+    }
+}
+
+impl<T> Debug for CachingExExPlugin<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachingExExPlugin")
+            .field("id", &self.id)
+            .field("capacity", &self.capacity)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> ExExPlugin for CachingExExPlugin<T>
+where
+    T: Clone + Serialize + Send + Sync + 'static,
+{
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn command(
+        &self,
+        command: String,
+        params: serde_json::Value,
+    ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value>> + Send + '_>> {
+        Box::pin(async move {
+            match command.as_str() {
+                "get" => {
+                    let Some(number) = params.get("number").and_then(|number| number.as_u64())
+                    else {
+                        eyre::bail!("missing block number");
+                    };
+                    Ok(serde_json::to_value(self.get(number))?)
+                }
+                _ => eyre::bail!("unsupported command: {command}"),
+            }
+        })
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            self.apply(
+                notification.reverted_chain().as_deref(),
+                notification.committed_chain().as_deref(),
+            );
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,83 @@
+//! C-compatible load metadata of plugins, returned by their constructor.
+
+use std::ffi::c_void;
+
+use crate::ExExPlugin;
+
+/// Name of the plugin constructor, which returns a [`PluginDescriptor`].
+///
+/// Preferred over the legacy
+/// [`EXEX_MANAGER_CONSTRUCTOR_FN_NAME`](crate::EXEX_MANAGER_CONSTRUCTOR_FN_NAME) constructor, if a
+/// library exports both.
+pub const EXEX_PLUGIN_DESCRIPTOR_FN_NAME: &[u8] = b"__create_exex_plugin_descriptor";
+
+/// Version of the [`PluginDescriptor`] contract, bumped on any change of its layout
+/// or of the plugin it points to.
+pub const EXEX_PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Plugin pointer and load metadata, returned by the [constructor](EXEX_PLUGIN_DESCRIPTOR_FN_NAME)
+/// generated by [`declare_exex_plugin!`](crate::declare_exex_plugin).
+///
+/// The plugin is passed as a thin pointer to `Box<dyn ExExPlugin>`, so the manager reads
+/// the metadata of a fixed layout first and only then trusts the plugin's vtable.
+#[derive(Debug)]
+#[repr(C)]
+pub struct PluginDescriptor {
+    /// [ABI version](EXEX_PLUGIN_ABI_VERSION) the plugin is built against.
+    pub abi_version: u32,
+    /// Load flags of the plugin, reserved and `0` for now.
+    ///
+    /// Unknown flags are rejected, so a plugin relying on them isn't loaded by an older manager.
+    pub flags: u32,
+    /// Pointer to `Box<dyn ExExPlugin>`.
+    pub plugin: *mut c_void,
+}
+
+impl PluginDescriptor {
+    /// Flags known to the manager.
+    pub const KNOWN_FLAGS: u32 = 0;
+
+    /// Describes a given plugin with the crate's ABI version.
+    pub fn new(plugin: Box<dyn ExExPlugin>) -> Self {
+        Self {
+            abi_version: EXEX_PLUGIN_ABI_VERSION,
+            flags: 0,
+            plugin: Box::into_raw(Box::new(plugin)).cast(),
+        }
+    }
+
+    /// Checks the descriptor's metadata against the host's ABI.
+    ///
+    /// Returns an error describing a mismatch.
+    pub fn check(&self) -> eyre::Result<()> {
+        if self.abi_version != EXEX_PLUGIN_ABI_VERSION {
+            eyre::bail!(
+                "plugin ABI version {} doesn't match host ABI version {EXEX_PLUGIN_ABI_VERSION}",
+                self.abi_version
+            );
+        }
+        let unknown = self.flags & !Self::KNOWN_FLAGS;
+        if unknown != 0 {
+            eyre::bail!("plugin has unknown flags: {unknown:#x}");
+        }
+        if self.plugin.is_null() {
+            eyre::bail!("plugin pointer is null");
+        }
+
+        Ok(())
+    }
+
+    /// [Checks](Self::check) the descriptor and takes the plugin it points to.
+    ///
+    /// A plugin of a mismatching descriptor is leaked, since its vtable can't be trusted
+    /// to drop it.
+    ///
+    /// # Safety
+    ///
+    /// A checked descriptor **must** point to a `Box<dyn ExExPlugin>`, e.g. one created
+    /// with [`Self::new`].
+    pub unsafe fn into_plugin(self) -> eyre::Result<Box<dyn ExExPlugin>> {
+        self.check()?;
+        Ok(*Box::from_raw(self.plugin.cast::<Box<dyn ExExPlugin>>()))
+    }
+}
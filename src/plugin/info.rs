@@ -0,0 +1,105 @@
+//! Information about a loaded ExEx plugin
+
+use std::{path::PathBuf, sync::atomic::Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::LoadedExExPlugin;
+use crate::{ChainKind, NormalizedNotification};
+
+/// Information about a loaded plugin and the library which backs it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginInfo {
+    /// Id of the plugin, suffixed with `@shadow` for a
+    /// [shadow](crate::ExExPluginManager::shadow_load).
+    pub id: String,
+    /// Id the plugin reports itself, differs from [`Self::id`] if it was
+    /// [suffixed](crate::ExExPluginManager::with_id_suffixes) on a collision.
+    pub reported_id: String,
+    pub version: String,
+    /// [Schema version](crate::ExExPlugin::schema_version) of the plugin's storage.
+    pub schema_version: u32,
+    /// Path the plugin was loaded from, `None` for in-process plugins.
+    pub path: Option<PathBuf>,
+    /// Resolved canonical path of the plugin's library, captured at load.
+    pub canonical_path: Option<PathBuf>,
+    /// Modification time of the plugin's library file at load, as a unix timestamp in seconds.
+    ///
+    /// Comparing it against the file on disk shows whether the running plugin is outdated.
+    pub modified: Option<u64>,
+    /// Whether the plugin is [required](crate::ExExPlugin::is_required).
+    pub required: bool,
+    /// Whether the plugin is [idempotent](crate::ExExPlugin::is_idempotent).
+    pub idempotent: bool,
+    /// Whether the plugin's failures are muted from holding back the finished height.
+    pub muted: bool,
+    /// Number of notifications passed to the plugin, since its metrics were last
+    /// [reset](crate::ExExPluginManager::reset_plugin_metrics).
+    pub handled: u64,
+    /// Number of the plugin's failed notifications, since its metrics were last reset.
+    pub failures: u64,
+    /// Kind of the last notification passed to the plugin, `None` if there were none.
+    pub last_kind: Option<ChainKind>,
+    /// Number of the plugin's failed notifications pending a retry.
+    pub pending_retries: usize,
+    /// Whether the plugin is [paused](crate::ExExPluginManager::set_plugin_paused).
+    pub paused: bool,
+    /// Number of notifications kept while the plugin is paused, pending a replay on resume.
+    pub paused_backlog: usize,
+    /// Whether the plugin panicked on its [dedicated thread], so it's not dispatched anymore.
+    ///
+    /// [dedicated thread]: crate::ExExPluginManager::with_plugin_threads
+    pub dead: bool,
+}
+
+/// Result of the plugin's [health check](crate::ExExPlugin::health).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginHealth {
+    pub healthy: bool,
+    /// Time the health check took, in milliseconds.
+    pub latency_ms: u64,
+    /// Error of the failed health check, or `"timeout"` if it timed out.
+    pub error: Option<String>,
+}
+
+/// Full diagnostic status of a loaded plugin, returned for all plugins by a single
+/// `exex_pluginsFull` RPC request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FullPluginStatus {
+    /// Information and metrics of the plugin.
+    pub info: PluginInfo,
+    /// Number of the plugin's notification handlers in flight.
+    pub inflight: usize,
+    /// Error of the plugin's last failed notification, `None` if there were none.
+    pub last_error: Option<String>,
+    /// Block ranges of the last notification passed to the plugin, `None` if there were none.
+    pub last_notification: Option<NormalizedNotification>,
+    /// Latest [result](crate::ExExPlugin::handle_notification_with_result) of the plugin.
+    pub latest_result: Option<serde_json::Value>,
+    /// Result of the plugin's health check.
+    pub health: PluginHealth,
+}
+
+impl From<&LoadedExExPlugin> for PluginInfo {
+    fn from(loaded: &LoadedExExPlugin) -> Self {
+        Self {
+            id: loaded.display_id(),
+            reported_id: loaded.reported_id().to_owned(),
+            version: loaded.version().to_owned(),
+            schema_version: loaded.plugin.schema_version(),
+            path: loaded.path.clone(),
+            canonical_path: loaded.canonical_path.clone(),
+            modified: loaded.modified,
+            required: loaded.is_required(),
+            idempotent: loaded.is_idempotent(),
+            muted: loaded.muted.load(Ordering::Relaxed),
+            handled: loaded.handled.load(Ordering::Relaxed),
+            failures: loaded.failures.load(Ordering::Relaxed),
+            last_kind: *loaded.last_kind.lock().unwrap(),
+            pending_retries: loaded.retries.lock().unwrap().len(),
+            paused: loaded.paused(),
+            paused_backlog: loaded.paused_backlog.lock().unwrap().len(),
+            dead: loaded.dead.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Filter of plugins' `tracing` output by their levels
+
+use std::fmt::Debug;
+
+use reth_tracing::{
+    tracing::{
+        field::{Field, Visit},
+        span, Level, Metadata, Subscriber,
+    },
+    tracing_subscriber::{
+        layer::{Context, Filter},
+        registry::LookupSpan,
+    },
+};
+
+/// Name of the span [`plugin_span`](super::plugin_span) creates.
+const PLUGIN_SPAN_NAME: &str = "exex_plugin";
+
+/// Level of a plugin, stored in the extensions of its span.
+#[derive(Debug, Clone, Copy)]
+struct PluginLevel(Level);
+
+/// Reads the `log_level` field of a plugin's span.
+#[derive(Default)]
+struct LogLevelVisitor(Option<Level>);
+
+impl Visit for LogLevelVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "log_level" {
+            self.0 = value.parse().ok();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "log_level" {
+            self.0 = format!("{value:?}").parse().ok();
+        }
+    }
+}
+
+/// A per-layer filter, which drops `tracing` events recorded inside of a plugin's
+/// [span](super::plugin_span) below the plugin's level, i.e. the one it's loaded with.
+///
+/// Events outside of plugins' spans pass through, so the filter only scopes plugins' output
+/// and leaves the node's one to the subscriber's other filters. It's composed with any layer
+/// of a `tracing_subscriber` registry, including the ones of `reth_tracing`.
+///
+/// # Example
+///
+/// ```rust
+/// use reth_exex_plugin::PluginLevelFilter;
+/// use reth_tracing::tracing_subscriber::{fmt, layer::SubscriberExt, registry, Layer};
+///
+/// let subscriber = registry().with(fmt::layer().with_filter(PluginLevelFilter));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PluginLevelFilter;
+
+impl<S> Filter<S> for PluginLevelFilter
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(&self, metadata: &Metadata<'_>, cx: &Context<'_, S>) -> bool {
+        if !metadata.is_event() {
+            return true;
+        }
+        let Some(current) = cx.lookup_current() else { return true };
+        // the innermost plugin's span sets the level
+        let level =
+            current.scope().find_map(|span| span.extensions().get::<PluginLevel>().copied());
+        level.map_or(true, |PluginLevel(level)| *metadata.level() <= level)
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: Context<'_, S>) {
+        if attrs.metadata().name() != PLUGIN_SPAN_NAME {
+            return;
+        }
+        let mut visitor = LogLevelVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(level), Some(span)) = (visitor.0, cx.span(id)) {
+            span.extensions_mut().insert(PluginLevel(level));
+        }
+    }
+}
@@ -2,22 +2,133 @@
 
 use std::{
     borrow::Borrow,
+    collections::VecDeque,
+    future::Future,
     hash::Hash,
-    ops::{Deref, DerefMut},
-    sync::Arc,
+    ops::Deref,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, UNIX_EPOCH},
 };
 
 use eyre::Result;
 use libloading::Library;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use tokio::runtime::Handle;
+use tokio_util::sync::CancellationToken;
 
+use reth::primitives::BlockNumHash;
 use reth_exex::ExExNotification;
+use reth_tracing::tracing::{
+    debug_span, error, error_span, info_span, trace, trace_span, warn, warn_span, Instrument,
+    Level, Span,
+};
 
-use super::ExExPlugin;
+use super::{
+    ExExPlugin, PluginHealth, PluginHook, PluginInfo, PluginLog, PluginProfiler, PluginSigner,
+    PluginTasks, PluginThread, ShardedSink, DEFAULT_PLUGIN_LOG_CAPACITY,
+};
+use crate::{
+    notification::SequencedNotification, supervisor::panic_message, ChainKind, ErrorLogSampler,
+    FilterReason, NodeInfo, NormalizedNotification, ShadowDivergence, SubscriptionSpec,
+    COALESCE_CAPABILITY,
+};
 
 #[derive(Debug)]
 pub(crate) struct LoadedExExPlugin {
-    pub(crate) plugin: Box<dyn ExExPlugin>,
-    pub(crate) lib: Arc<Library>,
+    pub(crate) plugin: Arc<dyn ExExPlugin>,
+    /// Id of the plugin on manager, if it differs from the self-reported one, see
+    /// [`ExExPluginManager::with_id_suffixes`](crate::ExExPluginManager::with_id_suffixes).
+    pub(crate) effective_id: Option<String>,
+    /// Dynamic library of the plugin, `None` for in-process [registered] plugins.
+    ///
+    /// [registered]: crate::ExExPluginManager::register_plugin
+    pub(crate) lib: Option<Arc<Library>>,
+    /// Path of the plugin's library, `None` for in-process plugins.
+    pub(crate) path: Option<PathBuf>,
+    /// Canonical path of the plugin's library, resolved at load.
+    pub(crate) canonical_path: Option<PathBuf>,
+    /// Modification time of the plugin's library file at load, in unix seconds.
+    pub(crate) modified: Option<u64>,
+    /// Preferred tracing level of the plugin's handlers span.
+    pub(crate) log_level: Option<Level>,
+    /// Effective [config](ExExPlugin::on_config) the plugin is loaded with.
+    pub(crate) config: Option<serde_json::Value>,
+    /// Number of notifications passed to the plugin since its metrics were last
+    /// [reset](Self::reset_metrics).
+    pub(crate) handled: AtomicU64,
+    /// Whether the plugin has handled its [warmup](ExExPlugin::warmup) number of notifications
+    /// before its metrics were reset.
+    pub(crate) warmed_up: AtomicBool,
+    /// Number of the plugin's failed notifications, excluding ones during warmup.
+    pub(crate) failures: AtomicU64,
+    /// Number of the plugin's notification handlers in flight.
+    pub(crate) inflight: AtomicUsize,
+    /// Kind of the last notification passed to the plugin.
+    pub(crate) last_kind: Mutex<Option<ChainKind>>,
+    /// Block ranges of the last notification passed to the plugin.
+    pub(crate) last_notification: Mutex<Option<NormalizedNotification>>,
+    /// Error of the plugin's last failed notification, excluding ones during warmup.
+    pub(crate) last_error: Mutex<Option<String>>,
+    /// Whether the plugin's last notification failed, excluding failures during warmup.
+    pub(crate) failing: AtomicBool,
+    /// Whether the plugin's failures are muted from holding back the finished height.
+    pub(crate) muted: AtomicBool,
+    /// Sequence number of the plugin's load on manager.
+    pub(crate) load_seq: u64,
+    /// Initialization [priority](crate::PluginState::priority) of the plugin.
+    pub(crate) priority: i32,
+    /// Set of [`ChainKind`]s the plugin receives, packed by [`ChainKind::bit`].
+    pub(crate) notification_kinds: AtomicU8,
+    /// Whether the plugin is paused, i.e. receives no notifications, regardless of its kinds.
+    pub(crate) paused: AtomicBool,
+    /// Tip of the last commit dispatched to the plugin, reset by reverts and reorgs.
+    pub(crate) last_commit: Mutex<Option<u64>>,
+    /// Latest result [returned](ExExPlugin::handle_notification_with_result) by the plugin.
+    pub(crate) latest_result: Mutex<Option<serde_json::Value>>,
+    /// Sampler of the plugin's failure logs.
+    pub(crate) error_log: Mutex<ErrorLogSampler>,
+    /// Background tasks of the plugin, set on [load](Self::load).
+    pub(crate) tasks: Option<PluginTasks>,
+    /// Handle to the manager's signing key, passed to the plugin on [load](Self::load).
+    pub(crate) signer: Option<PluginSigner>,
+    /// Dedicated log output of the plugin, passed to it on [load](Self::load).
+    pub(crate) log: PluginLog,
+    /// Sharded output of the plugin, passed to it on [load](Self::load).
+    pub(crate) sink: Option<ShardedSink>,
+    /// Recorder of the plugin's hook timings, set if
+    /// [profiling](crate::ExExPluginManager::with_profiling) is enabled.
+    pub(crate) profiler: Option<Arc<PluginProfiler>>,
+    /// Token passed to the plugin's [handlers], cancelled on the plugin's unload.
+    ///
+    /// [handlers]: ExExPlugin::handle_notification_with_cancellation
+    pub(crate) cancel: CancellationToken,
+    /// Whether the plugin is a shadow of a loaded one, with its output isolated.
+    pub(crate) shadow: bool,
+    /// Failed notifications pending a retry, in dispatch order, see
+    /// [`ExExPluginManager::with_retry_queue`](crate::ExExPluginManager::with_retry_queue).
+    pub(crate) retries: Mutex<VecDeque<SequencedNotification>>,
+    /// Notifications received while the plugin is paused, kept by its
+    /// [pause policy](ExExPlugin::pause_policy) for a replay on resume, or received during its
+    /// background load.
+    pub(crate) paused_backlog: Mutex<VecDeque<SequencedNotification>>,
+    /// The first divergence of the shadow plugin's result from the production one.
+    pub(crate) divergence: Mutex<Option<ShadowDivergence>>,
+    /// Dedicated [worker pool](ExExPlugin::worker_threads) of the plugin, set on
+    /// [load](Self::load).
+    pub(crate) pool: Option<Arc<ThreadPool>>,
+    /// Whether the plugin's handlers run on its own [thread](Self::thread), see
+    /// [`ExExPluginManager::with_plugin_threads`](crate::ExExPluginManager::with_plugin_threads).
+    pub(crate) dedicated_thread: bool,
+    /// Dedicated thread of the plugin, spawned on [load](Self::load).
+    pub(crate) thread: Option<PluginThread>,
+    /// Whether the plugin panicked on its dedicated thread, so it's not dispatched anymore.
+    pub(crate) dead: AtomicBool,
 }
 
 impl Borrow<str> for LoadedExExPlugin {
@@ -35,33 +146,580 @@ impl PartialEq for LoadedExExPlugin {
 impl Eq for LoadedExExPlugin {}
 
 impl Hash for LoadedExExPlugin {
+    // hashed by the effective id, as looked up by its `Borrow<str>`
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.plugin.hash(state);
+        self.id().hash(state);
     }
 }
 
 impl Deref for LoadedExExPlugin {
-    type Target = Box<dyn ExExPlugin>;
+    type Target = dyn ExExPlugin;
 
     fn deref(&self) -> &Self::Target {
-        &self.plugin
+        self.plugin.as_ref()
     }
 }
 
-impl DerefMut for LoadedExExPlugin {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.plugin
+impl LoadedExExPlugin {
+    pub(crate) fn new(
+        plugin: Box<dyn ExExPlugin>,
+        lib: Option<Arc<Library>>,
+        path: Option<PathBuf>,
+        log_level: Option<Level>,
+    ) -> Self {
+        let canonical_path = path.as_ref().and_then(|path| std::fs::canonicalize(path).ok());
+        let modified = path.as_deref().and_then(library_modified);
+
+        Self {
+            plugin: plugin.into(),
+            effective_id: None,
+            lib,
+            path,
+            canonical_path,
+            modified,
+            log_level,
+            config: None,
+            handled: AtomicU64::new(0),
+            warmed_up: AtomicBool::new(false),
+            failures: AtomicU64::new(0),
+            inflight: AtomicUsize::new(0),
+            last_kind: Mutex::new(None),
+            last_notification: Mutex::new(None),
+            last_error: Mutex::new(None),
+            failing: AtomicBool::new(false),
+            muted: AtomicBool::new(false),
+            load_seq: 0,
+            priority: 0,
+            notification_kinds: AtomicU8::new(u8::MAX),
+            paused: AtomicBool::new(false),
+            last_commit: Mutex::new(None),
+            latest_result: Mutex::new(None),
+            error_log: Mutex::default(),
+            tasks: None,
+            signer: None,
+            log: PluginLog::new(DEFAULT_PLUGIN_LOG_CAPACITY),
+            sink: None,
+            profiler: None,
+            cancel: CancellationToken::new(),
+            shadow: false,
+            retries: Mutex::new(VecDeque::new()),
+            paused_backlog: Mutex::new(VecDeque::new()),
+            divergence: Mutex::new(None),
+            pool: None,
+            dedicated_thread: false,
+            thread: None,
+            dead: AtomicBool::new(false),
+        }
     }
-}
 
-impl LoadedExExPlugin {
+    /// Passes the config, the runtime, the signer, the log and the sink to the plugin and calls its
+    /// [`ExExPlugin::on_load`] hook, followed by [`ExExPlugin::on_load_failed`] if it fails.
+    pub(crate) async fn load(&mut self) -> Result<()> {
+        if let Some(config) = self.config.clone() {
+            self.plugin_mut()?.on_config(config)?;
+        }
+
+        let tasks = PluginTasks::new(Handle::current());
+        self.plugin_mut()?.on_runtime(tasks.clone());
+        self.tasks = Some(tasks);
+
+        if let Some(signer) = self.signer.clone() {
+            self.plugin_mut()?.on_signer(signer);
+        }
+        let log = self.log.clone();
+        self.plugin_mut()?.on_log(log);
+        if let Some(sink) = self.sink.clone() {
+            self.plugin_mut()?.on_sink(sink);
+        }
+
+        if let Some(threads) = self.plugin.worker_threads() {
+            let id = self.id().to_owned();
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(move |index| format!("exex-plugin-{id}-{index}"))
+                .build()?;
+            self.pool = Some(Arc::new(pool));
+        }
+        if self.dedicated_thread {
+            self.thread = Some(PluginThread::spawn(self.id())?);
+        }
+
+        let started_at = self.profiling_start();
+        let res = self.plugin_mut()?.on_load().await;
+        self.profiling_end(PluginHook::OnLoad, started_at);
+        if res.is_err() {
+            trace!(id=%self.display_id(), action="on_load_failed", "calling");
+            if let Ok(plugin) = self.plugin_mut() {
+                plugin.on_load_failed();
+            }
+        }
+        res
+    }
+
+    /// Calls the plugin's [`ExExPlugin::on_unload`] hook.
+    pub(crate) fn unload(&mut self) -> Result<()> {
+        let started_at = self.profiling_start();
+        let res = self.plugin_mut().and_then(|plugin| plugin.on_unload());
+        self.profiling_end(PluginHook::OnUnload, started_at);
+        res
+    }
+
+    /// Returns the plugin for its `&mut self` hooks, which are called while none of its
+    /// [handler jobs](Self::handler_job) runs off the manager's task.
+    fn plugin_mut(&mut self) -> Result<&mut dyn ExExPlugin> {
+        Arc::get_mut(&mut self.plugin)
+            .ok_or_else(|| eyre::eyre!("plugin is handling a notification off the manager's task"))
+    }
+
+    /// Cancels the plugin's [token](Self::cancel) and aborts its background tasks.
+    ///
+    /// Returns the aborted tasks, unless all of them already finished, so the plugin's library
+    /// is closed once they're dropped, since their code lives in it.
+    pub(crate) fn abort_tasks(&self) -> Option<PluginTasks> {
+        self.cancel.cancel();
+        let tasks = self.tasks.as_ref()?;
+        tasks.abort_all();
+        (!tasks.is_empty()).then(|| tasks.clone())
+    }
+
+    /// Drops the plugin, returning its library, if any.
+    ///
+    /// The plugin is dropped first, since its code lives in the library.
+    pub(crate) fn into_library(mut self) -> Option<Arc<Library>> {
+        let lib = self.lib.take();
+        drop(self);
+        lib
+    }
+
+    /// Effective id of the plugin on manager, i.e. its [self-reported](Self::reported_id) id,
+    /// unless it was suffixed on a collision.
+    #[inline(always)]
+    pub(crate) fn id(&self) -> &str {
+        self.effective_id.as_deref().unwrap_or(self.plugin.id())
+    }
+
+    /// Id the plugin reports by [`ExExPlugin::id`].
     #[inline(always)]
-    #[allow(unused)]
-    pub(crate) fn id(&self) -> &'static str {
+    pub(crate) fn reported_id(&self) -> &'static str {
         self.plugin.id()
     }
 
-    pub(crate) async fn handle_notification(&self, notification: &ExExNotification) -> Result<()> {
-        self.plugin.handle_notification(notification).await
+    /// Span which scopes the plugin's handlers.
+    ///
+    /// Created on the plugin's preferred [`Level`], or [`Level::INFO`] if none was set on load.
+    /// A shadow's span is tagged with its [shadow id](Self::display_id).
+    pub(crate) fn span(&self) -> Span {
+        plugin_span(&self.display_id(), self.log_level.unwrap_or(Level::INFO))
+    }
+
+    /// Id of the plugin, suffixed with [`SHADOW_ID_SUFFIX`] for a shadow.
+    pub(crate) fn display_id(&self) -> String {
+        if self.shadow {
+            format!("{}{SHADOW_ID_SUFFIX}", self.id())
+        } else {
+            self.id().to_owned()
+        }
+    }
+
+    /// Returns `true` while the plugin hasn't handled its [warmup](ExExPlugin::warmup) number
+    /// of notifications yet.
+    pub(crate) fn in_warmup(&self) -> bool {
+        !self.warmed_up.load(Ordering::Relaxed)
+            && self.handled.load(Ordering::Relaxed) < self.plugin.warmup()
+    }
+
+    /// Reads and zeroes the plugin's counters of handled and failed notifications, returning
+    /// its information before the reset.
+    ///
+    /// Counters are swapped atomically, so an increment of a handler in flight is either in
+    /// the returned information or counted after the reset, never lost. A reset doesn't
+    /// restart the plugin's warmup.
+    pub(crate) fn reset_metrics(&self) -> PluginInfo {
+        if !self.in_warmup() {
+            self.warmed_up.store(true, Ordering::Relaxed);
+        }
+        let mut info = PluginInfo::from(self);
+        info.handled = self.handled.swap(0, Ordering::Relaxed);
+        info.failures = self.failures.swap(0, Ordering::Relaxed);
+        info
+    }
+
+    /// Returns `true` if the plugin's failure must hold back the finished height,
+    /// i.e. the plugin is [required](ExExPlugin::is_required) and not muted.
+    pub(crate) fn blocks_finished_height(&self) -> bool {
+        self.plugin.is_required() && !self.muted.load(Ordering::Relaxed)
+    }
+
+    /// Takes notifications pending a retry.
+    pub(crate) fn take_retries(&self) -> VecDeque<SequencedNotification> {
+        std::mem::take(&mut *self.retries.lock().unwrap())
+    }
+
+    /// Queues notifications for a retry, up to a given capacity of the queue.
+    ///
+    /// Notifications exceeding the capacity are dropped, so the plugin never receives them.
+    pub(crate) fn queue_retries(
+        &self,
+        notifications: impl IntoIterator<Item = SequencedNotification>,
+        capacity: usize,
+    ) {
+        let mut retries = self.retries.lock().unwrap();
+        let mut dropped = 0;
+        for notification in notifications {
+            if retries.len() < capacity {
+                retries.push_back(notification);
+            } else {
+                dropped += 1;
+            }
+        }
+        if dropped > 0 {
+            error!(id = %self.id(), dropped, capacity, "Retry queue is full, dropped notifications");
+        }
+    }
+
+    /// Keeps a notification received while the plugin is paused according to its
+    /// [pause policy](ExExPlugin::pause_policy).
+    ///
+    /// Returns `false` if the notification is skipped.
+    pub(crate) fn keep_paused(&self, notification: &SequencedNotification) -> bool {
+        self.plugin.pause_policy().keep(&mut self.paused_backlog.lock().unwrap(), notification)
+    }
+
+    /// Queues notifications received during a background load for a replay, like the ones
+    /// kept while the plugin is paused.
+    pub(crate) fn queue_backlog(
+        &self,
+        notifications: impl IntoIterator<Item = SequencedNotification>,
+    ) {
+        self.paused_backlog.lock().unwrap().extend(notifications);
+    }
+
+    /// Takes notifications kept while the plugin was paused, once it's resumed.
+    pub(crate) fn take_paused_backlog(&self) -> VecDeque<SequencedNotification> {
+        if self.paused() {
+            return VecDeque::new();
+        }
+        std::mem::take(&mut *self.paused_backlog.lock().unwrap())
+    }
+
+    /// Fires the plugin's [`ExExPlugin::on_tip`] hook, if a handled notification commits
+    /// the node's canonical head.
+    pub(crate) fn notify_tip(&self, notification: &ExExNotification, head: BlockNumHash) {
+        let Some(committed) = notification.committed_chain() else { return };
+        if committed.blocks().get(&head.number).is_some_and(|block| block.hash() == head.hash) {
+            trace!(id = %self.display_id(), number = head.number, action = "on_tip", "calling");
+            self.plugin.on_tip(head);
+        }
+    }
+
+    /// Returns a start time of a hook's call, if the plugin is profiled.
+    fn profiling_start(&self) -> Option<Instant> {
+        self.profiler.is_some().then(Instant::now)
+    }
+
+    /// Records a time of a hook's call started at a given time, if the plugin is profiled.
+    fn profiling_end(&self, hook: PluginHook, started_at: Option<Instant>) {
+        if let (Some(profiler), Some(started_at)) = (&self.profiler, started_at) {
+            profiler.record(hook, started_at.elapsed());
+        }
+    }
+
+    /// Fires the plugin's [`ExExPlugin::on_finalized`] hook, unless the plugin is paused or
+    /// its last notification failed, so it may have not processed the finalized block.
+    pub(crate) fn notify_finalized(&self, finalized: BlockNumHash) {
+        if self.paused() || self.failing.load(Ordering::Relaxed) {
+            return;
+        }
+        trace!(id = %self.display_id(), number = finalized.number, action = "on_finalized", "calling");
+        self.plugin.on_finalized(finalized);
+    }
+
+    /// Returns `true` if the plugin receives [coalesced](COALESCE_CAPABILITY) notifications.
+    pub(crate) fn coalesces(&self) -> bool {
+        self.plugin.capabilities().contains(&COALESCE_CAPABILITY)
+    }
+
+    /// Returns `true` if the plugin receives notifications of the given [`ChainKind`], by its
+    /// [subscription](Self::effective_subscription) and notification kinds set at runtime.
+    pub(crate) fn receives(&self, kind: ChainKind) -> bool {
+        self.effective_subscription()
+            .matches_kind(kind, self.notification_kinds.load(Ordering::Relaxed))
+    }
+
+    /// Sets [`ChainKind`]s of notifications the plugin receives.
+    pub(crate) fn set_notification_kinds(&self, kinds: &[ChainKind]) {
+        let kinds = kinds.iter().fold(0, |bits, kind| bits | kind.bit());
+        self.notification_kinds.store(kinds, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the notification only commits blocks the plugin has already
+    /// [processed](ExExPlugin::last_processed).
+    pub(crate) fn already_processed(&self, notification: &ExExNotification) -> bool {
+        let ExExNotification::ChainCommitted { new } = notification else { return false };
+        self.plugin.last_processed().is_some_and(|last| new.tip().number <= last)
+    }
+
+    /// Returns the plugin's [subscription](ExExPlugin::subscription), with its
+    /// [transaction filter](ExExPlugin::transaction_filter) folded in, unless the spec sets
+    /// its own.
+    pub(crate) fn effective_subscription(&self) -> SubscriptionSpec {
+        let mut spec = self.plugin.subscription();
+        if spec.tx_filter.is_none() {
+            spec.tx_filter = self.plugin.transaction_filter();
+        }
+        spec
+    }
+
+    /// Returns a reason the plugin doesn't receive the notification, checking the same
+    /// filters as the manager's dispatch in the same order, `None` if it receives it.
+    pub(crate) fn explain(&self, notification: &ExExNotification) -> Option<FilterReason> {
+        let spec = self.effective_subscription();
+        let runtime_kinds = self.notification_kinds.load(Ordering::Relaxed);
+        if self.dead.load(Ordering::Relaxed) {
+            Some(FilterReason::Dead)
+        } else if self.paused() {
+            Some(FilterReason::Paused)
+        } else if !spec.matches_kind(ChainKind::from(notification), runtime_kinds) {
+            Some(FilterReason::KindOptOut)
+        } else if self.already_processed(notification) {
+            Some(FilterReason::AlreadyProcessed)
+        } else {
+            spec.mismatch(notification, *self.last_commit.lock().unwrap())
+        }
+    }
+
+    /// Returns `true` if the plugin is paused, i.e. receives no notifications.
+    pub(crate) fn paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Pauses the plugin, keeping its notification kinds for a [resume](Self::resume).
+    pub(crate) fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    /// Resumes the paused plugin, which receives notifications of its kinds again.
+    pub(crate) fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    /// Counts a failed notification, keeping its error.
+    pub(crate) fn record_failure(&self, err: &eyre::Report) {
+        *self.last_error.lock().unwrap() = Some(err.to_string());
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        self.failing.store(true, Ordering::Relaxed);
+    }
+
+    /// Logs the plugin's failed notification, unless it's an error repeated within
+    /// a given interval.
+    pub(crate) fn log_failure(&self, err: &eyre::Report, interval: Duration) {
+        let err = err.to_string();
+        let Some(suppressed) =
+            self.error_log.lock().unwrap().sample(&err, interval, Instant::now())
+        else {
+            return;
+        };
+
+        if suppressed > 0 {
+            warn!(
+                id = %self.id(),
+                "plugin {} failed {suppressed} more times in the last {interval:?}",
+                self.id()
+            );
+        }
+        error!(id = %self.id(), %err, "failed to process notification");
+    }
+
+    /// Runs the plugin's health check within a given timeout, measuring its latency.
+    pub(crate) async fn health(&self, timeout: Duration) -> PluginHealth {
+        let started_at = Instant::now();
+        let res = tokio::time::timeout(timeout, self.plugin.health().instrument(self.span())).await;
+        let latency_ms = started_at.elapsed().as_millis() as u64;
+        self.profiling_end(PluginHook::Health, Some(started_at));
+
+        let error = match res {
+            Ok(Ok(())) => None,
+            Ok(Err(err)) => Some(err.to_string()),
+            Err(_) => Some("timeout".to_string()),
+        };
+        PluginHealth { healthy: error.is_none(), latency_ms, error }
+    }
+
+    pub(crate) async fn flush(&self) -> Result<()> {
+        self.plugin.flush().instrument(self.span()).await
+    }
+
+    /// Reconfigures the plugin, returning a panic of its reconfiguration as an error.
+    pub(crate) fn reconfigure(&self, config: serde_json::Value) -> Result<()> {
+        self.span().in_scope(|| {
+            panic::catch_unwind(AssertUnwindSafe(|| self.plugin.reconfigure(config)))
+                .unwrap_or_else(|panic| {
+                    Err(eyre::format_err!(
+                        "plugin panicked on reconfigure: {}",
+                        panic_message(&*panic)
+                    ))
+                })
+        })
+    }
+
+    pub(crate) async fn command(
+        &self,
+        command: String,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.plugin.command(command, params).instrument(self.span()).await
+    }
+
+    /// Returns a job, which drives the plugin's handler of a notification to completion on
+    /// the current runtime's handle, within the plugin's [worker pool](Self::pool), if any.
+    ///
+    /// The job owns the plugin, its library and the notification, so they outlive
+    /// the handler, even if a dispatch awaiting the job is dropped.
+    fn handler_job(
+        &self,
+        notification: &Arc<ExExNotification>,
+        node_info: &NodeInfo,
+    ) -> impl FnOnce() -> Result<Option<serde_json::Value>> + Send + 'static {
+        let owned = OwnedPlugin { plugin: self.plugin.clone(), _lib: self.lib.clone() };
+        let (notification, node_info) = (notification.clone(), *node_info);
+        let (cancel, span, pool) = (self.cancel.clone(), self.span(), self.pool.clone());
+        let handle = Handle::current();
+        move || {
+            let fut =
+                call_handler(&*owned.plugin, &notification, &node_info, cancel).instrument(span);
+            match pool {
+                Some(pool) => pool.install(|| handle.block_on(fut)),
+                None => handle.block_on(fut),
+            }
+        }
+    }
+
+    /// Dispatches a notification to the plugin, returning its result, if any.
+    pub(crate) async fn handle_notification(
+        &self,
+        notification: &Arc<ExExNotification>,
+        node_info: &NodeInfo,
+    ) -> Result<Option<serde_json::Value>> {
+        if self.dead.load(Ordering::Relaxed) {
+            eyre::bail!("plugin is dead, after it panicked on its thread");
+        }
+        self.inflight.fetch_add(1, Ordering::Relaxed);
+        // decrements the counter on completion, as well as when the dispatch is dropped
+        let _inflight = InflightGuard(&self.inflight);
+        if let Some(sink) = &self.sink {
+            sink.truncate_reverted(notification)?;
+        }
+
+        let started_at = self.profiling_start();
+        let res = match (&self.thread, &self.pool) {
+            (Some(thread), _) => {
+                let job = self.handler_job(notification, node_info);
+                thread.run(job).await.unwrap_or_else(|panic| {
+                    self.dead.store(true, Ordering::Relaxed);
+                    error!(id = %self.display_id(), %panic, "plugin panicked on its thread");
+                    Err(eyre::eyre!("plugin panicked: {panic}"))
+                })
+            }
+            // driven off the manager's task, so it keeps serving read-only RPC requests
+            (None, pool) if pool.is_some() || self.plugin.is_blocking() => {
+                let job = self.handler_job(notification, node_info);
+                match tokio::task::spawn_blocking(job).await {
+                    Ok(res) => res,
+                    Err(err) => match err.try_into_panic() {
+                        Ok(panic) => std::panic::resume_unwind(panic),
+                        Err(err) => Err(err.into()),
+                    },
+                }
+            }
+            _ => {
+                call_handler(&*self.plugin, notification, node_info, self.cancel.clone())
+                    .instrument(self.span())
+                    .await
+            }
+        };
+        self.profiling_end(PluginHook::HandleNotification, started_at);
+        self.handled.fetch_add(1, Ordering::Relaxed);
+        *self.last_kind.lock().unwrap() = Some(ChainKind::from(notification.as_ref()));
+        *self.last_notification.lock().unwrap() =
+            Some(NormalizedNotification::from(notification.as_ref()));
+        *self.last_commit.lock().unwrap() = match notification.as_ref() {
+            ExExNotification::ChainCommitted { new } => Some(new.tip().number),
+            _ => None,
+        };
+
+        if res.is_ok() {
+            self.failing.store(false, Ordering::Relaxed);
+        }
+        let result = res?;
+        if result.is_some() {
+            *self.latest_result.lock().unwrap() = result.clone();
+        }
+        Ok(result)
+    }
+}
+
+/// Calls a plugin's handler of a notification, i.e. [`ExExPlugin::on_reorg`] for a reorg and
+/// [`ExExPlugin::handle_notification_with_cancellation`] otherwise.
+fn call_handler<'a>(
+    plugin: &'a dyn ExExPlugin,
+    notification: &Arc<ExExNotification>,
+    node_info: &'a NodeInfo,
+    cancel: CancellationToken,
+) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'a>> {
+    match notification.as_ref() {
+        ExExNotification::ChainReorged { old, new } => {
+            plugin.on_reorg(old.range(), new.range(), notification.clone(), node_info, cancel)
+        }
+        _ => plugin.handle_notification_with_cancellation(notification.clone(), node_info, cancel),
+    }
+}
+
+/// A plugin and its library, owned by a handler running off the manager's task.
+///
+/// Fields drop in declaration order, so the plugin drops before its library, even if
+/// the handler panics, since its code lives in it.
+struct OwnedPlugin {
+    plugin: Arc<dyn ExExPlugin>,
+    _lib: Option<Arc<Library>>,
+}
+
+/// Decrements a plugin's [in flight](LoadedExExPlugin::inflight) handlers counter on drop.
+struct InflightGuard<'a>(&'a AtomicUsize);
+
+impl Drop for InflightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Suffix of a shadow plugin's id, see [`ExExPluginManager::shadow_load`].
+///
+/// [`ExExPluginManager::shadow_load`]: crate::ExExPluginManager::shadow_load
+pub(crate) const SHADOW_ID_SUFFIX: &str = "@shadow";
+
+/// Returns a modification time of the library file at a given path, in unix seconds.
+pub(crate) fn library_modified(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+/// Creates an `exex_plugin` span for the plugin with a given id on a given [`Level`].
+///
+/// All `tracing` events emitted by the plugin's handlers are recorded inside of this span,
+/// so their verbosity is scoped by [`PluginLevelFilter`](super::PluginLevelFilter), or with
+/// a span based filter directive, e.g. `RUST_LOG="[exex_plugin{id=MinimalExEx}]=debug"`.
+///
+/// # Limitations
+///
+/// The manager doesn't own the node's `tracing` subscriber, so the level is only a span's
+/// level and `log_level` field. Actual filtering of the plugin's output is applied only if
+/// the subscriber is configured with the filter or a directive, like above.
+pub fn plugin_span(id: &str, level: Level) -> Span {
+    match level {
+        Level::TRACE => trace_span!("exex_plugin", id, log_level = %level),
+        Level::DEBUG => debug_span!("exex_plugin", id, log_level = %level),
+        Level::INFO => info_span!("exex_plugin", id, log_level = %level),
+        Level::WARN => warn_span!("exex_plugin", id, log_level = %level),
+        _ => error_span!("exex_plugin", id, log_level = %level),
     }
 }
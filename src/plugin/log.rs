@@ -0,0 +1,91 @@
+//! Dedicated log output of a plugin
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// Default number of a plugin's latest log lines buffered by the manager, see
+/// [`ExExPluginManager::with_plugin_log_capacity`](crate::ExExPluginManager::with_plugin_log_capacity).
+pub const DEFAULT_PLUGIN_LOG_CAPACITY: usize = 1024;
+
+/// Stream a plugin's log line is written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// A line of a plugin's log output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginLogLine {
+    pub stream: LogStream,
+    /// Time the line was written at, in unix milliseconds.
+    pub timestamp_ms: u64,
+    pub message: String,
+}
+
+/// Dedicated log output of a plugin, passed by [`ExExPlugin::on_log`] hook.
+///
+/// Unlike `tracing` events, which mix with the node's logs, lines written to it are kept
+/// apart per plugin: the manager buffers the latest ones, served by `exex_pluginLogs` RPC,
+/// and streams new ones to `exex_subscribePluginLogs` subscribers.
+///
+/// [`ExExPlugin::on_log`]: crate::ExExPlugin::on_log
+#[derive(Debug, Clone)]
+pub struct PluginLog {
+    capacity: usize,
+    lines: Arc<Mutex<VecDeque<PluginLogLine>>>,
+    tx: broadcast::Sender<PluginLogLine>,
+}
+
+impl PluginLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity.max(1));
+        Self { capacity, lines: Arc::default(), tx }
+    }
+
+    /// Writes a line to the plugin's stdout.
+    pub fn println(&self, message: impl Into<String>) {
+        self.write(LogStream::Stdout, message.into())
+    }
+
+    /// Writes a line to the plugin's stderr.
+    pub fn eprintln(&self, message: impl Into<String>) {
+        self.write(LogStream::Stderr, message.into())
+    }
+
+    fn write(&self, stream: LogStream, message: String) {
+        let timestamp_ms =
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+        let line = PluginLogLine { stream, timestamp_ms, message };
+
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() >= self.capacity {
+            lines.pop_front();
+        }
+        if self.capacity > 0 {
+            lines.push_back(line.clone());
+        }
+        // no subscribers is not an error
+        let _ = self.tx.send(line);
+    }
+
+    /// Returns up to a given number of the latest buffered lines, all of them if `None`,
+    /// in the order they were written.
+    pub(crate) fn tail(&self, count: Option<usize>) -> Vec<PluginLogLine> {
+        let lines = self.lines.lock().unwrap();
+        let skip = count.map_or(0, |count| lines.len().saturating_sub(count));
+        lines.iter().skip(skip).cloned().collect()
+    }
+
+    /// Subscribes to lines written from now on.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<PluginLogLine> {
+        self.tx.subscribe()
+    }
+}
@@ -1,5 +1,57 @@
+mod abi;
+pub use abi::{
+    ExportedStr, PluginBuild, PluginMetadata, EXEX_PLUGIN_DEPENDS_ON_SYMBOL, EXEX_PLUGIN_ID_SYMBOL,
+    EXEX_PLUGIN_RUSTC_VERSION_SYMBOL, EXEX_PLUGIN_TARGET_SYMBOL,
+};
+
+mod descriptor;
+pub use descriptor::{PluginDescriptor, EXEX_PLUGIN_ABI_VERSION, EXEX_PLUGIN_DESCRIPTOR_FN_NAME};
+
+mod caching;
+pub use caching::CachingExExPlugin;
+
+mod queued;
+pub use queued::{QueueFullPolicy, QueuedExExPlugin};
+
+mod retry;
+pub use retry::{RetryExExPlugin, RetryPolicy};
+
+mod info;
+pub use info::{FullPluginStatus, PluginHealth, PluginInfo};
+
+mod level;
+pub use level::PluginLevelFilter;
+
 mod loaded;
-pub(crate) use loaded::LoadedExExPlugin;
+pub use loaded::plugin_span;
+pub(crate) use loaded::{library_modified, LoadedExExPlugin, SHADOW_ID_SUFFIX};
+
+mod log;
+pub use log::{LogStream, PluginLog, PluginLogLine, DEFAULT_PLUGIN_LOG_CAPACITY};
+
+mod profiling;
+pub(crate) use profiling::PluginProfiler;
+pub use profiling::{HookProfile, PluginHook, PluginProfile};
+
+mod signer;
+pub use signer::PluginSigner;
+
+mod sink;
+pub use sink::{ShardedSink, DEFAULT_SHARD_SIZE};
+
+mod thread;
+pub(crate) use thread::PluginThread;
+
+mod tasks;
+pub use tasks::PluginTasks;
 
 mod r#trait;
 pub use r#trait::{ExExPlugin, EXEX_MANAGER_CONSTRUCTOR_FN_NAME};
+
+mod webhook;
+pub use webhook::{WebhookConfig, WebhookExExPlugin, WEBHOOK_EXEX_PLUGIN_ID};
+
+#[cfg(unix)]
+mod socket;
+#[cfg(unix)]
+pub use socket::{SocketExExPlugin, SOCKET_EXEX_PLUGIN_ID};
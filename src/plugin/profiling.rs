@@ -0,0 +1,109 @@
+//! Timings of a plugin's hooks, see
+//! [`ExExPluginManager::with_profiling`](crate::ExExPluginManager::with_profiling).
+
+use std::{collections::BTreeMap, sync::Mutex, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Hook of a plugin timed by the profiler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginHook {
+    OnLoad,
+    HandleNotification,
+    OnUnload,
+    Health,
+}
+
+/// Percentiles of a hook's timings, in microseconds.
+///
+/// Timings are recorded into power of two buckets, so percentiles are upper bounds of their
+/// buckets, i.e. up to twice the actual timing, capped by the maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookProfile {
+    /// Number of timed calls.
+    pub count: u64,
+    pub p50_us: u64,
+    pub p90_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+}
+
+/// Timings of a plugin's hooks, returned by `exex_pluginProfile` RPC.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginProfile {
+    /// Profiles of hooks called at least once.
+    pub hooks: BTreeMap<PluginHook, HookProfile>,
+}
+
+/// Number of histogram buckets, one for zero and one per bit of a timing in microseconds.
+const BUCKETS: usize = u64::BITS as usize + 1;
+
+/// Histogram of a hook's timings in microseconds.
+#[derive(Debug, Clone)]
+struct Histogram {
+    /// Counts of timings by their bit length, i.e. bucket `i > 0` counts `2^(i-1)..2^i`.
+    buckets: [u64; BUCKETS],
+    count: u64,
+    max: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self { buckets: [0; BUCKETS], count: 0, max: 0 }
+    }
+}
+
+impl Histogram {
+    fn record(&mut self, micros: u64) {
+        self.buckets[(u64::BITS - micros.leading_zeros()) as usize] += 1;
+        self.count += 1;
+        self.max = self.max.max(micros);
+    }
+
+    /// Returns an upper bound of the timing at a given quantile in `0.0..=1.0`.
+    fn percentile(&self, quantile: f64) -> u64 {
+        let rank = ((quantile * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let upper = if bucket == 0 { 0 } else { u64::MAX >> (BUCKETS - 1 - bucket) };
+                return upper.min(self.max);
+            }
+        }
+        self.max
+    }
+
+    fn profile(&self) -> HookProfile {
+        HookProfile {
+            count: self.count,
+            p50_us: self.percentile(0.5),
+            p90_us: self.percentile(0.9),
+            p99_us: self.percentile(0.99),
+            max_us: self.max,
+        }
+    }
+}
+
+/// Recorder of a plugin's hook timings, set on load if profiling is enabled.
+#[derive(Debug, Default)]
+pub(crate) struct PluginProfiler {
+    hooks: Mutex<BTreeMap<PluginHook, Histogram>>,
+}
+
+impl PluginProfiler {
+    /// Records a time a call of the hook took.
+    pub(crate) fn record(&self, hook: PluginHook, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.hooks.lock().unwrap().entry(hook).or_default().record(micros);
+    }
+
+    /// Returns percentiles of recorded timings.
+    pub(crate) fn profile(&self) -> PluginProfile {
+        let hooks = self.hooks.lock().unwrap();
+        PluginProfile {
+            hooks: hooks.iter().map(|(hook, histogram)| (*hook, histogram.profile())).collect(),
+        }
+    }
+}
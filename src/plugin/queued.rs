@@ -0,0 +1,582 @@
+//! Reusable decorator plugin with a dedicated notifications queue
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    ops::RangeInclusive,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use eyre::Result;
+use reth::primitives::BlockNumHash;
+use reth_exex::ExExNotification;
+use reth_tracing::tracing::{error, warn, Instrument, Level};
+use tokio::sync::{
+    mpsc::{self, error::TrySendError},
+    oneshot, Notify,
+};
+use tokio_util::sync::CancellationToken;
+
+use super::plugin_span;
+use crate::{
+    ExExPlugin, NodeInfo, PausePolicy, PluginLog, PluginSigner, PluginTasks, ShardedSink,
+    SubscriptionSpec, TxFilter,
+};
+
+/// Behavior of [`QueuedExExPlugin`] on a notification received while its queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueFullPolicy {
+    /// Waits for a free slot, so the backpressure propagates to the manager's dispatch.
+    #[default]
+    Block,
+    /// Drops the notification and fails its dispatch, so the manager counts it as
+    /// the plugin's failure.
+    Drop,
+}
+
+/// A notification waiting in the queue.
+struct QueuedNotification {
+    notification: Arc<ExExNotification>,
+    node_info: NodeInfo,
+    cancel: CancellationToken,
+}
+
+/// An item of the queue: a notification or a hook fired after the preceding notifications.
+enum Queued {
+    Notification(QueuedNotification),
+    Tip(BlockNumHash),
+    Finalized(BlockNumHash),
+}
+
+/// A hook which didn't fit into a full queue, fired once the notifications enqueued before
+/// it are handled.
+#[derive(Debug, Default)]
+struct DeferredHook(Mutex<Option<(u64, BlockNumHash)>>);
+
+impl DeferredHook {
+    /// Defers the hook after a given number of enqueued notifications, replacing an older one.
+    fn set(&self, after: u64, block: BlockNumHash) {
+        *self.0.lock().unwrap() = Some((after, block));
+    }
+
+    /// Takes the hook, if a given number of handled notifications reached it.
+    fn take_due(&self, handled: u64) -> Option<BlockNumHash> {
+        let mut deferred = self.0.lock().unwrap();
+        let (after, block) = (*deferred)?;
+        (after <= handled).then(|| {
+            *deferred = None;
+            block
+        })
+    }
+}
+
+/// Counters of the queue, shared with its draining task.
+#[derive(Debug, Default)]
+struct QueueState {
+    /// Number of notifications queued or being handled.
+    pending: AtomicUsize,
+    /// Number of notifications dropped on a full queue.
+    dropped: AtomicU64,
+    /// Number of queued notifications the wrapped plugin failed to handle.
+    failed: AtomicU64,
+    /// Number of notifications enqueued so far.
+    enqueued: AtomicU64,
+    /// The latest tip, which didn't fit into a full queue.
+    deferred_tip: DeferredHook,
+    /// The latest finalized block, which didn't fit into a full queue.
+    deferred_finalized: DeferredHook,
+    /// Notified once all queued notifications are handled.
+    idle: Notify,
+    /// The block queued notifications are handled up to.
+    processed: Mutex<Option<BlockNumHash>>,
+    /// Whether the wrapped plugin is unloaded by the draining task, once the queue is drained.
+    unloading: AtomicBool,
+}
+
+impl QueueState {
+    /// Counts a notification as pending, moving the processed block below it, if it reverts
+    /// the processed one or the queue was idle.
+    fn start(&self, notification: &ExExNotification) {
+        let idle = self.pending.fetch_add(1, Ordering::AcqRel) == 0;
+        let Some(fork_block) = notification
+            .reverted_chain()
+            .or_else(|| notification.committed_chain())
+            .map(|chain| chain.fork_block())
+        else {
+            return;
+        };
+        let mut processed = self.processed.lock().unwrap();
+        if idle || processed.map_or(true, |processed| processed.number > fork_block.number) {
+            *processed = Some(fork_block);
+        }
+    }
+
+    /// Completes a pending notification, handled or not, moving the processed block past it.
+    fn complete(&self, notification: Option<&ExExNotification>) {
+        if let Some(notification) = notification {
+            let tip = match notification.committed_chain() {
+                Some(committed) => Some(committed.tip().num_hash()),
+                None => notification.reverted_chain().map(|reverted| reverted.fork_block()),
+            };
+            *self.processed.lock().unwrap() = tip;
+        }
+        if self.pending.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+
+    /// Fires the deferred hooks, which a given number of handled notifications reached.
+    fn fire_deferred(&self, plugin: &impl ExExPlugin, handled: u64) {
+        if let Some(tip) = self.deferred_tip.take_due(handled) {
+            plugin.on_tip(tip);
+        }
+        if let Some(block) = self.deferred_finalized.take_due(handled) {
+            plugin.on_finalized(block);
+        }
+    }
+}
+
+/// A plugin which wraps another one and delivers notifications to it through a dedicated
+/// bounded queue, drained by the plugin's own task.
+///
+/// The manager's dispatch only enqueues a notification, so a slow plugin backs up its own
+/// queue instead of delaying other plugins and the manager's `FinishedHeight` event.
+///
+/// # Ordering
+///
+/// Notifications are handled by the wrapped plugin one at a time, in the order they're
+/// dispatched. There's no ordering between plugins: a queued plugin may handle a notification
+/// after other plugins have handled the following ones.
+///
+/// # Backpressure
+///
+/// Once the queue is full, a new notification is handled according to the [`QueueFullPolicy`]:
+/// it either blocks the manager's dispatch until a slot is freed, or is dropped and fails the
+/// dispatch.
+///
+/// # Hooks
+///
+/// [`ExExPlugin::on_tip`] and [`ExExPlugin::on_finalized`] are delivered through the queue
+/// too, so the wrapped plugin sees them after the notifications dispatched before them. When
+/// the queue is full, the latest hook of each kind is held back and fired once these
+/// notifications are handled.
+///
+/// # Delivery
+///
+/// The manager considers a notification handled once it's enqueued, so failures of the wrapped
+/// plugin are only logged and [counted](Self::failed), and don't hold back the finished height
+/// of a [required](ExExPlugin::is_required) plugin. Still, the finished height is held at
+/// the block the queue is [processed](ExExPlugin::processed_height) up to, so notifications
+/// queued on the node's exit are re-delivered after a restart. The wrapped plugin is driven
+/// on the queue's task, so its [`ExExPlugin::is_blocking`] and [`ExExPlugin::worker_threads`]
+/// have no effect.
+///
+/// # Unload
+///
+/// On unload the queue is closed and its task handles the notifications still queued, before
+/// it [unloads](ExExPlugin::on_unload) the wrapped plugin, once the manager dropped
+/// the decorator. An error of the wrapped plugin's unload is only logged.
+///
+/// # Example
+///
+/// ```rust
+/// use reth_exex_plugin::{CachingExExPlugin, QueueFullPolicy, QueuedExExPlugin};
+///
+/// let cache = CachingExExPlugin::new("TxCountExEx", |block, _| block.body.transactions.len());
+/// let plugin = QueuedExExPlugin::new(cache, 64).with_policy(QueueFullPolicy::Drop);
+/// ```
+pub struct QueuedExExPlugin<P> {
+    plugin: Arc<P>,
+    capacity: usize,
+    policy: QueueFullPolicy,
+    tasks: Option<PluginTasks>,
+    /// Sender of the queue, set on load.
+    tx: Option<mpsc::Sender<Queued>>,
+    /// The wrapped plugin as seen by the draining task.
+    shared: Arc<Mutex<Option<Arc<P>>>>,
+    state: Arc<QueueState>,
+    /// Dropped after the wrapped plugin, so the draining task unloads it once it's the only
+    /// holder of the plugin.
+    _released: Option<oneshot::Sender<()>>,
+}
+
+impl<P: ExExPlugin> QueuedExExPlugin<P> {
+    /// Wraps a plugin with a queue of a given capacity, which is at least `1`.
+    pub fn new(plugin: P, capacity: usize) -> Self {
+        Self {
+            plugin: Arc::new(plugin),
+            capacity: capacity.max(1),
+            policy: QueueFullPolicy::default(),
+            tasks: None,
+            tx: None,
+            shared: Arc::default(),
+            state: Arc::default(),
+            _released: None,
+        }
+    }
+
+    /// Sets a behavior on a full queue.
+    pub fn with_policy(mut self, policy: QueueFullPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the wrapped plugin.
+    pub fn inner(&self) -> &P {
+        &self.plugin
+    }
+
+    /// Returns a number of notifications queued or being handled.
+    pub fn pending(&self) -> usize {
+        self.state.pending.load(Ordering::Acquire)
+    }
+
+    /// Returns a number of notifications dropped on a full queue.
+    pub fn dropped(&self) -> u64 {
+        self.state.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns a number of queued notifications the wrapped plugin failed to handle.
+    pub fn failed(&self) -> u64 {
+        self.state.failed.load(Ordering::Relaxed)
+    }
+
+    /// Returns the wrapped plugin for its `&mut self` hooks, which are called while its queue
+    /// isn't drained.
+    fn inner_mut(&mut self) -> Result<&mut P> {
+        Arc::get_mut(&mut self.plugin)
+            .ok_or_else(|| eyre::eyre!("plugin is handling a queued notification"))
+    }
+
+    /// Waits until all queued notifications are handled.
+    async fn wait_idle(&self) {
+        loop {
+            let idle = self.state.idle.notified();
+            if self.pending() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+
+    /// Enqueues a notification according to the [`QueueFullPolicy`].
+    async fn enqueue(
+        &self,
+        notification: Arc<ExExNotification>,
+        node_info: NodeInfo,
+        cancel: CancellationToken,
+    ) -> Result<()> {
+        let Some(tx) = &self.tx else { eyre::bail!("queue isn't started, plugin isn't loaded") };
+        // counted before the send, so the draining task never sees it below zero
+        self.state.start(&notification);
+        let queued = Queued::Notification(QueuedNotification { notification, node_info, cancel });
+        let pending = PendingGuard(&self.state);
+        let res = match self.policy {
+            QueueFullPolicy::Block => {
+                tx.send(queued).await.map_err(|_| eyre::eyre!("queue is closed"))
+            }
+            QueueFullPolicy::Drop => match tx.try_send(queued) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(_)) => {
+                    self.state.dropped.fetch_add(1, Ordering::Relaxed);
+                    warn!(id = self.plugin.id(), capacity = self.capacity, "queue is full");
+                    Err(eyre::eyre!("queue is full, notification is dropped"))
+                }
+                Err(TrySendError::Closed(_)) => Err(eyre::eyre!("queue is closed")),
+            },
+        };
+        if res.is_ok() {
+            self.state.enqueued.fetch_add(1, Ordering::AcqRel);
+            std::mem::forget(pending);
+        }
+        res
+    }
+
+    /// Enqueues a hook behind the enqueued notifications, deferring it on a full queue.
+    fn enqueue_hook(&self, hook: Queued, deferred: &DeferredHook, block: BlockNumHash) {
+        let Some(tx) = &self.tx else { return };
+        if let Err(TrySendError::Full(_)) = tx.try_send(hook) {
+            deferred.set(self.state.enqueued.load(Ordering::Acquire), block);
+        }
+    }
+}
+
+/// Completes a notification counted as pending on drop, unless it's enqueued, e.g. when
+/// a blocked dispatch is dropped.
+struct PendingGuard<'a>(&'a QueueState);
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.complete(None);
+    }
+}
+
+/// Drains the queue into the wrapped plugin, until the queue is closed, and unloads the plugin
+/// once it's released by the decorator, if the decorator is unloaded.
+async fn drain<P: ExExPlugin>(
+    shared: Arc<Mutex<Option<Arc<P>>>>,
+    mut rx: mpsc::Receiver<Queued>,
+    state: Arc<QueueState>,
+    released: oneshot::Receiver<()>,
+) {
+    let mut handled = 0;
+    while let Some(queued) = rx.recv().await {
+        let Some(plugin) = shared.lock().unwrap().clone() else { break };
+        let QueuedNotification { notification, node_info, cancel } = match queued {
+            Queued::Notification(queued) => queued,
+            Queued::Tip(tip) => {
+                plugin.on_tip(tip);
+                state.fire_deferred(plugin.as_ref(), handled);
+                continue;
+            }
+            Queued::Finalized(block) => {
+                plugin.on_finalized(block);
+                state.fire_deferred(plugin.as_ref(), handled);
+                continue;
+            }
+        };
+        let res = match notification.as_ref() {
+            ExExNotification::ChainReorged { old, new } => {
+                plugin
+                    .on_reorg(old.range(), new.range(), notification.clone(), &node_info, cancel)
+                    .await
+            }
+            _ => {
+                plugin
+                    .handle_notification_with_cancellation(notification.clone(), &node_info, cancel)
+                    .await
+            }
+        };
+        if let Err(err) = res {
+            state.failed.fetch_add(1, Ordering::Relaxed);
+            error!(id = plugin.id(), %err, "failed to process queued notification");
+        }
+        handled += 1;
+        state.fire_deferred(plugin.as_ref(), handled);
+        drop(plugin);
+        state.complete(Some(&notification));
+    }
+
+    let Some(mut plugin) = shared.lock().unwrap().take() else { return };
+    if !state.unloading.load(Ordering::Acquire) {
+        return;
+    }
+    // resolves once the decorator is dropped
+    let _ = released.await;
+    match Arc::get_mut(&mut plugin) {
+        Some(plugin) => {
+            if let Err(err) = plugin.on_unload() {
+                error!(id = plugin.id(), %err, "failed to unload queued plugin");
+            }
+        }
+        None => error!(id = plugin.id(), "queued plugin is still referenced, skipped its unload"),
+    }
+}
+
+impl<P: Debug> Debug for QueuedExExPlugin<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueuedExExPlugin")
+            .field("plugin", &self.plugin)
+            .field("capacity", &self.capacity)
+            .field("policy", &self.policy)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: ExExPlugin> ExExPlugin for QueuedExExPlugin<P> {
+    fn id(&self) -> &'static str {
+        self.plugin.id()
+    }
+
+    fn version(&self) -> &'static str {
+        self.plugin.version()
+    }
+
+    fn schema_version(&self) -> u32 {
+        self.plugin.schema_version()
+    }
+
+    fn on_config(&mut self, config: serde_json::Value) -> Result<()> {
+        self.inner_mut()?.on_config(config)
+    }
+
+    fn redact_config(&self, config: serde_json::Value) -> serde_json::Value {
+        self.plugin.redact_config(config)
+    }
+
+    fn on_runtime(&mut self, tasks: PluginTasks) {
+        if let Ok(plugin) = self.inner_mut() {
+            plugin.on_runtime(tasks.clone());
+        }
+        self.tasks = Some(tasks);
+    }
+
+    fn on_signer(&mut self, signer: PluginSigner) {
+        if let Ok(plugin) = self.inner_mut() {
+            plugin.on_signer(signer)
+        }
+    }
+
+    fn on_log(&mut self, log: PluginLog) {
+        if let Ok(plugin) = self.inner_mut() {
+            plugin.on_log(log)
+        }
+    }
+
+    fn on_sink(&mut self, sink: ShardedSink) {
+        if let Ok(plugin) = self.inner_mut() {
+            plugin.on_sink(sink)
+        }
+    }
+
+    fn on_load<'a: 'b, 'b>(&'a mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            self.inner_mut()?.on_load().await?;
+
+            let (tx, rx) = mpsc::channel(self.capacity);
+            let (released_tx, released) = oneshot::channel();
+            *self.shared.lock().unwrap() = Some(self.plugin.clone());
+            let span = plugin_span(self.plugin.id(), Level::INFO);
+            let drain =
+                drain(self.shared.clone(), rx, self.state.clone(), released).instrument(span);
+            match &self.tasks {
+                // tracked, so the plugin's library is kept open, but not aborted on unload
+                Some(tasks) => drop(tasks.tracker().spawn_on(drain, tasks.handle())),
+                None => drop(tokio::spawn(drain)),
+            }
+            self.tx = Some(tx);
+            self._released = Some(released_tx);
+            Ok(())
+        })
+    }
+
+    fn on_load_failed(&mut self) {
+        if let Ok(plugin) = self.inner_mut() {
+            plugin.on_load_failed()
+        }
+    }
+
+    fn on_unload(&mut self) -> Result<()> {
+        // the draining task unloads the plugin, once the closed queue is drained
+        if let Some(tx) = self.tx.take() {
+            self.state.unloading.store(true, Ordering::Release);
+            drop(tx);
+            return Ok(());
+        }
+        self.inner_mut()?.on_unload()
+    }
+
+    fn health(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        self.plugin.health()
+    }
+
+    fn flush(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            self.wait_idle().await;
+            self.plugin.flush().await
+        })
+    }
+
+    fn warmup(&self) -> u64 {
+        self.plugin.warmup()
+    }
+
+    fn is_required(&self) -> bool {
+        self.plugin.is_required()
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.plugin.is_idempotent()
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        self.plugin.depends_on()
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        self.plugin.capabilities()
+    }
+
+    fn required_node_rpc_namespaces(&self) -> &'static [&'static str] {
+        self.plugin.required_node_rpc_namespaces()
+    }
+
+    fn last_processed(&self) -> Option<u64> {
+        self.plugin.last_processed()
+    }
+
+    fn processed_height(&self) -> Option<BlockNumHash> {
+        let processed = *self.state.processed.lock().unwrap();
+        let queued = if self.pending() > 0 { processed } else { None };
+        // the lower of the queue's and the wrapped plugin's own heights
+        match (queued, self.plugin.processed_height()) {
+            (Some(queued), Some(own)) if own.number < queued.number => Some(own),
+            (queued, own) => queued.or(own),
+        }
+    }
+
+    fn reconfigure(&self, config: serde_json::Value) -> Result<()> {
+        self.plugin.reconfigure(config)
+    }
+
+    fn command(
+        &self,
+        command: String,
+        params: serde_json::Value,
+    ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value>> + Send + '_>> {
+        self.plugin.command(command, params)
+    }
+
+    fn transaction_filter(&self) -> Option<TxFilter> {
+        self.plugin.transaction_filter()
+    }
+
+    fn subscription(&self) -> SubscriptionSpec {
+        self.plugin.subscription()
+    }
+
+    fn pause_policy(&self) -> PausePolicy {
+        self.plugin.pause_policy()
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(self.enqueue(notification, *node_info, CancellationToken::new()))
+    }
+
+    fn handle_notification_with_cancellation<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+        cancel: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        Box::pin(async move { self.enqueue(notification, *node_info, cancel).await.map(|_| None) })
+    }
+
+    fn on_tip(&self, tip: BlockNumHash) {
+        self.enqueue_hook(Queued::Tip(tip), &self.state.deferred_tip, tip)
+    }
+
+    fn on_finalized(&self, block: BlockNumHash) {
+        self.enqueue_hook(Queued::Finalized(block), &self.state.deferred_finalized, block)
+    }
+
+    fn on_reorg<'a: 'b, 'b>(
+        &'a self,
+        _reverted: RangeInclusive<u64>,
+        _committed: RangeInclusive<u64>,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+        cancel: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        Box::pin(async move { self.enqueue(notification, *node_info, cancel).await.map(|_| None) })
+    }
+}
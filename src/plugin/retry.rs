@@ -0,0 +1,357 @@
+//! Reusable retry decorator plugin
+
+use std::{fmt::Debug, future::Future, ops::RangeInclusive, pin::Pin, sync::Arc, time::Duration};
+
+use eyre::Result;
+use reth::primitives::BlockNumHash;
+use reth_exex::ExExNotification;
+use reth_tracing::tracing::warn;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    ExExPlugin, NodeInfo, PausePolicy, PluginLog, PluginSigner, PluginTasks, ShardedSink,
+    SubscriptionSpec, TxFilter,
+};
+
+/// Predicate of errors which are retried.
+type RetryableFn = dyn Fn(&eyre::Report) -> bool + Send + Sync;
+
+/// Bounded retry policy of a failed attempt with an exponential backoff.
+///
+/// See [`RetryExExPlugin`] and [`WebhookConfig`](crate::WebhookConfig).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries of a single attempt, after which its error is returned.
+    pub max_retries: u32,
+    /// Backoff before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff, which is doubled on every retry.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns a backoff before the retry with a given number of `retries` already done,
+    /// or `None` if retries are exhausted.
+    pub fn backoff(&self, retries: u32) -> Option<Duration> {
+        if retries >= self.max_retries {
+            return None;
+        }
+
+        let factor = 2u32.checked_pow(retries).unwrap_or(u32::MAX);
+        Some(self.initial_backoff.saturating_mul(factor).min(self.max_backoff))
+    }
+}
+
+/// A plugin which wraps another one and retries its failed notification handlers with
+/// an exponential backoff.
+///
+/// Only errors matching the [retryable](Self::with_retryable) predicate are retried, by default
+/// the ones caused by an IO error. Other errors, and the last error once retries are exhausted,
+/// are returned to the manager as is. Retrying stops early when the plugin is cancelled.
+///
+/// All other methods are forwarded to the wrapped plugin, so the decorator is registered
+/// like any plugin, e.g. with [`register_plugin`](crate::ExExPluginManager::register_plugin).
+///
+/// # Idempotency
+///
+/// A notification is re-delivered to the wrapped plugin in full, so its handler must tolerate
+/// partially applied output of a failed attempt.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use reth_exex_plugin::{CachingExExPlugin, RetryExExPlugin, RetryPolicy};
+///
+/// let cache = CachingExExPlugin::new("TxCountExEx", |block, _| block.body.transactions.len());
+/// let plugin = RetryExExPlugin::new(cache)
+///     .with_policy(RetryPolicy {
+///         max_retries: 3,
+///         initial_backoff: Duration::from_millis(100),
+///         max_backoff: Duration::from_secs(1),
+///     })
+///     .with_retryable(|err| err.to_string().contains("timeout"));
+/// ```
+pub struct RetryExExPlugin<P> {
+    plugin: P,
+    policy: RetryPolicy,
+    retryable: Box<RetryableFn>,
+}
+
+impl<P: ExExPlugin> RetryExExPlugin<P> {
+    /// Wraps a plugin with the [default](RetryPolicy::default) policy, retrying IO errors.
+    pub fn new(plugin: P) -> Self {
+        Self {
+            plugin,
+            policy: RetryPolicy::default(),
+            retryable: Box::new(|err| {
+                err.chain().any(|cause| cause.downcast_ref::<std::io::Error>().is_some())
+            }),
+        }
+    }
+
+    /// Sets a policy of retries of a single notification.
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets a predicate of errors which are retried.
+    pub fn with_retryable(
+        mut self,
+        retryable: impl Fn(&eyre::Report) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retryable = Box::new(retryable);
+        self
+    }
+
+    /// Returns the wrapped plugin.
+    pub fn inner(&self) -> &P {
+        &self.plugin
+    }
+
+    /// Runs an attempt until it succeeds, fails with a non-retryable error, retries are
+    /// exhausted or a given token is cancelled.
+    async fn retry<'a, T, F>(
+        &'a self,
+        cancel: Option<&CancellationToken>,
+        mut attempt: impl FnMut() -> F,
+    ) -> Result<T>
+    where
+        F: Future<Output = Result<T>> + Send + 'a,
+    {
+        let mut retries = 0;
+        loop {
+            let err = match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            let Some(backoff) = self.policy.backoff(retries).filter(|_| (self.retryable)(&err))
+            else {
+                return Err(err);
+            };
+
+            retries += 1;
+            warn!(id = self.plugin.id(), %err, ?backoff, retries, "retrying failed notification");
+            match cancel {
+                Some(cancel) => tokio::select! {
+                    _ = cancel.cancelled() => return Err(err),
+                    _ = tokio::time::sleep(backoff) => {}
+                },
+                None => tokio::time::sleep(backoff).await,
+            }
+        }
+    }
+}
+
+impl<P: Debug> Debug for RetryExExPlugin<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryExExPlugin")
+            .field("plugin", &self.plugin)
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: ExExPlugin> ExExPlugin for RetryExExPlugin<P> {
+    fn id(&self) -> &'static str {
+        self.plugin.id()
+    }
+
+    fn version(&self) -> &'static str {
+        self.plugin.version()
+    }
+
+    fn schema_version(&self) -> u32 {
+        self.plugin.schema_version()
+    }
+
+    fn on_config(&mut self, config: serde_json::Value) -> Result<()> {
+        self.plugin.on_config(config)
+    }
+
+    fn redact_config(&self, config: serde_json::Value) -> serde_json::Value {
+        self.plugin.redact_config(config)
+    }
+
+    fn on_runtime(&mut self, tasks: PluginTasks) {
+        self.plugin.on_runtime(tasks)
+    }
+
+    fn on_signer(&mut self, signer: PluginSigner) {
+        self.plugin.on_signer(signer)
+    }
+
+    fn on_log(&mut self, log: PluginLog) {
+        self.plugin.on_log(log)
+    }
+
+    fn on_sink(&mut self, sink: ShardedSink) {
+        self.plugin.on_sink(sink)
+    }
+
+    fn on_load<'a: 'b, 'b>(&'a mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        self.plugin.on_load()
+    }
+
+    fn on_load_failed(&mut self) {
+        self.plugin.on_load_failed()
+    }
+
+    fn on_unload(&mut self) -> Result<()> {
+        self.plugin.on_unload()
+    }
+
+    fn health(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        self.plugin.health()
+    }
+
+    fn flush(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        self.plugin.flush()
+    }
+
+    fn warmup(&self) -> u64 {
+        self.plugin.warmup()
+    }
+
+    fn is_required(&self) -> bool {
+        self.plugin.is_required()
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.plugin.is_idempotent()
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        self.plugin.depends_on()
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        self.plugin.capabilities()
+    }
+
+    fn required_node_rpc_namespaces(&self) -> &'static [&'static str] {
+        self.plugin.required_node_rpc_namespaces()
+    }
+
+    fn is_blocking(&self) -> bool {
+        self.plugin.is_blocking()
+    }
+
+    fn worker_threads(&self) -> Option<usize> {
+        self.plugin.worker_threads()
+    }
+
+    fn last_processed(&self) -> Option<u64> {
+        self.plugin.last_processed()
+    }
+
+    fn processed_height(&self) -> Option<BlockNumHash> {
+        self.plugin.processed_height()
+    }
+
+    fn reconfigure(&self, config: serde_json::Value) -> Result<()> {
+        self.plugin.reconfigure(config)
+    }
+
+    fn command(
+        &self,
+        command: String,
+        params: serde_json::Value,
+    ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value>> + Send + '_>> {
+        self.plugin.command(command, params)
+    }
+
+    fn transaction_filter(&self) -> Option<TxFilter> {
+        self.plugin.transaction_filter()
+    }
+
+    fn subscription(&self) -> SubscriptionSpec {
+        self.plugin.subscription()
+    }
+
+    fn pause_policy(&self) -> PausePolicy {
+        self.plugin.pause_policy()
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(
+            self.retry(None, move || {
+                self.plugin.handle_notification(notification.clone(), node_info)
+            }),
+        )
+    }
+
+    fn handle_notification_with_result<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        Box::pin(self.retry(None, move || {
+            self.plugin.handle_notification_with_result(notification.clone(), node_info)
+        }))
+    }
+
+    fn handle_notification_with_cancellation<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+        cancel: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        Box::pin(async move {
+            self.retry(Some(&cancel), || {
+                self.plugin.handle_notification_with_cancellation(
+                    notification.clone(),
+                    node_info,
+                    cancel.clone(),
+                )
+            })
+            .await
+        })
+    }
+
+    fn on_tip(&self, tip: BlockNumHash) {
+        self.plugin.on_tip(tip)
+    }
+
+    fn on_finalized(&self, block: BlockNumHash) {
+        self.plugin.on_finalized(block)
+    }
+
+    fn on_reorg<'a: 'b, 'b>(
+        &'a self,
+        reverted: RangeInclusive<u64>,
+        committed: RangeInclusive<u64>,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+        cancel: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        Box::pin(async move {
+            self.retry(Some(&cancel), || {
+                self.plugin.on_reorg(
+                    reverted.clone(),
+                    committed.clone(),
+                    notification.clone(),
+                    node_info,
+                    cancel.clone(),
+                )
+            })
+            .await
+        })
+    }
+}
@@ -0,0 +1,42 @@
+//! Signing of plugin outputs with the manager's key
+
+use std::{fmt, sync::Arc};
+
+use ed25519_dalek::{Signer, SigningKey};
+
+use reth::primitives::B256;
+
+/// Handle to the manager's ed25519 signing key, passed by [`ExExPlugin::on_signer`] hook,
+/// e.g. to sign records a plugin publishes to external consumers.
+///
+/// The secret key is held by the manager and never exposed to plugins, consumers verify
+/// signatures against the [public key](Self::public_key), served by `exex_serverInfo` RPC.
+///
+/// [`ExExPlugin::on_signer`]: crate::ExExPlugin::on_signer
+#[derive(Clone)]
+pub struct PluginSigner {
+    key: Arc<SigningKey>,
+}
+
+impl PluginSigner {
+    /// Signer of a given 32 bytes ed25519 secret key.
+    pub fn new(secret: [u8; 32]) -> Self {
+        Self { key: Arc::new(SigningKey::from_bytes(&secret)) }
+    }
+
+    /// Signs a record, returning a 64 bytes ed25519 signature.
+    pub fn sign(&self, record: &[u8]) -> [u8; 64] {
+        self.key.sign(record).to_bytes()
+    }
+
+    /// Public key of the signer.
+    pub fn public_key(&self) -> B256 {
+        B256::from(self.key.verifying_key().to_bytes())
+    }
+}
+
+impl fmt::Debug for PluginSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PluginSigner").field("public_key", &self.public_key()).finish()
+    }
+}
@@ -0,0 +1,153 @@
+//! Plugin output sharded into files by block range
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use reth_exex::ExExNotification;
+
+/// Default number of blocks per shard of a [`ShardedSink`].
+pub const DEFAULT_SHARD_SIZE: u64 = 100_000;
+
+/// Output of a plugin, sharded into JSONL files by block range, passed by
+/// [`ExExPlugin::on_sink`] hook, e.g. for plugins writing large datasets.
+///
+/// Records of blocks `start..=end` of a shard go to `{start}-{end}.jsonl` in the plugin's
+/// directory, with zero-padded numbers, so shards sort by block range. Downstream consumers
+/// process complete shards in parallel and [prune](Self::prune) old ones.
+///
+/// Each line is `{"block": <number>, "record": <record>}`. Records must be written in
+/// the order of their blocks, so a revert [truncates](Self::truncate) the affected shard.
+/// The manager does that before dispatching a notification with reverted blocks,
+/// i.e. a revert or a reorg.
+///
+/// [`ExExPlugin::on_sink`]: crate::ExExPlugin::on_sink
+#[derive(Debug, Clone)]
+pub struct ShardedSink {
+    dir: PathBuf,
+    shard_size: u64,
+}
+
+/// Line of a shard.
+#[derive(Serialize)]
+struct ShardLine<'a, T> {
+    block: u64,
+    record: &'a T,
+}
+
+/// Block of a shard's line, ignoring its record.
+#[derive(Deserialize)]
+struct ShardLineBlock {
+    block: u64,
+}
+
+impl ShardedSink {
+    /// Sink writing to a given directory, with a given number of blocks per shard.
+    ///
+    /// The directory is created on the first write.
+    pub fn new(dir: impl Into<PathBuf>, shard_size: u64) -> Self {
+        Self { dir: dir.into(), shard_size: shard_size.max(1) }
+    }
+
+    /// Directory shards are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the shard a given block belongs to.
+    pub fn shard_path(&self, block: u64) -> PathBuf {
+        let start = block - block % self.shard_size;
+        let end = start.saturating_add(self.shard_size - 1);
+        self.dir.join(format!("{start:012}-{end:012}.jsonl"))
+    }
+
+    /// Appends a record of a given block to its shard.
+    pub fn write(&self, block: u64, record: &impl Serialize) -> Result<()> {
+        let mut line = serde_json::to_vec(&ShardLine { block, record })?;
+        line.push(b'\n');
+
+        fs::create_dir_all(&self.dir)?;
+        let mut shard =
+            OpenOptions::new().create(true).append(true).open(self.shard_path(block))?;
+        shard.write_all(&line)?;
+        Ok(())
+    }
+
+    /// Removes records of blocks from a given one onwards: truncates the shard it belongs to
+    /// and removes all later shards.
+    pub fn truncate(&self, from: u64) -> Result<()> {
+        let start = from - from % self.shard_size;
+        for (shard_start, path) in self.shards()? {
+            if shard_start > start {
+                fs::remove_file(path)?;
+            }
+        }
+
+        let path = self.shard_path(from);
+        let Ok(contents) = fs::read(&path) else { return Ok(()) };
+        let mut len = 0;
+        for line in contents.split_inclusive(|byte| *byte == b'\n') {
+            // a malformed line, e.g. partially written, is truncated too
+            let block =
+                serde_json::from_slice::<ShardLineBlock>(line).map_or(u64::MAX, |line| line.block);
+            if block >= from {
+                break;
+            }
+            len += line.len();
+        }
+        if len == 0 {
+            fs::remove_file(path)?;
+        } else {
+            OpenOptions::new().write(true).open(path)?.set_len(len as u64)?;
+        }
+        Ok(())
+    }
+
+    /// Removes shards of blocks entirely below a given one, returning their paths.
+    pub fn prune(&self, below: u64) -> Result<Vec<PathBuf>> {
+        let mut pruned = Vec::new();
+        for (start, path) in self.shards()? {
+            if start.saturating_add(self.shard_size) <= below {
+                fs::remove_file(&path)?;
+                pruned.push(path);
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Truncates records of reverted blocks of a notification, if any.
+    pub(crate) fn truncate_reverted(&self, notification: &ExExNotification) -> Result<()> {
+        match notification.reverted_chain() {
+            Some(reverted) => self.truncate(reverted.first().number),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns shards in the directory by their first block.
+    fn shards(&self) -> Result<Vec<(u64, PathBuf)>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut shards = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            let start = path
+                .file_name()
+                .and_then(|name| name.to_str()?.strip_suffix(".jsonl"))
+                .and_then(|range| range.split_once('-')?.0.parse().ok());
+            if let Some(start) = start {
+                shards.push((start, path));
+            }
+        }
+        shards.sort();
+        Ok(shards)
+    }
+}
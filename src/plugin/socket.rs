@@ -0,0 +1,104 @@
+//! Built-in ExEx plugin which streams notifications to an external process over a Unix socket.
+
+use std::{future::Future, path::PathBuf, pin::Pin, sync::Arc};
+
+use eyre::Result;
+use tokio::{io::AsyncWriteExt, net::UnixStream, sync::Mutex};
+
+use reth_exex::ExExNotification;
+use reth_tracing::tracing::{debug, warn};
+
+use crate::{ExExPlugin, NodeInfo, NormalizedNotification};
+
+/// Default id of the [`SocketExExPlugin`].
+pub const SOCKET_EXEX_PLUGIN_ID: &str = "SocketExEx";
+
+/// ExEx plugin which writes every [normalized](NormalizedNotification) notification
+/// into a Unix domain socket, so a sidecar process can consume them without
+/// implementing a dynamic library plugin.
+///
+/// Each notification is written as a frame: big-endian `u32` payload length followed by
+/// the JSON payload itself.
+///
+/// The connection is established lazily on the first notification. If the socket peer
+/// disconnects, the plugin reconnects and retries the write once, before returning an error.
+///
+/// Registered on manager via [`ExExPluginManager::register_plugin`].
+///
+/// [`ExExPluginManager::register_plugin`]: crate::ExExPluginManager::register_plugin
+#[derive(Debug)]
+pub struct SocketExExPlugin {
+    id: &'static str,
+    socket_path: PathBuf,
+    stream: Mutex<Option<UnixStream>>,
+}
+
+impl SocketExExPlugin {
+    pub fn new(socket_path: impl Into<PathBuf>) -> Self {
+        Self {
+            id: SOCKET_EXEX_PLUGIN_ID,
+            socket_path: socket_path.into(),
+            stream: Mutex::default(),
+        }
+    }
+
+    /// Overrides the plugin id, e.g. to register several socket plugins on the same manager.
+    pub fn with_id(mut self, id: &'static str) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Writes a frame into the socket, reconnecting if the connection is absent or dropped.
+    async fn write_frame(&self, frame: &[u8]) -> Result<()> {
+        let mut stream = self.stream.lock().await;
+
+        if let Some(conn) = stream.as_mut() {
+            match conn.write_all(frame).await {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    warn!(id = %self.id, %err, "socket peer disconnected, reconnecting");
+                    *stream = None;
+                }
+            }
+        }
+
+        let mut conn = UnixStream::connect(&self.socket_path).await.map_err(|err| {
+            eyre::format_err!("Failed to connect to {:?}: {err:?}", self.socket_path)
+        })?;
+        debug!(id = %self.id, path = ?self.socket_path, "connected to socket");
+
+        conn.write_all(frame).await?;
+        *stream = Some(conn);
+
+        Ok(())
+    }
+}
+
+impl ExExPlugin for SocketExExPlugin {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn on_unload(&mut self) -> Result<()> {
+        // close the connection, so the peer observes EOF
+        self.stream.get_mut().take();
+        Ok(())
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let payload = serde_json::to_vec(&NormalizedNotification::from(&*notification))?;
+            let len = u32::try_from(payload.len())?;
+
+            let mut frame = Vec::with_capacity(4 + payload.len());
+            frame.extend_from_slice(&len.to_be_bytes());
+            frame.extend_from_slice(&payload);
+
+            self.write_frame(&frame).await
+        })
+    }
+}
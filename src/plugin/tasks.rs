@@ -0,0 +1,72 @@
+//! Background tasks of a plugin
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    runtime::Handle,
+    task::{AbortHandle, JoinHandle},
+};
+use tokio_util::task::TaskTracker;
+
+/// Access of a plugin to the node's runtime, passed by [`ExExPlugin::on_runtime`] hook.
+///
+/// Tasks spawned with [`Self::spawn`] are tracked by the plugin's [`TaskTracker`]
+/// and aborted when the plugin is unloaded.
+///
+/// [`ExExPlugin::on_runtime`]: crate::ExExPlugin::on_runtime
+#[derive(Debug, Clone)]
+pub struct PluginTasks {
+    handle: Handle,
+    tracker: TaskTracker,
+    aborts: Arc<Mutex<Vec<AbortHandle>>>,
+}
+
+impl PluginTasks {
+    pub(crate) fn new(handle: Handle) -> Self {
+        Self { handle, tracker: TaskTracker::new(), aborts: Arc::default() }
+    }
+
+    /// Handle of the node's runtime.
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// Tracker of the plugin's tasks.
+    pub fn tracker(&self) -> &TaskTracker {
+        &self.tracker
+    }
+
+    /// Spawns a task on the node's runtime, which is aborted on the plugin's unload.
+    pub fn spawn<F>(&self, task: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let handle = self.tracker.spawn_on(task, &self.handle);
+        let mut aborts = self.aborts.lock().unwrap();
+        aborts.retain(|abort| !abort.is_finished());
+        aborts.push(handle.abort_handle());
+        handle
+    }
+
+    /// Returns a number of the plugin's running tasks.
+    pub fn len(&self) -> usize {
+        self.tracker.len()
+    }
+
+    /// Returns `true` if the plugin has no running tasks.
+    pub fn is_empty(&self) -> bool {
+        self.tracker.is_empty()
+    }
+
+    /// Aborts all tasks of the plugin and closes its tracker.
+    pub(crate) fn abort_all(&self) {
+        self.tracker.close();
+        for abort in self.aborts.lock().unwrap().drain(..) {
+            abort.abort();
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Dedicated OS thread of a plugin, see
+//! [`ExExPluginManager::with_plugin_threads`](crate::ExExPluginManager::with_plugin_threads).
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+};
+
+use eyre::Result;
+use tokio::sync::oneshot;
+
+use crate::supervisor::panic_message;
+
+/// Job run on a plugin's thread.
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Dedicated OS thread, which runs jobs of a single plugin one at a time, received over
+/// a single-consumer channel.
+///
+/// The thread exits once the plugin is dropped, closing the channel.
+#[derive(Debug)]
+pub(crate) struct PluginThread {
+    tx: mpsc::Sender<Job>,
+}
+
+impl PluginThread {
+    /// Spawns a thread named `exex-plugin-{id}`.
+    pub(crate) fn spawn(id: &str) -> Result<Self> {
+        let (tx, rx) = mpsc::channel::<Job>();
+        std::thread::Builder::new().name(format!("exex-plugin-{id}")).spawn(move || {
+            for job in rx {
+                job();
+            }
+        })?;
+        Ok(Self { tx })
+    }
+
+    /// Runs a job on the thread, awaiting its ack of completion without blocking the caller.
+    ///
+    /// The job is owned by the thread, so it runs to completion even if the returned future is
+    /// dropped, and its result is discarded then. A panic of the job is caught, so the thread
+    /// survives it, and returned as an error with the panic's message.
+    pub(crate) async fn run<T: Send + 'static>(
+        &self,
+        job: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T, String> {
+        let (ack_tx, ack_rx) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            let res = panic::catch_unwind(AssertUnwindSafe(job))
+                .map_err(|panic| panic_message(&*panic).to_owned());
+            let _ = ack_tx.send(res);
+        });
+        self.tx.send(job).map_err(|_| "plugin thread exited".to_owned())?;
+        ack_rx.await.unwrap_or_else(|_| Err("plugin thread exited".to_owned()))
+    }
+}
@@ -1,23 +1,33 @@
 //! ExEx plugin interface
 
-use std::{borrow::Borrow, fmt::Debug, future::Future, hash::Hash, pin::Pin};
+use std::{
+    borrow::Borrow, fmt::Debug, future::Future, hash::Hash, ops::RangeInclusive, pin::Pin,
+    sync::Arc,
+};
 
 use eyre::Result;
+use tokio_util::sync::CancellationToken;
 
+use reth::primitives::BlockNumHash;
 use reth_exex::ExExNotification;
 
+use crate::{
+    NodeInfo, PausePolicy, PluginLog, PluginSigner, PluginTasks, ShardedSink, SubscriptionSpec,
+    TxFilter,
+};
+
 /// Required name of the plugin contrusctor function.
 pub const EXEX_MANAGER_CONSTRUCTOR_FN_NAME: &[u8] = b"__create_exex_plugin";
 
 /// ExEx plugin trait.
 /// # Example - Declare ExEx Plugin
 /// ```rust
-/// use std::{future::Future, pin::Pin};
+/// use std::{future::Future, pin::Pin, sync::Arc};
 ///
 /// use eyre::Result;
 ///
 /// use reth_exex::ExExNotification;
-/// use reth_exex_plugin::ExExPlugin;
+/// use reth_exex_plugin::{ExExPlugin, NodeInfo};
 ///
 /// #[derive(Debug, Default)]
 /// struct MinimalExEx;
@@ -29,7 +39,8 @@ pub const EXEX_MANAGER_CONSTRUCTOR_FN_NAME: &[u8] = b"__create_exex_plugin";
 ///
 ///     fn handle_notification<'a: 'b, 'b>(
 ///         &'a self,
-///         notification: &'a ExExNotification,
+///         notification: Arc<ExExNotification>,
+///         node_info: &'a NodeInfo,
 ///     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
 ///         Box::pin(async { Ok(()) })
 ///     }
@@ -45,6 +56,65 @@ pub trait ExExPlugin: Debug + Send + Sync + 'static {
         env!("CARGO_PKG_VERSION")
     }
 
+    /// Version of the plugin's storage schema, `0` by default.
+    ///
+    /// A DB backed plugin bumps it on a storage migration, which it runs in [`Self::on_load`].
+    /// The manager warns when a reloaded plugin's schema version differs from the one of
+    /// its previous instance, so operators are aware of the migration.
+    fn schema_version(&self) -> u32 {
+        0
+    }
+
+    /// A hook fired before [`Self::on_load`] with a config the plugin is loaded with,
+    /// e.g. by `exex_loadPlugin` RPC.
+    ///
+    /// Not called if the plugin is loaded without a config. Returns an error by default,
+    /// so a config isn't silently ignored by a plugin which doesn't accept one.
+    fn on_config(&mut self, _config: serde_json::Value) -> Result<()> {
+        eyre::bail!("plugin doesn't accept a config")
+    }
+
+    /// Applies a new config to the running plugin, e.g. by `exex_reconfigurePlugin` RPC.
+    ///
+    /// Unlike [`Self::on_config`] on a reload, the plugin isn't reconstructed, so it keeps its
+    /// in-memory state. Takes `&self`, so the plugin keeps its reconfigurable settings behind
+    /// interior mutability. Returns an error by default, as reconfiguration is unsupported.
+    fn reconfigure(&self, _config: serde_json::Value) -> Result<()> {
+        eyre::bail!("reconfigure unsupported")
+    }
+
+    /// Redacts secrets, e.g. credentials, of the plugin's config before it's returned by
+    /// `exex_pluginConfig` RPC.
+    ///
+    /// Returns the config as is by default.
+    fn redact_config(&self, config: serde_json::Value) -> serde_json::Value {
+        config
+    }
+
+    /// A hook fired before [`Self::on_load`], which passes the plugin access to the node's
+    /// runtime for spawning background tasks.
+    ///
+    /// Tasks spawned with [`PluginTasks::spawn`] are aborted when the plugin is unloaded,
+    /// so the plugin shouldn't create its own runtime.
+    fn on_runtime(&mut self, _tasks: PluginTasks) {}
+
+    /// A hook fired before [`Self::on_load`], which passes the plugin a handle to
+    /// the manager's signing key, e.g. to sign its outputs.
+    ///
+    /// Not called if the manager has no [signing key](crate::ExExPluginManager::with_signing_key).
+    fn on_signer(&mut self, _signer: PluginSigner) {}
+
+    /// A hook fired before [`Self::on_load`], which passes the plugin its dedicated log
+    /// output, kept apart from the node's logs, e.g. for targeted debugging over RPC.
+    fn on_log(&mut self, _log: PluginLog) {}
+
+    /// A hook fired before [`Self::on_load`], which passes the plugin its output sharded into
+    /// files by block range, e.g. for writing large datasets.
+    ///
+    /// Not called if the manager has no [sharded
+    /// output](crate::ExExPluginManager::with_sharded_output).
+    fn on_sink(&mut self, _sink: ShardedSink) {}
+
     /// A hook fired immediately after the plugin is loaded by the system.
     ///
     /// Used for any initialization logic.
@@ -52,6 +122,13 @@ pub trait ExExPlugin: Debug + Send + Sync + 'static {
         Box::pin(async { Ok(()) })
     }
 
+    /// A callback fired if [`Self::on_load`] fails, before the plugin is dropped.
+    ///
+    /// Used for rolling back side effects of a partial initialization, e.g. removing
+    /// half-created files or closing opened connections. Unlike [`Self::on_unload`], which
+    /// isn't called for a plugin failed to load.
+    fn on_load_failed(&mut self) {}
+
     /// A callback fired immediately before the plugin is unloaded.
     ///
     /// Used for doing any cleanup before unload.
@@ -59,11 +136,279 @@ pub trait ExExPlugin: Debug + Send + Sync + 'static {
         Ok(())
     }
 
+    /// Health check of the plugin, e.g. of its connections.
+    ///
+    /// Returns an error if the plugin is unhealthy.
+    fn health(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Flushes output the plugin buffers, e.g. batched writes, into its storage.
+    ///
+    /// Called on [drain](crate::ExExPluginManager::drain) of the manager before the node exits.
+    fn flush(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Number of first notifications after load, during which the plugin is still warming up
+    /// (e.g. catching up connections).
+    ///
+    /// Errors of [`Self::handle_notification`] during warmup are logged at `debug` level
+    /// and are not counted as the plugin's failures.
+    fn warmup(&self) -> u64 {
+        0
+    }
+
+    /// Whether the plugin is required.
+    ///
+    /// A failed notification of a required plugin holds back the manager's `FinishedHeight`
+    /// event, so the node doesn't prune data the plugin hasn't processed yet.
+    fn is_required(&self) -> bool {
+        false
+    }
+
+    /// Whether the plugin's processing is idempotent, i.e. safe to replay, e.g. it upserts
+    /// its output by block.
+    ///
+    /// Only affects [required](Self::is_required) plugins. A failed notification of
+    /// a non-idempotent one holds back `FinishedHeight` until its retry succeeds, so the node
+    /// re-delivers the notification after a restart and the plugin sees each block it hasn't
+    /// acknowledged. For an idempotent one the manager advances `FinishedHeight` optimistically
+    /// past the failure, while still retrying the failed and all following notifications
+    /// in order from its [retry queue](crate::ExExPluginManager::with_retry_queue).
+    ///
+    /// # Replay safety
+    ///
+    /// The node doesn't re-deliver blocks below the finished height after a restart, so
+    /// notifications pending a retry when the node exits are lost for the plugin. It relies on
+    /// replaying them itself, e.g. by catching up from its [high-water mark](Self::last_processed)
+    /// on load. A non-idempotent plugin instead receives again blocks it processed while another
+    /// plugin held the height back, unless it reports its high-water mark, so the manager skips
+    /// them. Retried notifications may also overlap with output the plugin partially wrote
+    /// before failing.
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    /// Ids of plugins this plugin depends on.
+    ///
+    /// On [unload of all plugins](crate::ExExPluginManager::unload_all) the plugin is
+    /// unloaded before its dependencies.
+    fn depends_on(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Capabilities the plugin declares, e.g. `"on_reorg"` if it handles reorgs atomically
+    /// with [`Self::on_reorg`].
+    ///
+    /// Used to find plugins for targeted operations, e.g. by `exex_findPluginsByCapability` RPC.
+    fn capabilities(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Namespaces of the node's RPC the plugin calls back into, e.g. `"eth"` or `"debug"`.
+    ///
+    /// The manager refuses to load the plugin, unless all of them are enabled over the node's
+    /// HTTP or WS RPC at startup.
+    fn required_node_rpc_namespaces(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Whether the plugin's [`Self::handle_notification`] blocks the current thread,
+    /// e.g. on synchronous IO.
+    ///
+    /// Handlers of a blocking plugin are run by [`tokio::task::spawn_blocking`], so neither
+    /// the async reactor nor the manager's task is stalled, e.g. read-only RPC requests are
+    /// still answered. The manager still awaits the handler before emitting `FinishedHeight`.
+    ///
+    /// # Constraints
+    ///
+    /// The handler's future is driven on a blocking thread by the runtime's handle, so it
+    /// can't hold non-`Send` references.
+    fn is_blocking(&self) -> bool {
+        false
+    }
+
+    /// Number of threads of a dedicated worker pool for the plugin's CPU-bound handlers,
+    /// e.g. of a plugin re-executing transactions.
+    ///
+    /// If set, the pool is created on the plugin's load, and its handlers are driven on
+    /// the pool's threads (named `exex-plugin-{id}-{index}`), so heavy computation doesn't
+    /// share the node's general pool. Takes precedence over [`Self::is_blocking`].
+    ///
+    /// # Cost
+    ///
+    /// Each thread is a dedicated OS thread with its own stack (2 MiB by default), kept alive
+    /// until the plugin is unloaded. The manager awaits the handler before dispatching to
+    /// the next plugin, so a pool larger than the parallelism of the plugin's own work,
+    /// e.g. with `rayon` iterators, is wasted. Like handlers of [`Self::is_blocking`] plugins,
+    /// the pool's work is awaited off the manager's task.
+    fn worker_threads(&self) -> Option<usize> {
+        None
+    }
+
+    /// The plugin's high-water mark, i.e. the highest block number it has durably processed.
+    ///
+    /// Notifications are delivered at least once: a block processed before the node persisted
+    /// its finished height is re-delivered after a restart. If the mark is reported,
+    /// the manager skips commit notifications whose chain tip is at or below it, which gives
+    /// exactly-once processing of committed blocks, as long as the mark is persisted
+    /// atomically with the plugin's output. Reverts and reorgs are always delivered.
+    fn last_processed(&self) -> Option<u64> {
+        None
+    }
+
+    /// The block the plugin has processed its dispatched notifications up to, while it still
+    /// processes some of them after their handlers returned, e.g. a [`QueuedExExPlugin`].
+    ///
+    /// The manager holds `FinishedHeight` at it, so the node neither prunes nor, after
+    /// a restart, skips blocks the plugin hasn't processed yet. `None`, by default, if the plugin
+    /// has processed all dispatched notifications.
+    ///
+    /// [`QueuedExExPlugin`]: crate::QueuedExExPlugin
+    fn processed_height(&self) -> Option<BlockNumHash> {
+        None
+    }
+
+    /// Handles a plugin specific `command` with arbitrary JSON `params`,
+    /// e.g. invoked by `exex_pluginCommand` RPC.
+    ///
+    /// Gives the plugin an extensible control surface. Returns an error for any command
+    /// by default.
+    fn command(
+        &self,
+        command: String,
+        _params: serde_json::Value,
+    ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value>> + Send + '_>> {
+        Box::pin(async move { eyre::bail!("unsupported command: {command}") })
+    }
+
+    /// Optional filter of transactions the plugin is interested in.
+    ///
+    /// If set, the manager doesn't dispatch commit notifications without any transaction
+    /// matching the filter. Reverts and reorgs are always delivered.
+    ///
+    /// Checking the filter iterates over all transactions and receipt logs of the committed
+    /// chain for every notification, so it's heavier than checks on block ranges.
+    ///
+    /// It's the [filter](SubscriptionSpec::tx_filter) of the plugin's
+    /// [subscription](Self::subscription), unless the spec sets its own.
+    fn transaction_filter(&self) -> Option<TxFilter> {
+        None
+    }
+
+    /// Chain events the plugin subscribes to, all of them by default.
+    ///
+    /// The manager doesn't dispatch notifications not [matching](SubscriptionSpec::matches) the
+    /// spec, which is the single filter of the manager's dispatch, besides the plugin's state:
+    /// its [transaction filter](Self::transaction_filter) is folded in, and its kinds are
+    /// matched together with ones set at runtime.
+    fn subscription(&self) -> SubscriptionSpec {
+        SubscriptionSpec::default()
+    }
+
+    /// Policy of notifications received while the plugin is paused, which are skipped by
+    /// default.
+    ///
+    /// Kept notifications are replayed to the plugin once it's resumed, e.g. by
+    /// `exex_resumePlugin` RPC, before any new ones.
+    fn pause_policy(&self) -> PausePolicy {
+        PausePolicy::Skip
+    }
+
     /// Method to handle received ExEx [notification](ExExNotification).
+    ///
+    /// [`NodeInfo`] describes the node's network and its head after the notification.
+    ///
+    /// The notification is an owned [`Arc`] clone, shared between all plugins. It isn't tied
+    /// to the handler's lifetime, so the plugin can hold it across await points or move it
+    /// into a detached task.
+    ///
+    /// # Ordering
+    ///
+    /// Notifications are delivered to a single plugin strictly in chain order, i.e. the plugin
+    /// sees block `N` before block `N + 1`, one at a time: the next notification is passed only
+    /// once the handler of the previous one completed. Notifications pending a
+    /// [retry](crate::ExExPluginManager::with_retry_queue) or kept during a
+    /// [pause](Self::pause_policy) precede newer ones. The guarantee holds regardless of
+    /// the order plugins are dispatched in, which is unspecified, and of plugins running on
+    /// their own [threads](crate::ExExPluginManager::with_plugin_threads) or
+    /// [queues](crate::QueuedExExPlugin).
     fn handle_notification<'a: 'b, 'b>(
         &'a self,
-        notification: &'a ExExNotification,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>>;
+
+    /// A variant of [`Self::handle_notification`], which optionally returns a result of
+    /// the notification, e.g. a value computed from its blocks.
+    ///
+    /// The manager keeps the latest returned result of the plugin, so it can be queried by
+    /// `exex_pluginLatestResult` RPC. Forwards to [`Self::handle_notification`] without
+    /// a result by default.
+    fn handle_notification_with_result<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        let fut = self.handle_notification(notification, node_info);
+        Box::pin(async move { fut.await.map(|_| None) })
+    }
+
+    /// A variant of [`Self::handle_notification_with_result`], which receives the plugin's
+    /// cancellation token.
+    ///
+    /// # Cooperative cancellation
+    ///
+    /// The token is cancelled when the plugin is unloaded and when the manager drains plugins
+    /// on the node's shutdown, right before the plugin's background tasks are aborted.
+    /// The same token is passed to [`Self::on_reorg`] on reorgs.
+    /// A well-behaved plugin observes it in long work, e.g. in detached tasks, to return early
+    /// and leave its storage consistent, instead of being hard-aborted. The manager never
+    /// cancels the token mid-dispatch, so ignoring it changes nothing. Forwards to
+    /// [`Self::handle_notification_with_result`] by default.
+    fn handle_notification_with_cancellation<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+        _cancel: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        self.handle_notification_with_result(notification, node_info)
+    }
+
+    /// A hook fired after the plugin handled a notification, which commits the node's
+    /// canonical head, as tracked by the node's provider.
+    ///
+    /// Not fired for historical notifications, e.g. ones of the node's backfill, or ones
+    /// retried or replayed after a pause or a background load, below the head. So a plugin
+    /// maintaining a view on the current head doesn't re-derive it from block ranges.
+    fn on_tip(&self, _tip: BlockNumHash) {}
+
+    /// A hook fired once the node's finalized block advances, observed by the manager on
+    /// a notification, after the plugin handled it.
+    ///
+    /// Finalized blocks are never reverted, so it's a safe point for a plugin, which batches
+    /// its writes, to flush them and record a durable checkpoint, e.g. commit a database
+    /// transaction. Not fired while the plugin is paused or its last notification failed.
+    fn on_finalized(&self, _block: BlockNumHash) {}
+
+    /// A hook fired instead of [`Self::handle_notification_with_cancellation`] on a chain reorg,
+    /// i.e. a notification which both reverts and commits blocks.
+    ///
+    /// Allows the plugin to atomically roll back `reverted` blocks and re-apply `committed` ones.
+    /// Like other handlers, it receives the plugin's cancellation token and optionally returns
+    /// a result, kept as the plugin's latest one. Forwards to
+    /// [`Self::handle_notification_with_cancellation`] by default.
+    fn on_reorg<'a: 'b, 'b>(
+        &'a self,
+        _reverted: RangeInclusive<u64>,
+        _committed: RangeInclusive<u64>,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+        cancel: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        self.handle_notification_with_cancellation(notification, node_info, cancel)
+    }
 }
 
 impl Hash for dyn ExExPlugin + '_ {
@@ -83,27 +428,76 @@ impl Borrow<str> for Box<dyn ExExPlugin> {
 /// # Notes
 ///
 /// This works by automatically generating an `extern "C"` function with a
-/// pre-defined signature and symbol name. Therefore you will only be able to
-/// declare one plugin per library.
+/// pre-defined signature and symbol name, [`EXEX_PLUGIN_DESCRIPTOR_FN_NAME`], which returns
+/// the plugin with its load metadata as a [`PluginDescriptor`]. Therefore you will only be
+/// able to declare one plugin per library.
+///
+/// [`EXEX_PLUGIN_DESCRIPTOR_FN_NAME`]: crate::EXEX_PLUGIN_DESCRIPTOR_FN_NAME
+/// [`PluginDescriptor`]: crate::PluginDescriptor
+///
+/// Also exports the `rustc` version and target triple the plugin is built with
+/// (see [`PluginBuild`](crate::PluginBuild)), which the manager checks on load.
 #[macro_export]
 macro_rules! declare_exex_plugin {
+    (@build) => {
+        #[no_mangle]
+        pub static __EXEX_PLUGIN_RUSTC_VERSION: $crate::ExportedStr =
+            $crate::ExportedStr::RUSTC_VERSION;
+
+        #[no_mangle]
+        pub static __EXEX_PLUGIN_TARGET: $crate::ExportedStr = $crate::ExportedStr::TARGET;
+    };
+
     ($plugin_type:ty) => {
+        $crate::declare_exex_plugin!(@build);
+
         #[no_mangle]
-        pub extern "C" fn _create_exex_plugin() -> *mut dyn $crate::ExExPlugin {
-            let boxed: Box<dyn $crate::ExExPlugin> = Box::new(<$plugin_type>::default());
-            Box::into_raw(boxed)
+        pub extern "C" fn __create_exex_plugin_descriptor() -> $crate::PluginDescriptor {
+            $crate::PluginDescriptor::new(Box::new(<$plugin_type>::default()))
         }
     };
 
     ($plugin_type:ty, $constructor:path) => {
+        $crate::declare_exex_plugin!(@build);
+
         #[no_mangle]
-        pub extern "C" fn _create_exex_plugin() -> *mut dyn $crate::ExExPlugin {
+        pub extern "C" fn __create_exex_plugin_descriptor() -> $crate::PluginDescriptor {
             // make sure the constructor is the correct type.
             let constructor: fn() -> $plugin_type = $constructor;
 
-            let object = constructor();
-            let boxed: Box<dyn $crate::ExExPlugin> = Box::new(object);
-            Box::into_raw(boxed)
+            $crate::PluginDescriptor::new(Box::new(constructor()))
         }
     };
 }
+
+/// Declare static metadata of an ExEx plugin, i.e. its id and
+/// [dependencies](crate::ExExPlugin::depends_on), so the manager
+/// [checks](crate::ExExPluginManager::check_dependencies) them without constructing the plugin.
+///
+/// # Notes
+///
+/// The metadata is exported as [`EXEX_PLUGIN_ID_SYMBOL`] and [`EXEX_PLUGIN_DEPENDS_ON_SYMBOL`]
+/// statics, next to the ones of [`declare_exex_plugin!`](crate::declare_exex_plugin), and
+/// must match the plugin's own [`id`](crate::ExExPlugin::id) and
+/// [`depends_on`](crate::ExExPlugin::depends_on).
+///
+/// [`EXEX_PLUGIN_ID_SYMBOL`]: crate::EXEX_PLUGIN_ID_SYMBOL
+/// [`EXEX_PLUGIN_DEPENDS_ON_SYMBOL`]: crate::EXEX_PLUGIN_DEPENDS_ON_SYMBOL
+///
+/// # Example
+///
+/// ```ignore
+/// reth_exex_plugin::declare_exex_plugin_metadata!(id: "MyExEx", depends_on: ["OtherExEx"]);
+/// ```
+#[macro_export]
+macro_rules! declare_exex_plugin_metadata {
+    (id: $id:literal $(, depends_on: [$($dependency:literal),* $(,)?])? $(,)?) => {
+        #[no_mangle]
+        pub static __EXEX_PLUGIN_ID: $crate::ExportedStr =
+            $crate::ExportedStr::new(concat!($id, "\0"));
+
+        #[no_mangle]
+        pub static __EXEX_PLUGIN_DEPENDS_ON: $crate::ExportedStr =
+            $crate::ExportedStr::new(concat!($($($dependency, ",",)*)? "\0"));
+    };
+}
@@ -0,0 +1,365 @@
+//! Built-in ExEx plugin which posts notifications to an HTTP endpoint.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    sync::{
+        mpsc::{self, error::TrySendError},
+        Notify,
+    },
+};
+
+use reth_exex::ExExNotification;
+use reth_tracing::tracing::{debug, error, warn};
+
+use crate::{ExExPlugin, NodeInfo, NormalizedNotification, PluginTasks, RetryPolicy};
+
+/// Default id of the [`WebhookExExPlugin`].
+pub const WEBHOOK_EXEX_PLUGIN_ID: &str = "WebhookExEx";
+
+/// Upper bound of the backoff between retries of a webhook request.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Config of the [`WebhookExExPlugin`], passed as the plugin's config on registration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// URL of the endpoint, only `http` scheme is supported, e.g. `http://127.0.0.1:8080/blocks`.
+    pub url: String,
+    /// Timeout of a single request, in milliseconds.
+    #[serde(default = "WebhookConfig::default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Maximum number of retries of a failed request.
+    #[serde(default = "WebhookConfig::default_max_retries")]
+    pub max_retries: u32,
+    /// Backoff before the first retry, in milliseconds, doubled on every retry.
+    #[serde(default = "WebhookConfig::default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// Maximum number of notifications waiting to be posted.
+    #[serde(default = "WebhookConfig::default_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+impl WebhookConfig {
+    /// Config of a given URL with default timeout and retries.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            timeout_ms: Self::default_timeout_ms(),
+            max_retries: Self::default_max_retries(),
+            initial_backoff_ms: Self::default_initial_backoff_ms(),
+            queue_capacity: Self::default_queue_capacity(),
+        }
+    }
+
+    const fn default_timeout_ms() -> u64 {
+        5_000
+    }
+
+    const fn default_max_retries() -> u32 {
+        3
+    }
+
+    const fn default_initial_backoff_ms() -> u64 {
+        100
+    }
+
+    const fn default_queue_capacity() -> usize {
+        1024
+    }
+
+    /// Policy of retries of a failed request.
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: self.max_retries,
+            initial_backoff: Duration::from_millis(self.initial_backoff_ms),
+            max_backoff: MAX_BACKOFF,
+        }
+    }
+}
+
+/// ExEx plugin which POSTs every [normalized](NormalizedNotification) notification as JSON
+/// to a configured URL, e.g. to notify an external service on each new block without
+/// implementing a dynamic library plugin.
+///
+/// Notifications are posted one at a time by the plugin's background task, from a bounded
+/// queue, so a slow endpoint doesn't delay the manager's dispatch. A notification received
+/// while the queue is full fails like any other plugin's one.
+///
+/// A request failed to connect, timed out or answered with a non-2xx status is retried with
+/// an exponential backoff, see [`WebhookConfig`]. Once retries are exhausted, the notification
+/// is dropped, logged and [counted](WebhookExExPlugin::failed), since its dispatch is already
+/// completed.
+///
+/// Registered on manager like any plugin, with the URL either given on construction or
+/// passed as the plugin's config, e.g. via
+/// [`ExExPluginManager::register_plugin_with_config`].
+///
+/// # Limitations
+///
+/// Requests are sent over plain HTTP/1.1 with a new connection per request, TLS isn't
+/// supported. Notifications still queued on unload are posted before the task exits.
+///
+/// # Example
+///
+/// ```rust
+/// use reth_exex_plugin::{WebhookConfig, WebhookExExPlugin};
+///
+/// let plugin = WebhookExExPlugin::new(WebhookConfig::new("http://127.0.0.1:8080/blocks"));
+/// ```
+///
+/// [`ExExPluginManager::register_plugin_with_config`]: crate::ExExPluginManager::register_plugin_with_config
+#[derive(Debug, Default)]
+pub struct WebhookExExPlugin {
+    id: Option<&'static str>,
+    config: Option<WebhookConfig>,
+    tasks: Option<PluginTasks>,
+    /// Sender of the queue, set on load.
+    tx: Option<mpsc::Sender<Vec<u8>>>,
+    state: Arc<WebhookState>,
+}
+
+/// Counters of the queue, shared with the sending task.
+#[derive(Debug, Default)]
+struct WebhookState {
+    /// Number of notifications queued or being posted.
+    pending: AtomicUsize,
+    /// Number of notifications dropped once retries are exhausted.
+    failed: AtomicU64,
+    /// Notified once all queued notifications are posted.
+    idle: Notify,
+}
+
+impl WebhookState {
+    /// Completes a pending notification, posted or not.
+    fn complete(&self) {
+        if self.pending.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+}
+
+impl WebhookExExPlugin {
+    pub fn new(config: WebhookConfig) -> Self {
+        Self { config: Some(config), ..Default::default() }
+    }
+
+    /// Overrides the plugin id, e.g. to register several webhook plugins on the same manager.
+    pub fn with_id(mut self, id: &'static str) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Returns a number of notifications queued or being posted.
+    pub fn pending(&self) -> usize {
+        self.state.pending.load(Ordering::Acquire)
+    }
+
+    /// Returns a number of notifications dropped once retries of their requests are exhausted.
+    pub fn failed(&self) -> u64 {
+        self.state.failed.load(Ordering::Relaxed)
+    }
+}
+
+/// Posts queued payloads to the configured URL, until the queue is closed.
+async fn send(
+    id: &'static str,
+    config: WebhookConfig,
+    mut rx: mpsc::Receiver<Vec<u8>>,
+    state: Arc<WebhookState>,
+) {
+    while let Some(payload) = rx.recv().await {
+        if let Err(err) = post(id, &config, &payload).await {
+            state.failed.fetch_add(1, Ordering::Relaxed);
+            error!(id, %err, "failed to post notification to webhook, retries are exhausted");
+        }
+        state.complete();
+    }
+}
+
+/// Posts a JSON payload to the configured URL, retrying failed requests.
+async fn post(id: &'static str, config: &WebhookConfig, payload: &[u8]) -> Result<()> {
+    let url = HttpUrl::parse(&config.url)?;
+    let timeout = Duration::from_millis(config.timeout_ms);
+    let policy = config.retry_policy();
+
+    let mut retries = 0;
+    loop {
+        let err = match tokio::time::timeout(timeout, url.post(payload)).await {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(err)) => err,
+            Err(_) => eyre::eyre!("Webhook request timed out after {timeout:?}"),
+        };
+        let Some(backoff) = policy.backoff(retries) else { return Err(err) };
+
+        retries += 1;
+        warn!(id, %err, ?backoff, retries, "retrying webhook request");
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+impl ExExPlugin for WebhookExExPlugin {
+    fn id(&self) -> &'static str {
+        self.id.unwrap_or(WEBHOOK_EXEX_PLUGIN_ID)
+    }
+
+    fn on_config(&mut self, config: serde_json::Value) -> Result<()> {
+        let config: WebhookConfig = serde_json::from_value(config)?;
+        HttpUrl::parse(&config.url)?;
+        self.config = Some(config);
+        Ok(())
+    }
+
+    fn on_runtime(&mut self, tasks: PluginTasks) {
+        self.tasks = Some(tasks);
+    }
+
+    fn on_load<'a: 'b, 'b>(&'a mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let Some(config) = self.config.clone() else {
+                eyre::bail!("Webhook URL isn't configured");
+            };
+            HttpUrl::parse(&config.url)?;
+
+            let (tx, rx) = mpsc::channel(config.queue_capacity.max(1));
+            let send = send(self.id(), config, rx, self.state.clone());
+            match &self.tasks {
+                // tracked, but not aborted on unload, so queued notifications are posted
+                Some(tasks) => drop(tasks.tracker().spawn_on(send, tasks.handle())),
+                None => drop(tokio::spawn(send)),
+            }
+            self.tx = Some(tx);
+            Ok(())
+        })
+    }
+
+    fn on_unload(&mut self) -> Result<()> {
+        // the sending task exits once the closed queue is drained
+        self.tx = None;
+        Ok(())
+    }
+
+    fn flush(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            loop {
+                let idle = self.state.idle.notified();
+                if self.pending() == 0 {
+                    return Ok(());
+                }
+                idle.await;
+            }
+        })
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let Some(tx) = &self.tx else { eyre::bail!("Webhook isn't loaded") };
+            let payload = serde_json::to_vec(&NormalizedNotification::from(&*notification))?;
+
+            // counted before the send, so the sending task never sees it below zero
+            self.state.pending.fetch_add(1, Ordering::AcqRel);
+            tx.try_send(payload).map_err(|err| {
+                self.state.complete();
+                match err {
+                    TrySendError::Full(_) => eyre::eyre!("Webhook queue is full"),
+                    TrySendError::Closed(_) => eyre::eyre!("Webhook queue is closed"),
+                }
+            })
+        })
+    }
+}
+
+/// Parsed `http` URL of a webhook endpoint.
+#[derive(Debug)]
+struct HttpUrl<'a> {
+    /// Host with an optional port, as given in the URL.
+    authority: &'a str,
+    /// Host to connect to, without brackets of an IPv6 address.
+    host: &'a str,
+    /// Port to connect to, `80` if the URL has none.
+    port: u16,
+    path: &'a str,
+}
+
+impl<'a> HttpUrl<'a> {
+    fn parse(url: &'a str) -> Result<Self> {
+        let Some(rest) = url.strip_prefix("http://") else {
+            eyre::bail!("Unsupported webhook URL: `{url}`, expected `http://` scheme");
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        // the port of an IPv6 host follows its closing bracket, e.g. `[::1]:8080`
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(bracketed) => match bracketed.split_once(']') {
+                Some((host, "")) => (host, None),
+                Some((host, port)) => match port.strip_prefix(':') {
+                    Some(port) => (host, Some(port)),
+                    None => eyre::bail!("Webhook URL `{url}` has a malformed IPv6 host"),
+                },
+                None => eyre::bail!("Webhook URL `{url}` has an unclosed IPv6 host"),
+            },
+            None => match authority.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        if host.is_empty() {
+            eyre::bail!("Webhook URL `{url}` has no host");
+        }
+        let port = match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| eyre::format_err!("Webhook URL `{url}` has an invalid port"))?,
+            None => 80,
+        };
+        Ok(Self { authority, host, port, path })
+    }
+
+    /// Sends a single POST request, returning an error on a non-2xx status.
+    async fn post(&self, payload: &[u8]) -> Result<()> {
+        let mut conn = TcpStream::connect((self.host, self.port))
+            .await
+            .map_err(|err| eyre::format_err!("Failed to connect to {}: {err:?}", self.authority))?;
+
+        let head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.authority,
+            payload.len()
+        );
+        conn.write_all(head.as_bytes()).await?;
+        conn.write_all(payload).await?;
+        conn.flush().await?;
+
+        let mut status_line = String::new();
+        BufReader::new(conn).read_line(&mut status_line).await?;
+        let status = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse::<u16>().ok())
+            .ok_or_else(|| eyre::eyre!("Malformed webhook response: {status_line:?}"))?;
+        if !(200..300).contains(&status) {
+            eyre::bail!("Webhook responded with status {status}");
+        }
+        debug!(authority = self.authority, status, "posted notification to webhook");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+//! Serialization profiles of notification payloads on the RPC boundary.
+
+use serde::Serialize;
+
+/// Case of field names in serialized payloads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldCase {
+    /// `snake_case`, the default serde output.
+    #[default]
+    Snake,
+    /// `camelCase`, e.g. for JavaScript consumers.
+    Camel,
+}
+
+/// Serialization profile of notification related payloads, e.g. events of
+/// `exex_subscribePluginErrors` RPC, see [`ExExPluginRpc::with_profile`].
+///
+/// The profile is a transformation of the default serde output, applied recursively to all
+/// nested objects of a payload.
+///
+/// [`ExExPluginRpc::with_profile`]: crate::ExExPluginRpc::with_profile
+///
+/// # Example
+///
+/// ```rust
+/// use reth_exex_plugin::{FieldCase, SerializationProfile};
+///
+/// // camelCase fields without hashes, e.g. `headHash` of plugin error events is dropped
+/// let profile = SerializationProfile::default().with_case(FieldCase::Camel).with_hashes(false);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerializationProfile {
+    /// Case of field names.
+    pub case: FieldCase,
+    /// Whether hash fields, i.e. `hash` and ones suffixed with `_hash`, are included.
+    pub include_hashes: bool,
+}
+
+impl Default for SerializationProfile {
+    fn default() -> Self {
+        Self { case: FieldCase::default(), include_hashes: true }
+    }
+}
+
+impl SerializationProfile {
+    /// Sets a case of field names.
+    pub fn with_case(mut self, case: FieldCase) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Sets whether hash fields are included.
+    pub fn with_hashes(mut self, include: bool) -> Self {
+        self.include_hashes = include;
+        self
+    }
+
+    /// Serializes a value according to the profile.
+    pub fn to_value<T: Serialize>(&self, value: &T) -> serde_json::Result<serde_json::Value> {
+        Ok(self.apply(serde_json::to_value(value)?))
+    }
+
+    fn apply(&self, value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(object) => object
+                .into_iter()
+                .filter(|(key, _)| self.include_hashes || !is_hash_field(key))
+                .map(|(key, value)| (self.rename(key), self.apply(value)))
+                .collect(),
+            serde_json::Value::Array(values) => {
+                values.into_iter().map(|value| self.apply(value)).collect()
+            }
+            value => value,
+        }
+    }
+
+    fn rename(&self, key: String) -> String {
+        match self.case {
+            FieldCase::Snake => key,
+            FieldCase::Camel => {
+                let mut parts = key.split('_');
+                let mut renamed = parts.next().unwrap_or_default().to_owned();
+                for part in parts {
+                    let mut chars = part.chars();
+                    renamed.extend(chars.next().map(|first| first.to_ascii_uppercase()));
+                    renamed.push_str(chars.as_str());
+                }
+                renamed
+            }
+        }
+    }
+}
+
+/// Returns `true` if a `snake_case` field name is a hash field.
+fn is_hash_field(key: &str) -> bool {
+    key == "hash" || key.ends_with("_hash")
+}
@@ -1,24 +1,224 @@
-use std::path::PathBuf;
+use std::{
+    collections::{HashSet, VecDeque},
+    path::PathBuf,
+    time::Instant,
+};
 
 use futures::future::BoxFuture;
 use jsonrpsee::{
-    core::RpcResult,
+    core::{RpcResult, SubscriptionResult},
     proc_macros::rpc,
     types::{error::INTERNAL_ERROR_CODE, ErrorObjectOwned as RpcError},
-    RpcModule,
+    PendingSubscriptionSink, RpcModule, SubscriptionMessage, SubscriptionSink,
 };
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+use reth_tracing::tracing::{debug, warn, Level};
 
-use crate::sender::Sender;
+use crate::{
+    format_rpc_err, sender::Sender, ChainKind, DependencyCheck, DiscoveredPlugin, DispatchBlocks,
+    FullPluginStatus, ManagerState, ManagerStatus, ManifestReport, PluginDispatch,
+    PluginErrorEvent, PluginHealth, PluginInfo, PluginLogLine, PluginProfile, Readiness,
+    SerializationProfile, ServerInfo, ShadowDivergence,
+};
 
 /// RPC response sender representation
 pub type ResponseTx<T> = oneshot::Sender<RpcResult<T>>;
 
 #[derive(Debug)]
 pub enum RpcRequest {
-    ListPlugins { tx: ResponseTx<Vec<String>> },
-    LoadPlugin { plugin_path: PathBuf, tx: ResponseTx<String> },
-    UnloadPlugin { id: String, tx: ResponseTx<()> },
+    ListPlugins {
+        tx: ResponseTx<Vec<String>>,
+    },
+    PluginCount {
+        tx: ResponseTx<usize>,
+    },
+    LoadPlugin {
+        plugin_path: PathBuf,
+        log_level: Option<Level>,
+        config: Option<serde_json::Value>,
+        expected_id: Option<String>,
+        tx: ResponseTx<String>,
+    },
+    UnloadPlugin {
+        id: String,
+        tx: ResponseTx<()>,
+    },
+    SetPluginBlockingMuted {
+        id: String,
+        muted: bool,
+        tx: ResponseTx<()>,
+    },
+    SetPluginPaused {
+        id: String,
+        paused: bool,
+        tx: ResponseTx<()>,
+    },
+    ReloadPlugin {
+        id: String,
+        plugin_path: Option<PathBuf>,
+        allow_id_change: bool,
+        tx: ResponseTx<String>,
+    },
+    PluginCommand {
+        id: String,
+        command: String,
+        params: serde_json::Value,
+        tx: ResponseTx<serde_json::Value>,
+    },
+    DiscoverPlugins {
+        dir: PathBuf,
+        tx: ResponseTx<Vec<DiscoveredPlugin>>,
+    },
+    GetPluginInfo {
+        id: String,
+        tx: ResponseTx<PluginInfo>,
+    },
+    SetPluginNotificationKinds {
+        id: String,
+        kinds: Vec<ChainKind>,
+        tx: ResponseTx<()>,
+    },
+    PluginHealthDetailed {
+        id: String,
+        tx: ResponseTx<PluginHealth>,
+    },
+    Ping {
+        tx: ResponseTx<()>,
+    },
+    PluginLatestResult {
+        id: String,
+        tx: ResponseTx<Option<serde_json::Value>>,
+    },
+    FindPluginsByCapability {
+        capability: String,
+        tx: ResponseTx<Vec<String>>,
+    },
+    SubscribePluginErrors {
+        tx: ResponseTx<broadcast::Receiver<PluginErrorEvent>>,
+    },
+    SubscribePluginLogs {
+        id: String,
+        tx: ResponseTx<broadcast::Receiver<PluginLogLine>>,
+    },
+    LoadAndSubscribe {
+        plugin_path: PathBuf,
+        log_level: Option<Level>,
+        config: Option<serde_json::Value>,
+        tx: ResponseTx<(String, broadcast::Receiver<PluginErrorEvent>)>,
+    },
+    ApplyManifest {
+        path: PathBuf,
+        tx: ResponseTx<Vec<ManifestReport>>,
+    },
+    PluginConfig {
+        id: String,
+        tx: ResponseTx<Option<serde_json::Value>>,
+    },
+    ShadowLoad {
+        id: String,
+        new_path: PathBuf,
+        tx: ResponseTx<String>,
+    },
+    PromoteShadow {
+        id: String,
+        tx: ResponseTx<String>,
+    },
+    ManagerStatus {
+        tx: ResponseTx<ManagerStatus>,
+    },
+    Snapshot {
+        tx: ResponseTx<ManagerState>,
+    },
+    Restore {
+        snapshot: ManagerState,
+        tx: ResponseTx<Vec<ManifestReport>>,
+    },
+    PluginInflight {
+        id: String,
+        tx: ResponseTx<usize>,
+    },
+    CheckDependencies {
+        plugin_path: PathBuf,
+        tx: ResponseTx<DependencyCheck>,
+    },
+    PluginsFull {
+        tx: ResponseTx<Vec<FullPluginStatus>>,
+    },
+    ServerInfo {
+        tx: ResponseTx<ServerInfo>,
+    },
+    Readiness {
+        tx: ResponseTx<Readiness>,
+    },
+    ShadowDivergence {
+        id: String,
+        tx: ResponseTx<Option<ShadowDivergence>>,
+    },
+    GcTempFiles {
+        tx: ResponseTx<usize>,
+    },
+    PluginLogs {
+        id: String,
+        tail: Option<usize>,
+        tx: ResponseTx<Vec<PluginLogLine>>,
+    },
+    PluginProfile {
+        id: String,
+        tx: ResponseTx<PluginProfile>,
+    },
+    ResetPluginMetrics {
+        id: String,
+        tx: ResponseTx<PluginInfo>,
+    },
+    ReconfigurePlugin {
+        id: String,
+        config: serde_json::Value,
+        tx: ResponseTx<()>,
+    },
+    ExplainDispatch {
+        blocks: DispatchBlocks,
+        kind: ChainKind,
+        tx: ResponseTx<Vec<PluginDispatch>>,
+    },
+}
+
+/// RPC requests the manager handled or deferred while dispatching a notification.
+#[derive(Debug, Default)]
+pub(crate) struct RpcRequests {
+    /// Requests received during a notification dispatch, handled once it's finished.
+    deferred: VecDeque<RpcRequest>,
+    /// Time the manager last handled a request at.
+    last_handled_at: Option<Instant>,
+}
+
+impl RpcRequests {
+    /// Records a handled request.
+    pub(crate) fn handled(&mut self) {
+        self.last_handled_at = Some(Instant::now());
+    }
+
+    /// Returns the time the manager last handled a request at.
+    pub(crate) const fn last_handled_at(&self) -> Option<Instant> {
+        self.last_handled_at
+    }
+
+    /// Returns the next request deferred during the last dispatch, in arrival order.
+    pub(crate) fn next_deferred(&mut self) -> Option<RpcRequest> {
+        self.deferred.pop_front()
+    }
+
+    /// Records a request received during a dispatch to a plugin, deferring it unless it's
+    /// already handled, i.e. a read request.
+    pub(crate) fn received_during_dispatch(&mut self, id: &str, unhandled: Option<RpcRequest>) {
+        match unhandled {
+            Some(req) => {
+                debug!(%id, "Deferred RPC request received during dispatch");
+                self.deferred.push_back(req);
+            }
+            None => self.handled(),
+        }
+    }
 }
 
 #[rpc(server, namespace = "exex")]
@@ -27,15 +227,267 @@ trait ExExRpcPluginApi {
     #[method(name = "listPlugins")]
     async fn list_plugins(&self) -> RpcResult<Vec<String>>;
 
+    /// Returns a number of presented ExEx plugins.
+    #[method(name = "pluginCount")]
+    async fn plugin_count(&self) -> RpcResult<usize>;
+
     /// Loads ExEx plugin to the node and initializes it.
     ///
+    /// Optional `log_level` (e.g. `"debug"`) is a preferred tracing level of the plugin's span.
+    /// Optional `config` is passed to the plugin before its initialization.
+    /// Optional `expected_id` is compared to the id the plugin declares, refusing to load
+    /// another plugin than intended.
+    ///
     /// Returns an ExEx plugin id.
     #[method(name = "loadPlugin")]
-    async fn load_plugin(&self, plugin_path: PathBuf) -> RpcResult<String>;
+    async fn load_plugin(
+        &self,
+        plugin_path: PathBuf,
+        log_level: Option<String>,
+        config: Option<serde_json::Value>,
+        expected_id: Option<String>,
+    ) -> RpcResult<String>;
 
     /// Unloads ExEx plugin from the node.
     #[method(name = "unloadPlugin")]
     async fn unload_plugin(&self, id: String) -> RpcResult<()>;
+
+    /// Mutes ExEx plugin's failures from holding back the node's finished height.
+    ///
+    /// The plugin keeps handling notifications.
+    #[method(name = "mutePluginBlocking")]
+    async fn mute_plugin_blocking(&self, id: String) -> RpcResult<()>;
+
+    /// Unmutes ExEx plugin's failures from holding back the node's finished height.
+    #[method(name = "unmutePluginBlocking")]
+    async fn unmute_plugin_blocking(&self, id: String) -> RpcResult<()>;
+
+    /// Pauses ExEx plugin, which receives no notifications until it's resumed.
+    ///
+    /// Notifications received meanwhile are handled by the plugin's pause policy.
+    #[method(name = "pausePlugin")]
+    async fn pause_plugin(&self, id: String) -> RpcResult<()>;
+
+    /// Resumes paused ExEx plugin, which receives notifications of its kinds again.
+    #[method(name = "resumePlugin")]
+    async fn resume_plugin(&self, id: String) -> RpcResult<()>;
+
+    /// Reloads ExEx plugin from a given path, or from the path it was loaded from.
+    ///
+    /// Refuses to reload, if the new plugin's id doesn't match, unless `allow_id_change` is set.
+    ///
+    /// Returns a reloaded ExEx plugin id.
+    #[method(name = "reloadPlugin")]
+    async fn reload_plugin(
+        &self,
+        id: String,
+        plugin_path: Option<PathBuf>,
+        allow_id_change: Option<bool>,
+    ) -> RpcResult<String>;
+
+    /// Invokes a plugin specific command with arbitrary JSON params.
+    ///
+    /// Returns the command's JSON result.
+    #[method(name = "pluginCommand")]
+    async fn plugin_command(
+        &self,
+        id: String,
+        command: String,
+        params: serde_json::Value,
+    ) -> RpcResult<serde_json::Value>;
+
+    /// Lists plugin libraries available in a given directory, without loading them.
+    ///
+    /// Libraries which fail to open are reported with `loadable: false` and an error.
+    #[method(name = "discoverPlugins")]
+    async fn discover_plugins(&self, dir: PathBuf) -> RpcResult<Vec<DiscoveredPlugin>>;
+
+    /// Returns information about ExEx plugin and the library file which backs it.
+    ///
+    /// Includes the library's canonical path and modification time captured at load.
+    #[method(name = "getPluginInfo")]
+    async fn get_plugin_info(&self, id: String) -> RpcResult<PluginInfo>;
+
+    /// Sets kinds of notifications (`commit`, `revert` or `reorg`) ExEx plugin receives.
+    ///
+    /// Narrows the plugin's scope without reloading it. All kinds are received by default.
+    #[method(name = "setPluginNotificationKinds")]
+    async fn set_plugin_notification_kinds(
+        &self,
+        id: String,
+        kinds: Vec<ChainKind>,
+    ) -> RpcResult<()>;
+
+    /// Runs ExEx plugin's health check with a timeout and returns its result with latency.
+    ///
+    /// A timed out check is reported as `healthy: false` with a `"timeout"` error.
+    #[method(name = "pluginHealthDetailed")]
+    async fn plugin_health_detailed(&self, id: String) -> RpcResult<PluginHealth>;
+
+    /// Pings the manager through its `run` loop, proving the RPC channel and the loop are alive.
+    ///
+    /// Returns the round-trip latency in microseconds.
+    #[method(name = "ping")]
+    async fn ping(&self) -> RpcResult<u64>;
+
+    /// Returns the latest notification result of ExEx plugin by the given id, if any.
+    #[method(name = "pluginLatestResult")]
+    async fn plugin_latest_result(&self, id: String) -> RpcResult<Option<serde_json::Value>>;
+
+    /// Returns ids of all ExEx plugins declaring a given capability.
+    #[method(name = "findPluginsByCapability")]
+    async fn find_plugins_by_capability(&self, capability: String) -> RpcResult<Vec<String>>;
+
+    /// Converges loaded ExEx plugins to a manifest at the given path, in the state file format.
+    #[method(name = "applyManifest")]
+    async fn apply_manifest(&self, path: PathBuf) -> RpcResult<Vec<ManifestReport>>;
+
+    /// Returns the config ExEx plugin is loaded with, with its secrets redacted by the plugin.
+    ///
+    /// Returns `null` if the plugin is loaded without a config.
+    #[method(name = "pluginConfig")]
+    async fn plugin_config(&self, id: String) -> RpcResult<Option<serde_json::Value>>;
+
+    /// Loads a new version of ExEx plugin from a given path as a shadow of the loaded one.
+    ///
+    /// The shadow receives the same notifications, with its output isolated and tagged with
+    /// a shadow id, i.e. the plugin's id suffixed with `@shadow`.
+    ///
+    /// Returns a shadow ExEx plugin id.
+    #[method(name = "shadowLoad")]
+    async fn shadow_load(&self, id: String, new_path: PathBuf) -> RpcResult<String>;
+
+    /// Replaces ExEx plugin with its shadow.
+    ///
+    /// Returns a promoted ExEx plugin id.
+    #[method(name = "promoteShadow")]
+    async fn promote_shadow(&self, id: String) -> RpcResult<String>;
+
+    /// Returns the manager's uptime and last activity, e.g. to detect a stalled manager.
+    #[method(name = "managerStatus")]
+    async fn manager_status(&self) -> RpcResult<ManagerStatus>;
+
+    /// Subscribes to failures of ExEx plugins to handle notifications, of a given plugin
+    /// or of all plugins.
+    ///
+    /// Each event carries the plugin id, block ranges of the notification and an error message.
+    #[subscription(
+        name = "subscribePluginErrors" => "pluginError",
+        unsubscribe = "unsubscribePluginErrors",
+        item = PluginErrorEvent
+    )]
+    async fn subscribe_plugin_errors(&self, id: Option<String>) -> SubscriptionResult;
+
+    /// Subscribes to new log lines of ExEx plugin, written to its dedicated log output.
+    #[subscription(
+        name = "subscribePluginLogs" => "pluginLog",
+        unsubscribe = "unsubscribePluginLogs",
+        item = PluginLogLine
+    )]
+    async fn subscribe_plugin_logs(&self, id: String) -> SubscriptionResult;
+
+    /// Loads ExEx plugin to the node and subscribes to its failures to handle notifications,
+    /// the same as `exex_subscribePluginErrors`.
+    ///
+    /// The subscription is established before the plugin is loaded and accepted once
+    /// the load succeeds, so no failure of the plugin is missed, including ones of
+    /// notifications received while it was loading.
+    #[subscription(
+        name = "loadAndSubscribe" => "loadedPluginError",
+        unsubscribe = "unsubscribeLoadAndSubscribe",
+        item = PluginErrorEvent
+    )]
+    async fn load_and_subscribe(
+        &self,
+        plugin_path: PathBuf,
+        log_level: Option<String>,
+        config: Option<serde_json::Value>,
+    ) -> SubscriptionResult;
+
+    /// Returns a snapshot of loaded ExEx plugins, i.e. their paths, log levels, priorities and
+    /// configs.
+    #[method(name = "snapshot")]
+    async fn snapshot(&self) -> RpcResult<ManagerState>;
+
+    /// Converges loaded ExEx plugins to a snapshot, the same way as the manifest apply.
+    ///
+    /// Either the whole snapshot is restored, or loaded plugins are kept as is.
+    #[method(name = "restore")]
+    async fn restore(&self, snapshot: ManagerState) -> RpcResult<Vec<ManifestReport>>;
+
+    /// Returns a number of ExEx plugin's notification handlers in flight.
+    ///
+    /// A count persistently above zero signals a hung handler.
+    #[method(name = "pluginInflight")]
+    async fn plugin_inflight(&self, id: String) -> RpcResult<usize>;
+
+    /// Checks dependencies of ExEx plugin from a given path against loaded plugins, without loading
+    /// it.
+    ///
+    /// Returns which dependencies are loaded and which are missing.
+    #[method(name = "checkDependencies")]
+    async fn check_dependencies(&self, plugin_path: PathBuf) -> RpcResult<DependencyCheck>;
+
+    /// Returns a full diagnostic status of every loaded ExEx plugin in a single call.
+    ///
+    /// Each status bundles the plugin's info and metrics, handlers in flight, last error, last
+    /// notification, latest result and health.
+    #[method(name = "pluginsFull")]
+    async fn plugins_full(&self) -> RpcResult<Vec<FullPluginStatus>>;
+
+    /// Returns the manager's network, version and the plugin ABI version it loads, e.g. to tell
+    /// apart managers of several nodes.
+    #[method(name = "serverInfo")]
+    async fn server_info(&self) -> RpcResult<ServerInfo>;
+
+    /// Returns readiness of the manager, e.g. for a readiness probe.
+    ///
+    /// The manager is ready only if all of its required ExEx plugins are loaded, not paused, not
+    /// failing and healthy. Otherwise, reasons it isn't ready are returned.
+    #[method(name = "readiness")]
+    async fn readiness(&self) -> RpcResult<Readiness>;
+
+    /// Returns the first divergence of the plugin's shadow from the production plugin, if any.
+    #[method(name = "shadowDivergence")]
+    async fn shadow_divergence(&self, id: String) -> RpcResult<Option<ShadowDivergence>>;
+
+    /// Removes files of the manager's temp directory, which aren't referenced by any loaded plugin.
+    ///
+    /// Returns a number of removed files.
+    #[method(name = "gcTempFiles")]
+    async fn gc_temp_files(&self) -> RpcResult<usize>;
+
+    /// Returns up to `tail` latest log lines of ExEx plugin, written to its dedicated log output,
+    /// all buffered ones if `tail` is omitted.
+    #[method(name = "pluginLogs")]
+    async fn plugin_logs(&self, id: String, tail: Option<usize>) -> RpcResult<Vec<PluginLogLine>>;
+
+    /// Returns percentiles of hook timings of a plugin, if profiling is enabled.
+    #[method(name = "pluginProfile")]
+    async fn plugin_profile(&self, id: String) -> RpcResult<PluginProfile>;
+
+    /// Reads and zeroes a plugin's counters of handled and failed notifications,
+    /// returning its information before the reset.
+    #[method(name = "resetPluginMetrics")]
+    async fn reset_plugin_metrics(&self, id: String) -> RpcResult<PluginInfo>;
+
+    /// Applies a new config to the running plugin without reloading it.
+    ///
+    /// The plugin keeps its in-memory state. Fails if it doesn't support reconfiguration.
+    #[method(name = "reconfigurePlugin")]
+    async fn reconfigure_plugin(&self, id: String, config: serde_json::Value) -> RpcResult<()>;
+
+    /// Explains which plugins would receive a notification of a given kind of given blocks,
+    /// by their number range or hashes.
+    ///
+    /// Returns every plugin in dispatch order, with a reason it would be filtered out, if any.
+    /// The notification isn't dispatched.
+    #[method(name = "explainDispatch")]
+    async fn explain_dispatch(
+        &self,
+        blocks: DispatchBlocks,
+        kind: ChainKind,
+    ) -> RpcResult<Vec<PluginDispatch>>;
 }
 
 /// ExEx manager RPC module
@@ -43,17 +495,104 @@ trait ExExRpcPluginApi {
 pub struct ExExPluginRpc {
     /// Request sender to ExEx plugin [manager](`crate::manager::ExExManager`).
     pub tx: Sender<RpcRequest>,
+    /// Serialization profile of notification related subscription events.
+    pub profile: SerializationProfile,
+    /// Methods allowed to be called, all of them if `None`, see [`Self::with_allowed_methods`].
+    pub allowed_methods: Option<HashSet<String>>,
 }
 
 impl ExExPluginRpc {
     pub fn new(tx: mpsc::UnboundedSender<RpcRequest>) -> Self {
-        ExExPluginRpc { tx: Sender::new(tx) }
+        ExExPluginRpc {
+            tx: Sender::new(tx),
+            profile: SerializationProfile::default(),
+            allowed_methods: None,
+        }
+    }
+
+    /// RPC module over a bounded channel, which applies backpressure on requests
+    /// when the manager falls behind.
+    pub fn bounded(tx: mpsc::Sender<RpcRequest>) -> Self {
+        ExExPluginRpc {
+            tx: Sender::bounded(tx),
+            profile: SerializationProfile::default(),
+            allowed_methods: None,
+        }
+    }
+
+    /// Sets a [`SerializationProfile`] of notification related subscription events,
+    /// e.g. of `exex_subscribePluginErrors`.
+    pub fn with_profile(mut self, profile: SerializationProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Limits methods which may be called to given ones, named without the `exex_` namespace,
+    /// e.g. to expose only read-only methods like `listPlugins` on a public interface.
+    ///
+    /// Calls of other methods, including subscriptions, fail with a "method disabled" error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use reth_exex_plugin::ExExPluginRpc;
+    ///
+    /// let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    /// let rpc = ExExPluginRpc::new(tx).with_allowed_methods(["listPlugins", "managerStatus"]);
+    /// ```
+    pub fn with_allowed_methods(
+        mut self,
+        methods: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.allowed_methods = Some(methods.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns an error if a given method isn't [allowed](Self::with_allowed_methods).
+    fn check_method(&self, method: &str) -> RpcResult<()> {
+        match &self.allowed_methods {
+            Some(allowed) if !allowed.contains(method) => {
+                Err(format_rpc_err!("method disabled: exex_{method}"))
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Wrapper for [ExExRpcPluginApi] RPC server to [RpcModule].
     pub fn rpc_module(tx: mpsc::UnboundedSender<RpcRequest>) -> RpcModule<Self> {
         Self::new(tx).into_rpc()
     }
+
+    /// Forwards plugin errors to a subscription sink, of a given plugin or of all plugins,
+    /// until the subscription is closed.
+    async fn pipe_plugin_errors(
+        &self,
+        sink: SubscriptionSink,
+        mut errors: broadcast::Receiver<PluginErrorEvent>,
+        id: Option<String>,
+    ) -> SubscriptionResult {
+        loop {
+            let event = tokio::select! {
+                _ = sink.closed() => break,
+                event = errors.recv() => event,
+            };
+            match event {
+                Ok(event) if id.as_ref().is_some_and(|id| *id != event.id) => continue,
+                Ok(event) => {
+                    let event = self.profile.to_value(&event)?;
+                    if sink.send(SubscriptionMessage::from_json(&event)?).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "plugin errors subscriber lagged behind");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl ExExRpcPluginApiServer for ExExPluginRpc {
@@ -62,8 +601,21 @@ impl ExExRpcPluginApiServer for ExExPluginRpc {
     #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
     fn list_plugins<'a: 'b, 'b>(&'a self) -> BoxFuture<'b, RpcResult<Vec<String>>> {
         Box::pin(async move {
+            self.check_method("listPlugins")?;
             let (tx, rx) = oneshot::channel();
-            self.tx.send(RpcRequest::ListPlugins { tx });
+            send_request(&self.tx, RpcRequest::ListPlugins { tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns a number of presented ExEx plugins."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn plugin_count<'a: 'b, 'b>(&'a self) -> BoxFuture<'b, RpcResult<usize>> {
+        Box::pin(async move {
+            self.check_method("pluginCount")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PluginCount { tx }).await?;
             process_request_rx(rx).await
         })
     }
@@ -71,10 +623,23 @@ impl ExExRpcPluginApiServer for ExExPluginRpc {
     #[doc = " Loads ExEx plugin to the node and initializes it."]
     #[must_use]
     #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
-    fn load_plugin<'a: 'b, 'b>(&'a self, plugin_path: PathBuf) -> BoxFuture<'b, RpcResult<String>> {
+    fn load_plugin<'a: 'b, 'b>(
+        &'a self,
+        plugin_path: PathBuf,
+        log_level: Option<String>,
+        config: Option<serde_json::Value>,
+        expected_id: Option<String>,
+    ) -> BoxFuture<'b, RpcResult<String>> {
         Box::pin(async move {
+            self.check_method("loadPlugin")?;
+            let log_level = log_level
+                .map(|level| level.parse::<Level>())
+                .transpose()
+                .map_err(|err| format_rpc_err!("invalid plugin log level: {err}"))?;
+
             let (tx, rx) = oneshot::channel();
-            self.tx.send(RpcRequest::LoadPlugin { plugin_path, tx });
+            let req = RpcRequest::LoadPlugin { plugin_path, log_level, config, expected_id, tx };
+            send_request(&self.tx, req).await?;
             process_request_rx(rx).await
         })
     }
@@ -84,15 +649,610 @@ impl ExExRpcPluginApiServer for ExExPluginRpc {
     #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
     fn unload_plugin<'a: 'b, 'b>(&'a self, id: String) -> BoxFuture<'b, RpcResult<()>> {
         Box::pin(async move {
+            self.check_method("unloadPlugin")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::UnloadPlugin { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Mutes ExEx plugin's failures from holding back the node's finished height."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn mute_plugin_blocking<'a: 'b, 'b>(&'a self, id: String) -> BoxFuture<'b, RpcResult<()>> {
+        Box::pin(async move {
+            self.check_method("mutePluginBlocking")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::SetPluginBlockingMuted { id, muted: true, tx })
+                .await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Unmutes ExEx plugin's failures from holding back the node's finished height."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn unmute_plugin_blocking<'a: 'b, 'b>(&'a self, id: String) -> BoxFuture<'b, RpcResult<()>> {
+        Box::pin(async move {
+            self.check_method("unmutePluginBlocking")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::SetPluginBlockingMuted { id, muted: false, tx })
+                .await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Pauses ExEx plugin, which receives no notifications until it's resumed."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn pause_plugin<'a: 'b, 'b>(&'a self, id: String) -> BoxFuture<'b, RpcResult<()>> {
+        Box::pin(async move {
+            self.check_method("pausePlugin")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::SetPluginPaused { id, paused: true, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Resumes paused ExEx plugin, which receives notifications of its kinds again."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn resume_plugin<'a: 'b, 'b>(&'a self, id: String) -> BoxFuture<'b, RpcResult<()>> {
+        Box::pin(async move {
+            self.check_method("resumePlugin")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::SetPluginPaused { id, paused: false, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Reloads ExEx plugin from a given path, or from the path it was loaded from."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn reload_plugin<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+        plugin_path: Option<PathBuf>,
+        allow_id_change: Option<bool>,
+    ) -> BoxFuture<'b, RpcResult<String>> {
+        Box::pin(async move {
+            self.check_method("reloadPlugin")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(
+                &self.tx,
+                RpcRequest::ReloadPlugin {
+                    id,
+                    plugin_path,
+                    allow_id_change: allow_id_change.unwrap_or_default(),
+                    tx,
+                },
+            )
+            .await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Invokes a plugin specific command with arbitrary JSON params."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn plugin_command<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+        command: String,
+        params: serde_json::Value,
+    ) -> BoxFuture<'b, RpcResult<serde_json::Value>> {
+        Box::pin(async move {
+            self.check_method("pluginCommand")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PluginCommand { id, command, params, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Lists plugin libraries available in a given directory, without loading them."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn discover_plugins<'a: 'b, 'b>(
+        &'a self,
+        dir: PathBuf,
+    ) -> BoxFuture<'b, RpcResult<Vec<DiscoveredPlugin>>> {
+        Box::pin(async move {
+            self.check_method("discoverPlugins")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::DiscoverPlugins { dir, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns information about ExEx plugin and the library file which backs it."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn get_plugin_info<'a: 'b, 'b>(&'a self, id: String) -> BoxFuture<'b, RpcResult<PluginInfo>> {
+        Box::pin(async move {
+            self.check_method("getPluginInfo")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::GetPluginInfo { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Sets kinds of notifications (`commit`, `revert` or `reorg`) ExEx plugin receives."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn set_plugin_notification_kinds<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+        kinds: Vec<ChainKind>,
+    ) -> BoxFuture<'b, RpcResult<()>> {
+        Box::pin(async move {
+            self.check_method("setPluginNotificationKinds")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::SetPluginNotificationKinds { id, kinds, tx })
+                .await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Runs ExEx plugin's health check with a timeout and returns its result with latency."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn plugin_health_detailed<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+    ) -> BoxFuture<'b, RpcResult<PluginHealth>> {
+        Box::pin(async move {
+            self.check_method("pluginHealthDetailed")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PluginHealthDetailed { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Pings the manager through its `run` loop, proving the RPC channel and the loop are alive."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn ping<'a: 'b, 'b>(&'a self) -> BoxFuture<'b, RpcResult<u64>> {
+        Box::pin(async move {
+            self.check_method("ping")?;
+            let started_at = Instant::now();
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::Ping { tx }).await?;
+            process_request_rx(rx).await?;
+            Ok(started_at.elapsed().as_micros() as u64)
+        })
+    }
+
+    #[doc = " Returns the latest notification result of ExEx plugin by the given id, if any."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn plugin_latest_result<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+    ) -> BoxFuture<'b, RpcResult<Option<serde_json::Value>>> {
+        Box::pin(async move {
+            self.check_method("pluginLatestResult")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PluginLatestResult { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns ids of all ExEx plugins declaring a given capability."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn find_plugins_by_capability<'a: 'b, 'b>(
+        &'a self,
+        capability: String,
+    ) -> BoxFuture<'b, RpcResult<Vec<String>>> {
+        Box::pin(async move {
+            self.check_method("findPluginsByCapability")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::FindPluginsByCapability { capability, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Converges loaded ExEx plugins to a manifest at the given path, in the state file format."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn apply_manifest<'a: 'b, 'b>(
+        &'a self,
+        path: PathBuf,
+    ) -> BoxFuture<'b, RpcResult<Vec<ManifestReport>>> {
+        Box::pin(async move {
+            self.check_method("applyManifest")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::ApplyManifest { path, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns the config ExEx plugin is loaded with, with its secrets redacted by the plugin."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn plugin_config<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+    ) -> BoxFuture<'b, RpcResult<Option<serde_json::Value>>> {
+        Box::pin(async move {
+            self.check_method("pluginConfig")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PluginConfig { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Loads a new version of ExEx plugin from a given path as a shadow of the loaded one."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn shadow_load<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+        new_path: PathBuf,
+    ) -> BoxFuture<'b, RpcResult<String>> {
+        Box::pin(async move {
+            self.check_method("shadowLoad")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::ShadowLoad { id, new_path, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Replaces ExEx plugin with its shadow."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn promote_shadow<'a: 'b, 'b>(&'a self, id: String) -> BoxFuture<'b, RpcResult<String>> {
+        Box::pin(async move {
+            self.check_method("promoteShadow")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PromoteShadow { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns the manager's uptime and last activity, e.g. to detect a stalled manager."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn manager_status<'a: 'b, 'b>(&'a self) -> BoxFuture<'b, RpcResult<ManagerStatus>> {
+        Box::pin(async move {
+            self.check_method("managerStatus")?;
             let (tx, rx) = oneshot::channel();
-            self.tx.send(RpcRequest::UnloadPlugin { id, tx });
+            send_request(&self.tx, RpcRequest::ManagerStatus { tx }).await?;
             process_request_rx(rx).await
         })
     }
+
+    #[doc = " Subscribes to failures of ExEx plugins to handle notifications, of a given plugin"]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn subscribe_plugin_errors<'a: 'b, 'b>(
+        &'a self,
+        pending: PendingSubscriptionSink,
+        id: Option<String>,
+    ) -> BoxFuture<'b, SubscriptionResult> {
+        Box::pin(async move {
+            if let Err(err) = self.check_method("subscribePluginErrors") {
+                pending.reject(err).await;
+                return Ok(());
+            }
+            let (tx, rx) = oneshot::channel();
+            let subscribed =
+                match send_request(&self.tx, RpcRequest::SubscribePluginErrors { tx }).await {
+                    Ok(()) => process_request_rx(rx).await,
+                    Err(err) => Err(err),
+                };
+            let errors = match subscribed {
+                Ok(errors) => errors,
+                Err(err) => {
+                    pending.reject(err).await;
+                    return Ok(());
+                }
+            };
+
+            let sink = pending.accept().await?;
+            self.pipe_plugin_errors(sink, errors, id).await
+        })
+    }
+
+    #[doc = " Subscribes to new log lines of ExEx plugin, written to its dedicated log output."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn subscribe_plugin_logs<'a: 'b, 'b>(
+        &'a self,
+        pending: PendingSubscriptionSink,
+        id: String,
+    ) -> BoxFuture<'b, SubscriptionResult> {
+        Box::pin(async move {
+            if let Err(err) = self.check_method("subscribePluginLogs") {
+                pending.reject(err).await;
+                return Ok(());
+            }
+            let (tx, rx) = oneshot::channel();
+            let subscribed =
+                match send_request(&self.tx, RpcRequest::SubscribePluginLogs { id, tx }).await {
+                    Ok(()) => process_request_rx(rx).await,
+                    Err(err) => Err(err),
+                };
+            let mut lines = match subscribed {
+                Ok(lines) => lines,
+                Err(err) => {
+                    pending.reject(err).await;
+                    return Ok(());
+                }
+            };
+
+            let sink = pending.accept().await?;
+            loop {
+                let line = tokio::select! {
+                    _ = sink.closed() => break,
+                    line = lines.recv() => line,
+                };
+                match line {
+                    Ok(line) => {
+                        if sink.send(SubscriptionMessage::from_json(&line)?).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "plugin logs subscriber lagged behind");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    #[doc = " Loads ExEx plugin to the node and subscribes to its failures to handle notifications,"]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn load_and_subscribe<'a: 'b, 'b>(
+        &'a self,
+        pending: PendingSubscriptionSink,
+        plugin_path: PathBuf,
+        log_level: Option<String>,
+        config: Option<serde_json::Value>,
+    ) -> BoxFuture<'b, SubscriptionResult> {
+        Box::pin(async move {
+            if let Err(err) = self.check_method("loadAndSubscribe") {
+                pending.reject(err).await;
+                return Ok(());
+            }
+            let log_level = match log_level.map(|level| level.parse::<Level>()).transpose() {
+                Ok(log_level) => log_level,
+                Err(err) => {
+                    pending.reject(format_rpc_err!("invalid plugin log level: {err}")).await;
+                    return Ok(());
+                }
+            };
+
+            let (tx, rx) = oneshot::channel();
+            let req = RpcRequest::LoadAndSubscribe { plugin_path, log_level, config, tx };
+            let loaded = match send_request(&self.tx, req).await {
+                Ok(()) => process_request_rx(rx).await,
+                Err(err) => Err(err),
+            };
+            let (id, errors) = match loaded {
+                Ok(loaded) => loaded,
+                Err(err) => {
+                    pending.reject(err).await;
+                    return Ok(());
+                }
+            };
+
+            let sink = pending.accept().await?;
+            self.pipe_plugin_errors(sink, errors, Some(id)).await
+        })
+    }
+
+    #[doc = " Returns a snapshot of loaded ExEx plugins, i.e. their paths, log levels, priorities and configs."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn snapshot<'a: 'b, 'b>(&'a self) -> BoxFuture<'b, RpcResult<ManagerState>> {
+        Box::pin(async move {
+            self.check_method("snapshot")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::Snapshot { tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Converges loaded ExEx plugins to a snapshot, the same way as the manifest apply."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn restore<'a: 'b, 'b>(
+        &'a self,
+        snapshot: ManagerState,
+    ) -> BoxFuture<'b, RpcResult<Vec<ManifestReport>>> {
+        Box::pin(async move {
+            self.check_method("restore")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::Restore { snapshot, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns a number of ExEx plugin's notification handlers in flight."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn plugin_inflight<'a: 'b, 'b>(&'a self, id: String) -> BoxFuture<'b, RpcResult<usize>> {
+        Box::pin(async move {
+            self.check_method("pluginInflight")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PluginInflight { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Checks dependencies of ExEx plugin from a given path against loaded plugins, without loading it."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn check_dependencies<'a: 'b, 'b>(
+        &'a self,
+        plugin_path: PathBuf,
+    ) -> BoxFuture<'b, RpcResult<DependencyCheck>> {
+        Box::pin(async move {
+            self.check_method("checkDependencies")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::CheckDependencies { plugin_path, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns a full diagnostic status of every loaded ExEx plugin in a single call."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn plugins_full<'a: 'b, 'b>(&'a self) -> BoxFuture<'b, RpcResult<Vec<FullPluginStatus>>> {
+        Box::pin(async move {
+            self.check_method("pluginsFull")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PluginsFull { tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns the manager's network, version and the plugin ABI version it loads, e.g. to tell"]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn server_info<'a: 'b, 'b>(&'a self) -> BoxFuture<'b, RpcResult<ServerInfo>> {
+        Box::pin(async move {
+            self.check_method("serverInfo")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::ServerInfo { tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns readiness of the manager, e.g. for a readiness probe."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn readiness<'a: 'b, 'b>(&'a self) -> BoxFuture<'b, RpcResult<Readiness>> {
+        Box::pin(async move {
+            self.check_method("readiness")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::Readiness { tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns the first divergence of the plugin's shadow from the production plugin, if any."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn shadow_divergence<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+    ) -> BoxFuture<'b, RpcResult<Option<ShadowDivergence>>> {
+        Box::pin(async move {
+            self.check_method("shadowDivergence")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::ShadowDivergence { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Removes files of the manager's temp directory, which aren't referenced by any loaded plugin."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn gc_temp_files<'a: 'b, 'b>(&'a self) -> BoxFuture<'b, RpcResult<usize>> {
+        Box::pin(async move {
+            self.check_method("gcTempFiles")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::GcTempFiles { tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns up to `tail` latest log lines of ExEx plugin, written to its dedicated log output,"]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn plugin_logs<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+        tail: Option<usize>,
+    ) -> BoxFuture<'b, RpcResult<Vec<PluginLogLine>>> {
+        Box::pin(async move {
+            self.check_method("pluginLogs")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PluginLogs { id, tail, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Returns percentiles of hook timings of a plugin, if profiling is enabled."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn plugin_profile<'a: 'b, 'b>(&'a self, id: String) -> BoxFuture<'b, RpcResult<PluginProfile>> {
+        Box::pin(async move {
+            self.check_method("pluginProfile")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::PluginProfile { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Reads and zeroes a plugin's counters of handled and failed notifications,"]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn reset_plugin_metrics<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+    ) -> BoxFuture<'b, RpcResult<PluginInfo>> {
+        Box::pin(async move {
+            self.check_method("resetPluginMetrics")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::ResetPluginMetrics { id, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Applies a new config to the running plugin without reloading it."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn reconfigure_plugin<'a: 'b, 'b>(
+        &'a self,
+        id: String,
+        config: serde_json::Value,
+    ) -> BoxFuture<'b, RpcResult<()>> {
+        Box::pin(async move {
+            self.check_method("reconfigurePlugin")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::ReconfigurePlugin { id, config, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+
+    #[doc = " Explains which plugins would receive a notification of a given kind of given blocks,"]
+    #[doc = " by their number range or hashes."]
+    #[must_use]
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn explain_dispatch<'a: 'b, 'b>(
+        &'a self,
+        blocks: DispatchBlocks,
+        kind: ChainKind,
+    ) -> BoxFuture<'b, RpcResult<Vec<PluginDispatch>>> {
+        Box::pin(async move {
+            self.check_method("explainDispatch")?;
+            let (tx, rx) = oneshot::channel();
+            send_request(&self.tx, RpcRequest::ExplainDispatch { blocks, kind, tx }).await?;
+            process_request_rx(rx).await
+        })
+    }
+}
+
+/// Helper to send a request to ExEx plugin manager, awaiting the channel capacity in bounded mode.
+pub(crate) async fn send_request(tx: &Sender<RpcRequest>, req: RpcRequest) -> RpcResult<()> {
+    tx.send_async(req).await.map_err(|_| {
+        RpcError::owned(
+            INTERNAL_ERROR_CODE,
+            "ExEx plugin manager request channel rx was dropped.",
+            None::<()>,
+        )
+    })
 }
 
 /// Helper to process response from polled [`oneshot::Receiver`]
-async fn process_request_rx<T>(rx: oneshot::Receiver<RpcResult<T>>) -> RpcResult<T> {
+pub(crate) async fn process_request_rx<T>(rx: oneshot::Receiver<RpcResult<T>>) -> RpcResult<T> {
     rx.await.map_err(|_| {
         RpcError::owned(
             INTERNAL_ERROR_CODE,
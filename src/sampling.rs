@@ -0,0 +1,38 @@
+//! Sampling of repeated plugin error logs.
+
+use std::time::{Duration, Instant};
+
+/// Default interval, within which repeated identical errors of a plugin are logged only once.
+pub const DEFAULT_ERROR_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Sampler of a plugin's error logs.
+///
+/// An error identical to the last logged one is suppressed until the interval since
+/// the last log elapses. Suppressed errors are counted, so the next logged error can be
+/// accompanied with a summary.
+#[derive(Debug, Default)]
+pub struct ErrorLogSampler {
+    /// The last logged error with its time.
+    last: Option<(String, Instant)>,
+    /// Number of errors suppressed since the last log.
+    suppressed: u64,
+}
+
+impl ErrorLogSampler {
+    /// Samples an error occurred at a given time.
+    ///
+    /// Returns `None` if the error must be suppressed, otherwise a number of errors
+    /// suppressed since the last log.
+    pub fn sample(&mut self, err: &str, interval: Duration, now: Instant) -> Option<u64> {
+        let repeated = self.last.as_ref().is_some_and(|(last_err, logged_at)| {
+            last_err == err && now.saturating_duration_since(*logged_at) < interval
+        });
+        if repeated {
+            self.suppressed += 1;
+            return None;
+        }
+
+        self.last = Some((err.to_owned(), now));
+        Some(std::mem::take(&mut self.suppressed))
+    }
+}
@@ -1,4 +1,4 @@
-//! Wrapper around [mpsc::UnboundedSender]
+//! Wrapper around [mpsc::UnboundedSender] or bounded [mpsc::Sender]
 //! with a `receiver_dropped` flag for keeping track of channel.
 
 use std::sync::{
@@ -6,32 +6,80 @@ use std::sync::{
     Arc,
 };
 
-use tokio::sync::mpsc;
+use tokio::sync::mpsc::{self, error::SendError};
 
 use reth_tracing::tracing::warn;
 
+#[derive(Debug, Clone)]
+enum Tx<T> {
+    Unbounded(mpsc::UnboundedSender<T>),
+    Bounded(mpsc::Sender<T>),
+}
+
 #[derive(Debug, Clone)]
 pub struct Sender<T: Send> {
     receiver_dropped: Arc<AtomicBool>,
-    tx: mpsc::UnboundedSender<T>,
+    tx: Tx<T>,
 }
 
 impl<T: Send> Sender<T> {
     pub fn new(tx: mpsc::UnboundedSender<T>) -> Self {
-        Self { receiver_dropped: Arc::new(AtomicBool::new(false)), tx }
+        Self { receiver_dropped: Arc::new(AtomicBool::new(false)), tx: Tx::Unbounded(tx) }
+    }
+
+    /// Sender over a bounded channel, see [`Self::send_async`] to await its capacity.
+    pub fn bounded(tx: mpsc::Sender<T>) -> Self {
+        Self { receiver_dropped: Arc::new(AtomicBool::new(false)), tx: Tx::Bounded(tx) }
     }
 }
 
 impl<T: Send> Sender<T> {
+    /// Sends a message without waiting.
+    ///
+    /// In bounded mode the message is dropped, if the channel is full.
     pub fn send(&self, msg: T) {
         if self.receiver_dropped() {
             return;
         }
 
-        if let Err(e) = self.tx.send(msg) {
-            warn!("[Sender] Receiver was dropped on error while send. Error: {e}");
+        match &self.tx {
+            Tx::Unbounded(tx) => {
+                if let Err(e) = tx.send(msg) {
+                    warn!("[Sender] Receiver was dropped on error while send. Error: {e}");
+                    self.receiver_dropped.store(true, Ordering::SeqCst);
+                }
+            }
+            Tx::Bounded(tx) => match tx.try_send(msg) {
+                Ok(()) => {}
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    warn!("[Sender] Channel is full, message was dropped.");
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    warn!("[Sender] Receiver was dropped on error while send.");
+                    self.receiver_dropped.store(true, Ordering::SeqCst);
+                }
+            },
+        }
+    }
+
+    /// Sends a message, awaiting available capacity in bounded mode.
+    ///
+    /// Returns the message back, if the receiver was dropped.
+    pub async fn send_async(&self, msg: T) -> Result<(), SendError<T>> {
+        if self.receiver_dropped() {
+            return Err(SendError(msg));
+        }
+
+        let res = match &self.tx {
+            Tx::Unbounded(tx) => tx.send(msg),
+            Tx::Bounded(tx) => tx.send(msg).await,
+        };
+        if res.is_err() {
+            warn!("[Sender] Receiver was dropped on error while send.");
             self.receiver_dropped.store(true, Ordering::SeqCst);
         }
+
+        res
     }
 
     pub fn send_many(&self, msgs: Vec<T>) {
@@ -39,12 +87,48 @@ impl<T: Send> Sender<T> {
             return;
         }
 
-        msgs.into_iter().for_each(|msg| {
-            let _ = self.tx.send(msg);
-        })
+        msgs.into_iter().for_each(|msg| self.send(msg))
     }
 
-    fn receiver_dropped(&self) -> bool {
+    /// Returns `true` if the receiver was observed dropped.
+    pub fn receiver_dropped(&self) -> bool {
         self.receiver_dropped.load(Ordering::SeqCst)
     }
 }
+
+/// Receiving half of a channel for [`Sender`], either unbounded or bounded.
+#[derive(Debug)]
+pub enum Receiver<T> {
+    Unbounded(mpsc::UnboundedReceiver<T>),
+    Bounded(mpsc::Receiver<T>),
+}
+
+impl<T> Receiver<T> {
+    /// Receives the next message, see [`mpsc::Receiver::recv`].
+    pub async fn recv(&mut self) -> Option<T> {
+        match self {
+            Self::Unbounded(rx) => rx.recv().await,
+            Self::Bounded(rx) => rx.recv().await,
+        }
+    }
+
+    /// Receives the next message if one is available, see [`mpsc::Receiver::try_recv`].
+    pub fn try_recv(&mut self) -> Option<T> {
+        match self {
+            Self::Unbounded(rx) => rx.try_recv().ok(),
+            Self::Bounded(rx) => rx.try_recv().ok(),
+        }
+    }
+}
+
+impl<T> From<mpsc::UnboundedReceiver<T>> for Receiver<T> {
+    fn from(rx: mpsc::UnboundedReceiver<T>) -> Self {
+        Self::Unbounded(rx)
+    }
+}
+
+impl<T> From<mpsc::Receiver<T>> for Receiver<T> {
+    fn from(rx: mpsc::Receiver<T>) -> Self {
+        Self::Bounded(rx)
+    }
+}
@@ -0,0 +1,75 @@
+//! Persisted state of the [`ExExPluginManager`](crate::ExExPluginManager).
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+/// A persisted set of loaded plugins, restored on the node restart.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManagerState {
+    pub plugins: Vec<PluginState>,
+}
+
+/// A persisted loaded plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginState {
+    /// Path of the plugin's library.
+    pub path: PathBuf,
+    /// Preferred tracing level of the plugin's span.
+    pub log_level: Option<String>,
+    /// Initialization priority of the plugin, plugins with lower values are loaded first.
+    #[serde(default)]
+    pub priority: i32,
+    /// [Config](crate::ExExPlugin::on_config) of the plugin, if it's loaded with one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_json::Value>,
+    /// Ids of chains the plugin is loaded on by a [manifest], all if `None`.
+    ///
+    /// So a single manifest is shipped to a multi-network deployment, e.g. with some plugins
+    /// loaded only on mainnet.
+    ///
+    /// [manifest]: crate::ExExPluginManager::apply_manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chains: Option<Vec<u64>>,
+}
+
+impl PluginState {
+    /// Returns `true` if the plugin is loaded on a chain with a given id.
+    pub fn loads_on(&self, chain_id: u64) -> bool {
+        self.chains.as_ref().map_or(true, |chains| chains.contains(&chain_id))
+    }
+}
+
+impl ManagerState {
+    /// Reads a state from the given file.
+    ///
+    /// Returns `None` if the file doesn't exist.
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Returns plugins in their load order, i.e. sorted by priority, with ties broken by path.
+    pub fn load_order(&self) -> Vec<&PluginState> {
+        let mut plugins = self.plugins.iter().collect::<Vec<_>>();
+        plugins.sort_by(|a, b| a.priority.cmp(&b.priority).then_with(|| a.path.cmp(&b.path)));
+        plugins
+    }
+
+    /// Writes a state into the given file.
+    ///
+    /// Writes into a temporary file first, so the state file is never left half-written.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+}
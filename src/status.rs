@@ -0,0 +1,86 @@
+//! Liveness status of the [`ExExPluginManager`](crate::ExExPluginManager).
+
+use reth::primitives::B256;
+use reth_exex::ExExNotification;
+use serde::{Deserialize, Serialize};
+
+use crate::{ChainKind, NetworkLabel};
+
+/// Uptime and last activity of the manager, e.g. to detect a stalled manager on a dashboard.
+///
+/// A manager whose last notification is long ago despite the chain progress is stalled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManagerStatus {
+    /// Network of the node the manager is attached to.
+    pub network: NetworkLabel,
+    /// Time since the manager was created, in milliseconds.
+    pub uptime_ms: u64,
+    /// Number of the node's head block, as seen by the manager.
+    pub head_number: u64,
+    /// Number of loaded plugins.
+    pub plugins: usize,
+    /// Time since the manager last received a notification, in milliseconds,
+    /// `None` if it hasn't received any yet.
+    pub last_notification_ms: Option<u64>,
+    /// Time since the manager last processed an RPC request, in milliseconds,
+    /// `None` if it hasn't processed any yet.
+    pub last_rpc_request_ms: Option<u64>,
+    /// Counters of notifications received by the manager.
+    pub notifications: NotificationStats,
+    /// [Sequence number](crate::NodeInfo::sequence) of the last dispatched notification,
+    /// `0` if none was dispatched yet.
+    pub sequence: u64,
+}
+
+/// Static information about the manager, e.g. to identify a node in a multi-node deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerInfo {
+    /// Network of the node the manager is attached to.
+    pub network: NetworkLabel,
+    /// Version of the manager's crate.
+    pub version: String,
+    /// Version of the plugin ABI the manager loads, see
+    /// [`EXEX_PLUGIN_ABI_VERSION`](crate::EXEX_PLUGIN_ABI_VERSION).
+    pub abi_version: u32,
+    /// Public ed25519 key plugins' outputs are signed with, `None` if the manager has no
+    /// [signing key](crate::ExExPluginManager::with_signing_key).
+    pub public_key: Option<B256>,
+}
+
+/// Readiness of the manager, aggregated from states of its required plugins, e.g. for
+/// a readiness probe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Readiness {
+    /// Whether all required plugins are loaded, not paused, not failing and healthy.
+    pub ready: bool,
+    /// Reasons the manager isn't ready, empty if it's ready.
+    pub reasons: Vec<String>,
+}
+
+/// Counters of notifications received from the node, recorded regardless of loaded plugins.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationStats {
+    /// Number of received commits.
+    pub commits: u64,
+    /// Number of received reverts.
+    pub reverts: u64,
+    /// Number of received reorgs.
+    pub reorgs: u64,
+    /// Highest committed block number, `None` if no block was committed yet.
+    pub highest_block: Option<u64>,
+}
+
+impl NotificationStats {
+    /// Records a received notification.
+    pub(crate) fn record(&mut self, notification: &ExExNotification) {
+        match ChainKind::from(notification) {
+            ChainKind::Commit => self.commits += 1,
+            ChainKind::Revert => self.reverts += 1,
+            ChainKind::Reorg => self.reorgs += 1,
+        }
+        if let Some(committed) = notification.committed_chain() {
+            let tip = committed.tip().number;
+            self.highest_block = Some(self.highest_block.map_or(tip, |highest| highest.max(tip)));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Declarative subscription of plugins to chain events.
+
+use std::collections::HashSet;
+
+use reth_exex::ExExNotification;
+
+use crate::{BlockRange, ChainKind, FilterReason, TxFilter};
+
+/// Chain events a plugin [subscribes](crate::ExExPlugin::subscription) to.
+///
+/// A notification is dispatched to the plugin only if it matches all of the set filters,
+/// an empty spec matches every notification.
+///
+/// # Example
+///
+/// ```rust
+/// use reth_exex_plugin::{BlockRange, ChainKind, SubscriptionSpec};
+///
+/// // Commits of blocks from 100 on, at most one per 10 blocks
+/// let spec = SubscriptionSpec::default()
+///     .with_blocks(BlockRange { from: 100, to: u64::MAX })
+///     .with_kind(ChainKind::Commit)
+///     .with_min_interval(10);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriptionSpec {
+    /// Blocks to match, by any of the notification's reverted or committed blocks.
+    pub blocks: Option<BlockRange>,
+    /// [`ChainKind`]s to match, all kinds if empty.
+    ///
+    /// The manager matches them together with the plugin's
+    /// [notification kinds](crate::ExExPluginManager::set_plugin_notification_kinds).
+    pub kinds: HashSet<ChainKind>,
+    /// Filter of committed transactions, the plugin's [`ExExPlugin::transaction_filter`]
+    /// if unset.
+    ///
+    /// [`ExExPlugin::transaction_filter`]: crate::ExExPlugin::transaction_filter
+    pub tx_filter: Option<TxFilter>,
+    /// Minimum number of blocks between tips of dispatched commits.
+    ///
+    /// Commits are throttled only, reverts and reorgs are always delivered and restart
+    /// the interval.
+    pub min_interval: Option<u64>,
+}
+
+impl SubscriptionSpec {
+    /// Sets blocks to match.
+    pub fn with_blocks(mut self, blocks: BlockRange) -> Self {
+        self.blocks = Some(blocks);
+        self
+    }
+
+    /// Adds a [`ChainKind`] to match.
+    pub fn with_kind(mut self, kind: ChainKind) -> Self {
+        self.kinds.insert(kind);
+        self
+    }
+
+    /// Sets a filter of committed transactions.
+    pub fn with_tx_filter(mut self, tx_filter: TxFilter) -> Self {
+        self.tx_filter = Some(tx_filter);
+        self
+    }
+
+    /// Sets a minimum number of blocks between tips of dispatched commits.
+    pub fn with_min_interval(mut self, blocks: u64) -> Self {
+        self.min_interval = Some(blocks);
+        self
+    }
+
+    /// Returns `true` if the notification matches the spec, given a tip of the last
+    /// dispatched commit for the [min interval](Self::min_interval).
+    ///
+    /// # Cost
+    ///
+    /// Block ranges and kinds are checked first, the [transaction filter](Self::tx_filter)
+    /// only for notifications matching them.
+    pub fn matches(&self, notification: &ExExNotification, last_commit: Option<u64>) -> bool {
+        self.mismatch(notification, last_commit).is_none()
+    }
+
+    /// Returns `true` if notifications of the kind match the spec, given kinds set for the plugin
+    /// at runtime as a bitmask of [`ChainKind::bit`]s.
+    pub(crate) fn matches_kind(&self, kind: ChainKind, runtime_kinds: u8) -> bool {
+        let kinds = if self.kinds.is_empty() {
+            u8::MAX
+        } else {
+            self.kinds.iter().fold(0, |bits, kind| bits | kind.bit())
+        };
+        kinds & runtime_kinds & kind.bit() != 0
+    }
+
+    /// Returns a reason the notification doesn't [match](Self::matches) the spec, if any.
+    pub(crate) fn mismatch(
+        &self,
+        notification: &ExExNotification,
+        last_commit: Option<u64>,
+    ) -> Option<FilterReason> {
+        if !self.matches_kind(ChainKind::from(notification), u8::MAX) {
+            return Some(FilterReason::KindOptOut);
+        }
+        if let Some(blocks) = self.blocks {
+            let overlaps = |from: u64, to: u64| from <= blocks.to && blocks.from <= to;
+            let reverted = notification.reverted_chain();
+            let committed = notification.committed_chain();
+            if ![reverted, committed]
+                .into_iter()
+                .flatten()
+                .any(|chain| overlaps(chain.first().number, chain.tip().number))
+            {
+                return Some(FilterReason::BlockFilter);
+            }
+        }
+
+        let ExExNotification::ChainCommitted { new } = notification else { return None };
+        if let (Some(interval), Some(last)) = (self.min_interval, last_commit) {
+            if new.tip().number < last.saturating_add(interval) {
+                return Some(FilterReason::RateLimit);
+            }
+        }
+
+        match &self.tx_filter {
+            Some(filter) if !filter.matches_chain(new) => Some(FilterReason::TxFilter),
+            _ => None,
+        }
+    }
+}
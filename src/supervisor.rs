@@ -0,0 +1,53 @@
+//! Restart policy of the supervised ExEx plugin manager.
+
+use std::{any::Any, time::Duration};
+
+/// Bounded restart policy with an exponential backoff.
+///
+/// See [`ExExPluginManager::run_supervised`](crate::ExExPluginManager::run_supervised).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Maximum number of restarts, after which the manager's error is returned.
+    pub max_restarts: u32,
+    /// Backoff before the first restart.
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff, which is doubled on every restart.
+    pub max_backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Policy which never restarts the manager.
+    pub const fn never() -> Self {
+        Self { max_restarts: 0, initial_backoff: Duration::ZERO, max_backoff: Duration::ZERO }
+    }
+
+    /// Returns a backoff before the restart with a given number of `restarts` already done,
+    /// or `None` if restarts are exhausted.
+    pub fn backoff(&self, restarts: u32) -> Option<Duration> {
+        if restarts >= self.max_restarts {
+            return None;
+        }
+
+        let factor = 2u32.checked_pow(restarts).unwrap_or(u32::MAX);
+        Some(self.initial_backoff.saturating_mul(factor).min(self.max_backoff))
+    }
+}
+
+/// Helper to extract a message from a caught panic payload.
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
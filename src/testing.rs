@@ -0,0 +1,273 @@
+//! Helpers for deterministic testing of plugins without a live node.
+
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    path::Path,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use eyre::Result;
+use tokio_util::sync::CancellationToken;
+
+use reth::{
+    primitives::{
+        BlockBody, BlockNumHash, Header, SealedBlock, SealedBlockWithSenders, SealedHeader, B256,
+    },
+    providers::{Chain, ExecutionOutcome},
+};
+use reth_exex::ExExNotification;
+
+use crate::{BlockRange, ChainKind, ExExPlugin, NodeInfo, NormalizedNotification};
+
+/// Chain id of [`NodeInfo`] passed to plugins on replay.
+pub const REPLAY_CHAIN_ID: u64 = 1;
+
+/// Id of [`RecordingExExPlugin`] by default.
+pub const RECORDING_EXEX_PLUGIN_ID: &str = "RecordingExEx";
+
+/// In-memory plugin which records all notifications it receives in normalized form.
+///
+/// Clones share the recorded notifications, so a clone can be kept to assert on them
+/// after the plugin is [registered](crate::ExExPluginManager::register_plugin) on manager,
+/// e.g. which notifications passed the manager's filtering.
+#[derive(Debug, Clone)]
+pub struct RecordingExExPlugin {
+    id: &'static str,
+    recorded: Arc<Mutex<Vec<NormalizedNotification>>>,
+}
+
+impl Default for RecordingExExPlugin {
+    fn default() -> Self {
+        Self::new(RECORDING_EXEX_PLUGIN_ID)
+    }
+}
+
+impl RecordingExExPlugin {
+    /// Creates a recording plugin with a given id.
+    pub fn new(id: &'static str) -> Self {
+        Self { id, recorded: Arc::default() }
+    }
+
+    /// Returns all recorded notifications in the order they were received.
+    pub fn notifications(&self) -> Vec<NormalizedNotification> {
+        self.recorded.lock().unwrap().clone()
+    }
+}
+
+impl ExExPlugin for RecordingExExPlugin {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            self.recorded.lock().unwrap().push(NormalizedNotification::from(notification.as_ref()));
+            Ok(())
+        })
+    }
+}
+
+/// Replays notifications recorded in a given file to the plugin in order.
+///
+/// The file is JSONL of [`NormalizedNotification`]s, i.e. the same schema that
+/// [`SocketExExPlugin`](crate::SocketExExPlugin) streams. Each of them is reconstructed
+/// into a [synthetic](synthetic_notification) notification and dispatched like the manager
+/// does, so reorgs go to [`ExExPlugin::on_reorg`].
+///
+/// Returns a number of replayed notifications.
+pub async fn replay_from_file(path: impl AsRef<Path>, plugin: &dyn ExExPlugin) -> Result<usize> {
+    let recorded = std::fs::read_to_string(path)?;
+
+    let mut replayed = 0;
+    for line in recorded.lines().filter(|line| !line.trim().is_empty()) {
+        let notification = Arc::new(synthetic_notification(&serde_json::from_str(line)?)?);
+        let node_info = replay_node_info(&notification, replayed as u64 + 1);
+
+        match notification.as_ref() {
+            ExExNotification::ChainReorged { old, new } => {
+                let cancel = CancellationToken::new();
+                plugin
+                    .on_reorg(old.range(), new.range(), notification.clone(), &node_info, cancel)
+                    .await?;
+            }
+            _ => plugin.handle_notification(notification.clone(), &node_info).await?,
+        }
+        replayed += 1;
+    }
+
+    Ok(replayed)
+}
+
+/// Reconstructs a synthetic [`ExExNotification`] from its normalized representation.
+///
+/// Both ranges make a reorg, otherwise it's either a commit or a revert.
+/// The notification's [kind](crate::ChainKind) must match its ranges.
+pub fn synthetic_notification(normalized: &NormalizedNotification) -> Result<ExExNotification> {
+    let notification = match (normalized.reverted, normalized.committed) {
+        (Some(reverted), Some(committed)) => ExExNotification::ChainReorged {
+            old: synthetic_chain(reverted)?.into(),
+            new: synthetic_chain(committed)?.into(),
+        },
+        (None, Some(committed)) => {
+            ExExNotification::ChainCommitted { new: synthetic_chain(committed)?.into() }
+        }
+        (Some(reverted), None) => {
+            ExExNotification::ChainReverted { old: synthetic_chain(reverted)?.into() }
+        }
+        (None, None) => eyre::bail!("notification has neither reverted nor committed blocks"),
+    };
+
+    let kind = ChainKind::from(&notification);
+    if normalized.kind != kind {
+        eyre::bail!("{:?} notification has ranges of {kind:?}", normalized.kind);
+    }
+
+    Ok(notification)
+}
+
+/// Creates a chain of empty blocks in a given range, linked by their parent hashes.
+pub fn synthetic_chain(range: BlockRange) -> Result<Chain> {
+    if range.from > range.to {
+        eyre::bail!("invalid block range: {}..={}", range.from, range.to);
+    }
+
+    let mut parent_hash = B256::ZERO;
+    let blocks = (range.from..=range.to).map(|number| {
+        let block = synthetic_block(number, parent_hash, 0);
+        parent_hash = block.hash();
+        block
+    });
+
+    Ok(Chain::new(blocks, ExecutionOutcome::default(), None))
+}
+
+/// Creates an empty block with a given number and parent hash.
+///
+/// The timestamp salts the block's hash, e.g. to tell apart blocks of competing forks.
+fn synthetic_block(number: u64, parent_hash: B256, timestamp: u64) -> SealedBlockWithSenders {
+    let header = Header { number, parent_hash, timestamp, ..Default::default() };
+    let hash = header.hash_slow();
+    SealedBlockWithSenders {
+        block: SealedBlock::new(SealedHeader::new(header, hash), BlockBody::default()),
+        senders: Vec::new(),
+    }
+}
+
+/// Deterministic source of notifications over consecutive empty blocks, linked by their
+/// parent hashes across notifications.
+///
+/// Each generated notification commits the next blocks on top of the generator's tip,
+/// while [reorgs](Self::reorg) replace the blocks above a chosen fork block with a new fork.
+/// The same calls always produce the same blocks, so it suits both the crate's tests and
+/// tests of downstream plugins.
+///
+/// Iterating over the generator commits one block per notification.
+///
+/// # Example
+///
+/// ```rust
+/// use reth_exex_plugin::testing::NotificationGenerator;
+///
+/// let mut generator = NotificationGenerator::new(1);
+/// let commits = generator.by_ref().take(3).collect::<Vec<_>>();
+/// // Replaces block 3 with two blocks of a new fork
+/// let reorg = generator.reorg(2, 2).unwrap();
+/// assert_eq!(generator.tip().unwrap().number, 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NotificationGenerator {
+    /// Number of the first generated block.
+    start: u64,
+    /// Canonical generated blocks by their number.
+    blocks: BTreeMap<u64, SealedBlockWithSenders>,
+    /// Number of forks, which salts timestamps of their blocks.
+    forks: u64,
+}
+
+impl NotificationGenerator {
+    /// Creates a generator, which starts from a given block number.
+    pub fn new(start: u64) -> Self {
+        Self { start, blocks: BTreeMap::new(), forks: 0 }
+    }
+
+    /// Returns the latest canonical generated block, if any.
+    pub fn tip(&self) -> Option<BlockNumHash> {
+        self.blocks.last_key_value().map(|(_, block)| block.num_hash())
+    }
+
+    /// Commits a given number of next blocks in a single notification.
+    ///
+    /// Returns an error if the number is zero.
+    pub fn commit(&mut self, count: u64) -> Result<ExExNotification> {
+        Ok(ExExNotification::ChainCommitted { new: self.extend(count)?.into() })
+    }
+
+    /// Reverts blocks above a given fork block and commits a given number of new blocks
+    /// on top of it, in a single notification.
+    ///
+    /// Returns an error if there are no generated blocks above the fork block, it's below the
+    /// first generated one or the number of new blocks is zero.
+    pub fn reorg(&mut self, fork: u64, count: u64) -> Result<ExExNotification> {
+        if self.tip().map_or(true, |tip| tip.number <= fork) {
+            eyre::bail!("no generated blocks above fork block {fork}");
+        }
+        if fork + 1 < self.start {
+            eyre::bail!("fork block {fork} is below the first generated block {}", self.start);
+        }
+        if count == 0 {
+            eyre::bail!("reorg must commit at least one block");
+        }
+
+        let reverted = self.blocks.split_off(&(fork + 1)).into_values();
+        let old = Chain::new(reverted, ExecutionOutcome::default(), None);
+        self.forks += 1;
+
+        Ok(ExExNotification::ChainReorged { old: old.into(), new: self.extend(count)?.into() })
+    }
+
+    /// Generates a given number of blocks on top of the tip.
+    fn extend(&mut self, count: u64) -> Result<Chain> {
+        if count == 0 {
+            eyre::bail!("chain must have at least one block");
+        }
+
+        let (mut number, mut parent_hash) =
+            self.tip().map_or((self.start, B256::ZERO), |tip| (tip.number + 1, tip.hash));
+        let mut blocks = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let block = synthetic_block(number, parent_hash, self.forks);
+            parent_hash = block.hash();
+            number += 1;
+            self.blocks.insert(block.number, block.clone());
+            blocks.push(block);
+        }
+
+        Ok(Chain::new(blocks, ExecutionOutcome::default(), None))
+    }
+}
+
+impl Iterator for NotificationGenerator {
+    type Item = ExExNotification;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.commit(1).ok()
+    }
+}
+
+/// Node info on replay, with the head after a given notification and its sequence number.
+fn replay_node_info(notification: &ExExNotification, sequence: u64) -> NodeInfo {
+    let head = match notification.committed_chain() {
+        Some(committed) => committed.tip().num_hash(),
+        None => {
+            notification.reverted_chain().map(|reverted| reverted.fork_block()).unwrap_or_default()
+        }
+    };
+
+    NodeInfo { chain_id: REPLAY_CHAIN_ID, head_number: head.number, head_hash: head.hash, sequence }
+}
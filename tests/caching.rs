@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use reth::primitives::B256;
+use reth_exex_plugin::{
+    testing::synthetic_chain, BlockRange, CachingExExPlugin, ExExNotification, ExExPlugin, NodeInfo,
+};
+
+/// Caching plugin which indexes a block number of each block.
+fn block_numbers() -> CachingExExPlugin<u64> {
+    CachingExExPlugin::new("BlockNumbersExEx", |block, _| block.number)
+}
+
+/// Dispatches a notification to the plugin.
+async fn handle(plugin: &dyn ExExPlugin, notification: ExExNotification) -> Result<()> {
+    let node_info = NodeInfo { chain_id: 1, head_number: 0, head_hash: B256::ZERO, sequence: 1 };
+    plugin.handle_notification(Arc::new(notification), &node_info).await
+}
+
+#[tokio::test]
+async fn should_evict_reverted_blocks_from_cache() -> Result<()> {
+    let plugin = block_numbers();
+
+    let committed = synthetic_chain(BlockRange { from: 1, to: 4 })?;
+    handle(&plugin, ExExNotification::ChainCommitted { new: committed.into() }).await?;
+    assert_eq!(plugin.len(), 4);
+    assert_eq!(plugin.get(3), Some(3));
+
+    let reverted = synthetic_chain(BlockRange { from: 3, to: 4 })?;
+    handle(&plugin, ExExNotification::ChainReverted { old: reverted.into() }).await?;
+    assert_eq!(plugin.range(0..=10), vec![(1, 1), (2, 2)]);
+    assert_eq!(plugin.get(3), None);
+
+    // Queried through a command
+    let value = plugin.command("get".to_owned(), serde_json::json!({ "number": 2 })).await?;
+    assert_eq!(value, serde_json::json!(2));
+    let value = plugin.command("get".to_owned(), serde_json::json!({ "number": 4 })).await?;
+    assert!(value.is_null());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_evict_lowest_blocks_over_capacity() -> Result<()> {
+    let plugin = block_numbers().with_capacity(2);
+
+    let committed = synthetic_chain(BlockRange { from: 1, to: 3 })?;
+    handle(&plugin, ExExNotification::ChainCommitted { new: committed.into() }).await?;
+    assert_eq!(plugin.range(0..=10), vec![(2, 2), (3, 3)]);
+
+    Ok(())
+}
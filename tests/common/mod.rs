@@ -0,0 +1,46 @@
+//! Helpers shared by integration tests.
+
+use std::{
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use eyre::Result;
+
+/// Returns a path of the dynamic library of an in-tree example plugin by its crate name,
+/// e.g. `minimal`, building it in release mode first if it isn't built yet.
+///
+/// The library is named by the platform's conventions, e.g. `libminimal.so` on Linux,
+/// `libminimal.dylib` on macOS and `minimal.dll` on Windows, so tests don't hardcode it.
+pub fn example_library(name: &str) -> Result<PathBuf> {
+    build_library(&Path::new(env!("CARGO_MANIFEST_DIR")).join("examples"), name)
+}
+
+/// Returns a path of the dynamic library of a fixture plugin under `tests/fixtures` by its crate
+/// name, building it like [`example_library`].
+#[allow(dead_code)] // not every test crate loads fixtures
+pub fn fixture_library(name: &str) -> Result<PathBuf> {
+    build_library(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures"), name)
+}
+
+/// Returns a path of the dynamic library of a plugin crate in a given directory, building it in
+/// release mode first if it isn't built yet.
+fn build_library(dir: &Path, name: &str) -> Result<PathBuf> {
+    let krate = dir.join(name);
+    let path = krate.join("target").join("release").join(format!("{DLL_PREFIX}{name}{DLL_SUFFIX}"));
+    if path.exists() {
+        return Ok(path);
+    }
+
+    // `CARGO` is set to the cargo running the tests, so the plugin is built by the same one
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = Command::new(cargo)
+        .args(["build", "--release", "--manifest-path"])
+        .arg(krate.join("Cargo.toml"))
+        .status()?;
+    eyre::ensure!(status.success(), "Failed to build plugin `{name}`: {status}");
+    eyre::ensure!(path.exists(), "Plugin `{name}` has no library at {}", path.display());
+
+    Ok(path)
+}
@@ -0,0 +1,37 @@
+//! Fixture plugin for integration tests.
+//!
+//! Depends on the `minimal` example plugin and requires the node's `debug` RPC namespace,
+//!     declaring both in its static metadata as well.
+
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use eyre::Result;
+use reth_exex_plugin::{ExExNotification, ExExPlugin, NodeInfo};
+
+#[derive(Debug, Default)]
+pub(crate) struct DependentExEx;
+
+impl ExExPlugin for DependentExEx {
+    fn id(&self) -> &'static str {
+        "DependentExEx"
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["MinimalExEx"]
+    }
+
+    fn required_node_rpc_namespaces(&self) -> &'static [&'static str] {
+        &["debug"]
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move { Ok(()) })
+    }
+}
+
+reth_exex_plugin::declare_exex_plugin!(DependentExEx);
+reth_exex_plugin::declare_exex_plugin_metadata!(id: "DependentExEx", depends_on: ["MinimalExEx"]);
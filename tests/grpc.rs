@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use reth_exex_plugin::{
+    proto::{
+        plugin_manager_client::PluginManagerClient, ListPluginsRequest, LoadPluginRequest,
+        ManagerStatusRequest, UnloadPluginRequest,
+    },
+    ExExPluginGrpc, ExExPluginManager,
+};
+use reth_exex_test_utils::test_exex_context;
+use tonic::transport::{Channel, Server};
+
+mod common;
+
+/// Helper to connect to a gRPC server which may not be listening yet
+async fn connect(addr: std::net::SocketAddr) -> eyre::Result<PluginManagerClient<Channel>> {
+    let mut attempts = 0;
+    loop {
+        match PluginManagerClient::connect(format!("http://{addr}")).await {
+            Ok(client) => return Ok(client),
+            Err(_) if attempts < 50 => {
+                attempts += 1;
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn should_load_list_and_unload_plugins_over_grpc() -> eyre::Result<()> {
+    let (rpc_request_tx, rpc_request_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    tokio::spawn(plugin_manager.run());
+
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+    let service = ExExPluginGrpc::new(rpc_request_tx).into_service();
+    tokio::spawn(Server::builder().add_service(service).serve(addr));
+    let mut client = connect(addr).await?;
+
+    let id = client
+        .load_plugin(LoadPluginRequest {
+            plugin_path: common::example_library("minimal")?.display().to_string(),
+            log_level: None,
+            config: None,
+            expected_id: Some("MinimalExEx".to_owned()),
+        })
+        .await?
+        .into_inner()
+        .id;
+    assert_eq!(id, "MinimalExEx");
+
+    let ids = client.list_plugins(ListPluginsRequest {}).await?.into_inner().ids;
+    assert_eq!(ids, vec![id.clone()]);
+    let status = client.manager_status(ManagerStatusRequest {}).await?.into_inner();
+    assert_eq!(status.plugins, 1);
+
+    client.unload_plugin(UnloadPluginRequest { id: id.clone() }).await?;
+    assert!(client.list_plugins(ListPluginsRequest {}).await?.into_inner().ids.is_empty());
+
+    // Manager errors are surfaced as gRPC statuses
+    let status = client.unload_plugin(UnloadPluginRequest { id }).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Internal);
+
+    Ok(())
+}
@@ -0,0 +1,3565 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    ops::RangeInclusive,
+    path::PathBuf,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use eyre::{OptionExt, Result};
+use reth::{
+    chainspec::EthChainSpec,
+    primitives::{
+        Address, BlockNumHash, Log, Receipt, Signature, Transaction, TransactionSigned, TxKind,
+        TxLegacy, B256,
+    },
+    providers::{BlockWriter, CanonChainTracker, Chain, ExecutionOutcome, StorageLocation},
+};
+use reth_exex::ExExEvent;
+use reth_exex_plugin::{
+    testing::{
+        synthetic_chain, synthetic_notification, NotificationGenerator, RecordingExExPlugin,
+    },
+    AuditSink, BlockRange, CancellationToken, ChainKind, CoalesceConfig, DeadLetterRecord,
+    DeadLetterSink, DispatchBlocks, ErrorLogSampler, ExExNotification, ExExPlugin,
+    ExExPluginManager, ExExPluginRpc, ExExRpcPluginApiServer, FilterReason, FinishedHeightRecord,
+    LogStream, MetricsSnapshot, NetworkLabel, NodeInfo, NormalizedNotification, NotificationStats,
+    PausePolicy, PluginDispatch, PluginErrorEvent, PluginHook, PluginLog, PluginLogLine,
+    PluginSigner, PluginTasks, QueuedExExPlugin, Readiness, RestartPolicy, RetryExExPlugin,
+    RpcRequest, ShardedSink, SubscriptionSpec, TxFilter, COALESCE_CAPABILITY,
+};
+use reth_exex_test_utils::{test_exex_context, Adapter, TestExExHandle};
+use tokio::sync::{mpsc, oneshot};
+
+/// Plugin which fails on every notification.
+#[derive(Debug)]
+struct FailingExEx {
+    warmup: u64,
+    required: bool,
+}
+
+impl ExExPlugin for FailingExEx {
+    fn id(&self) -> &'static str {
+        "FailingExEx"
+    }
+
+    fn warmup(&self) -> u64 {
+        self.warmup
+    }
+
+    fn is_required(&self) -> bool {
+        self.required
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { eyre::bail!("not ready") })
+    }
+}
+
+/// Plugin which records a [`NodeInfo`] of the last notification.
+#[derive(Debug, Default)]
+struct NodeInfoExEx {
+    last: Arc<Mutex<Option<NodeInfo>>>,
+}
+
+impl ExExPlugin for NodeInfoExEx {
+    fn id(&self) -> &'static str {
+        "NodeInfoExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            *self.last.lock().unwrap() = Some(*node_info);
+            Ok(())
+        })
+    }
+}
+
+/// Reverted and committed block ranges of a reorg.
+type ReorgRanges = (RangeInclusive<u64>, RangeInclusive<u64>);
+
+/// Plugin which records reorgs and counts other notifications.
+#[derive(Debug, Default)]
+struct ReorgExEx {
+    reorgs: Arc<Mutex<Vec<ReorgRanges>>>,
+    notifications: Arc<Mutex<usize>>,
+}
+
+impl ExExPlugin for ReorgExEx {
+    fn id(&self) -> &'static str {
+        "ReorgExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            *self.notifications.lock().unwrap() += 1;
+            Ok(())
+        })
+    }
+
+    fn on_reorg<'a: 'b, 'b>(
+        &'a self,
+        reverted: RangeInclusive<u64>,
+        committed: RangeInclusive<u64>,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+        _cancel: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        Box::pin(async move {
+            self.reorgs.lock().unwrap().push((reverted, committed));
+            Ok(None)
+        })
+    }
+}
+
+/// Plugin which reports a high-water mark and counts handled notifications.
+#[derive(Debug, Default)]
+struct IdempotentExEx {
+    last_processed: Option<u64>,
+    handled: Arc<Mutex<usize>>,
+}
+
+impl ExExPlugin for IdempotentExEx {
+    fn id(&self) -> &'static str {
+        "IdempotentExEx"
+    }
+
+    fn last_processed(&self) -> Option<u64> {
+        self.last_processed
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            *self.handled.lock().unwrap() += 1;
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which responds to a `ping` command.
+#[derive(Debug, Default)]
+struct PingExEx;
+
+impl ExExPlugin for PingExEx {
+    fn id(&self) -> &'static str {
+        "PingExEx"
+    }
+
+    fn command(
+        &self,
+        command: String,
+        params: serde_json::Value,
+    ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value>> + Send + '_>> {
+        Box::pin(async move {
+            match command.as_str() {
+                "ping" => Ok(serde_json::json!({ "pong": params })),
+                _ => eyre::bail!("unknown command: {command}"),
+            }
+        })
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Plugin which blocks the thread and records ticks of a concurrent task seen meanwhile.
+#[derive(Debug, Default)]
+struct BlockingExEx {
+    ticks: Arc<AtomicU64>,
+    ticked_while_blocked: Arc<AtomicU64>,
+}
+
+impl ExExPlugin for BlockingExEx {
+    fn id(&self) -> &'static str {
+        "BlockingExEx"
+    }
+
+    fn is_blocking(&self) -> bool {
+        true
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let before = self.ticks.load(Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(200));
+            let after = self.ticks.load(Ordering::SeqCst);
+            self.ticked_while_blocked.store(after - before, Ordering::SeqCst);
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which counts handled notifications matching its transaction filter.
+#[derive(Debug, Default)]
+struct FilteredExEx {
+    filter: TxFilter,
+    handled: Arc<Mutex<usize>>,
+}
+
+impl ExExPlugin for FilteredExEx {
+    fn id(&self) -> &'static str {
+        "FilteredExEx"
+    }
+
+    fn transaction_filter(&self) -> Option<TxFilter> {
+        Some(self.filter.clone())
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            *self.handled.lock().unwrap() += 1;
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which handles notifications in detached tasks.
+#[derive(Debug)]
+struct DetachedExEx {
+    tx: mpsc::UnboundedSender<Arc<ExExNotification>>,
+}
+
+impl ExExPlugin for DetachedExEx {
+    fn id(&self) -> &'static str {
+        "DetachedExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            tx.send(notification)
+        });
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Plugin which records its unload into a shared list.
+#[derive(Debug)]
+struct DependentExEx {
+    id: &'static str,
+    depends_on: &'static [&'static str],
+    unloaded: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl ExExPlugin for DependentExEx {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        self.depends_on
+    }
+
+    fn on_unload(&mut self) -> Result<()> {
+        self.unloaded.lock().unwrap().push(self.id);
+        Ok(())
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Plugin with given capabilities.
+#[derive(Debug)]
+struct CapableExEx {
+    id: &'static str,
+    capabilities: &'static [&'static str],
+}
+
+impl ExExPlugin for CapableExEx {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        self.capabilities
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Plugin requiring given namespaces of the node's RPC.
+#[derive(Debug)]
+struct RpcClientExEx {
+    id: &'static str,
+    namespaces: &'static [&'static str],
+}
+
+impl ExExPlugin for RpcClientExEx {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn required_node_rpc_namespaces(&self) -> &'static [&'static str] {
+        self.namespaces
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Plugin of a given version.
+#[derive(Debug)]
+struct VersionedExEx {
+    id: &'static str,
+    version: &'static str,
+}
+
+impl ExExPlugin for VersionedExEx {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn version(&self) -> &'static str {
+        self.version
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Required plugin, which fails its notifications and health checks while toggled.
+#[derive(Debug, Default)]
+struct ToggledExEx {
+    failing: Arc<AtomicBool>,
+}
+
+impl ExExPlugin for ToggledExEx {
+    fn id(&self) -> &'static str {
+        "ToggledExEx"
+    }
+
+    fn is_required(&self) -> bool {
+        true
+    }
+
+    fn health(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            eyre::ensure!(!self.failing.load(Ordering::SeqCst), "connection lost");
+            Ok(())
+        })
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            eyre::ensure!(!self.failing.load(Ordering::SeqCst), "connection lost");
+            Ok(())
+        })
+    }
+}
+
+/// Plugin with a health check which takes a given time, and fails if set.
+#[derive(Debug)]
+struct HealthExEx {
+    latency: Duration,
+    fail: bool,
+}
+
+impl ExExPlugin for HealthExEx {
+    fn id(&self) -> &'static str {
+        "HealthExEx"
+    }
+
+    fn health(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            tokio::time::sleep(self.latency).await;
+            if self.fail {
+                eyre::bail!("connection lost");
+            }
+            Ok(())
+        })
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Plugin with a slow initialization, which counts handled notifications.
+#[derive(Debug)]
+struct SlowLoadExEx {
+    load_time: Duration,
+    handled: Arc<Mutex<usize>>,
+}
+
+impl ExExPlugin for SlowLoadExEx {
+    fn id(&self) -> &'static str {
+        "SlowLoadExEx"
+    }
+
+    fn on_load<'a: 'b, 'b>(&'a mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            tokio::time::sleep(self.load_time).await;
+            Ok(())
+        })
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            *self.handled.lock().unwrap() += 1;
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which panics on its first tip, counting handled notifications.
+#[derive(Debug, Default)]
+struct PanickingTipExEx {
+    handled: Arc<AtomicU64>,
+    panicked: AtomicBool,
+}
+
+impl ExExPlugin for PanickingTipExEx {
+    fn id(&self) -> &'static str {
+        "PanickingTipExEx"
+    }
+
+    fn on_tip(&self, _tip: BlockNumHash) {
+        if !self.panicked.swap(true, Ordering::SeqCst) {
+            panic!("tip panicked");
+        }
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            self.handled.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which creates a file on load and fails, recording its cleanup hooks.
+#[derive(Debug)]
+struct PartialLoadExEx {
+    path: PathBuf,
+    hooks: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl ExExPlugin for PartialLoadExEx {
+    fn id(&self) -> &'static str {
+        "PartialLoadExEx"
+    }
+
+    fn on_load<'a: 'b, 'b>(&'a mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            std::fs::write(&self.path, b"half-created")?;
+            eyre::bail!("connection refused")
+        })
+    }
+
+    fn on_load_failed(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+        self.hooks.lock().unwrap().push("on_load_failed");
+    }
+
+    fn on_unload(&mut self) -> Result<()> {
+        self.hooks.lock().unwrap().push("on_unload");
+        Ok(())
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Signed records with their signatures.
+type SignedRecords = Arc<Mutex<Vec<(Vec<u8>, [u8; 64])>>>;
+
+/// Plugin which signs every notification's block ranges with the manager's key.
+#[derive(Debug, Default)]
+struct SigningExEx {
+    signer: Option<PluginSigner>,
+    signed: SignedRecords,
+}
+
+impl ExExPlugin for SigningExEx {
+    fn id(&self) -> &'static str {
+        "SigningExEx"
+    }
+
+    fn on_signer(&mut self, signer: PluginSigner) {
+        self.signer = Some(signer);
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let signer = self.signer.as_ref().ok_or_else(|| eyre::eyre!("no signer"))?;
+            let record = serde_json::to_vec(&NormalizedNotification::from(&*notification))?;
+            let signature = signer.sign(&record);
+            self.signed.lock().unwrap().push((record, signature));
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which spawns a never ending background task on load, holding a given sender.
+#[derive(Debug)]
+struct SpawningExEx {
+    tx: Option<oneshot::Sender<()>>,
+    tasks: Option<PluginTasks>,
+}
+
+impl ExExPlugin for SpawningExEx {
+    fn id(&self) -> &'static str {
+        "SpawningExEx"
+    }
+
+    fn on_runtime(&mut self, tasks: PluginTasks) {
+        self.tasks = Some(tasks);
+    }
+
+    fn on_load<'a: 'b, 'b>(&'a mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let tx = self.tx.take();
+            let tasks = self.tasks.as_ref().ok_or_else(|| eyre::eyre!("no runtime"))?;
+            tasks.spawn(async move {
+                let _tx = tx;
+                std::future::pending::<()>().await
+            });
+            Ok(())
+        })
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Plugin which takes a given time to handle a notification, counting handled ones.
+#[derive(Debug)]
+struct SlowExEx {
+    delay: Duration,
+    handled: Arc<AtomicU64>,
+}
+
+impl ExExPlugin for SlowExEx {
+    fn id(&self) -> &'static str {
+        "SlowExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            tokio::time::sleep(self.delay).await;
+            self.handled.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    }
+}
+
+/// Required plugin which fails a given number of notifications, recording tips of
+/// the handled ones.
+#[derive(Debug)]
+struct RecoveringExEx {
+    failures: AtomicU64,
+    idempotent: bool,
+    handled: Arc<Mutex<Vec<u64>>>,
+}
+
+impl ExExPlugin for RecoveringExEx {
+    fn id(&self) -> &'static str {
+        "RecoveringExEx"
+    }
+
+    fn is_required(&self) -> bool {
+        true
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.idempotent
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let failures = self.failures.load(Ordering::SeqCst);
+            if failures > 0 {
+                self.failures.store(failures - 1, Ordering::SeqCst);
+                eyre::bail!("not ready");
+            }
+            let tip = notification.committed_chain().map(|chain| chain.tip().number);
+            self.handled.lock().unwrap().extend(tip);
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which returns a number of committed blocks it has seen.
+#[derive(Debug, Default)]
+struct BlockCountExEx {
+    blocks: AtomicU64,
+}
+
+impl ExExPlugin for BlockCountExEx {
+    fn id(&self) -> &'static str {
+        "BlockCountExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn handle_notification_with_result<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        Box::pin(async move {
+            let Some(committed) = notification.committed_chain() else { return Ok(None) };
+            let blocks = self.blocks.fetch_add(committed.len() as u64, Ordering::Relaxed)
+                + committed.len() as u64;
+            Ok(Some(serde_json::json!({ "blocks": blocks })))
+        })
+    }
+}
+
+/// Plugin which buffers numbers of committed tips until flushed.
+#[derive(Debug, Default)]
+struct BufferingExEx {
+    buffer: Mutex<Vec<u64>>,
+    flushed: Arc<Mutex<Vec<u64>>>,
+}
+
+impl ExExPlugin for BufferingExEx {
+    fn id(&self) -> &'static str {
+        "BufferingExEx"
+    }
+
+    fn flush(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            let buffered = std::mem::take(&mut *self.buffer.lock().unwrap());
+            self.flushed.lock().unwrap().extend(buffered);
+            Ok(())
+        })
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            if let Some(committed) = notification.committed_chain() {
+                self.buffer.lock().unwrap().push(committed.tip().number);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which keeps its config, redacting an `api_key` of it.
+#[derive(Debug, Default)]
+struct ConfiguredExEx {
+    config: Arc<Mutex<Option<serde_json::Value>>>,
+}
+
+impl ExExPlugin for ConfiguredExEx {
+    fn id(&self) -> &'static str {
+        "ConfiguredExEx"
+    }
+
+    fn on_config(&mut self, config: serde_json::Value) -> Result<()> {
+        *self.config.lock().unwrap() = Some(config);
+        Ok(())
+    }
+
+    fn redact_config(&self, mut config: serde_json::Value) -> serde_json::Value {
+        if let Some(api_key) = config.get_mut("api_key") {
+            *api_key = "<redacted>".into();
+        }
+        config
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Plugin with a dedicated worker pool, which records names of threads handling notifications.
+#[derive(Debug, Default)]
+struct CpuBoundExEx {
+    threads: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl ExExPlugin for CpuBoundExEx {
+    fn id(&self) -> &'static str {
+        "CpuBoundExEx"
+    }
+
+    fn worker_threads(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let name = std::thread::current().name().map(str::to_owned);
+            self.threads.lock().unwrap().push(name);
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which finishes its work in a detached task once cancelled.
+#[derive(Debug, Default)]
+struct CancellableExEx {
+    cancelled: Arc<Mutex<Vec<u64>>>,
+}
+
+impl ExExPlugin for CancellableExEx {
+    fn id(&self) -> &'static str {
+        "CancellableExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { eyre::bail!("handled with cancellation") })
+    }
+
+    fn handle_notification_with_cancellation<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+        cancel: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<serde_json::Value>>> + Send + 'b>> {
+        let cancelled = self.cancelled.clone();
+        Box::pin(async move {
+            assert!(!cancel.is_cancelled(), "Token isn't cancelled mid-dispatch");
+            let tip = notification.committed_chain().map(|chain| chain.tip().number);
+            tokio::spawn(async move {
+                cancel.cancelled().await;
+                cancelled.lock().unwrap().extend(tip);
+            });
+            Ok(None)
+        })
+    }
+}
+
+/// Plugin which records notifications matching its subscription.
+#[derive(Debug)]
+struct SubscribedExEx {
+    spec: SubscriptionSpec,
+    recording: RecordingExExPlugin,
+}
+
+impl ExExPlugin for SubscribedExEx {
+    fn id(&self) -> &'static str {
+        "SubscribedExEx"
+    }
+
+    fn subscription(&self) -> SubscriptionSpec {
+        self.spec.clone()
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        self.recording.handle_notification(notification, node_info)
+    }
+}
+
+/// Recording plugin with a given pause policy.
+#[derive(Debug, Default)]
+struct PausableExEx {
+    policy: PausePolicy,
+    recording: RecordingExExPlugin,
+}
+
+impl ExExPlugin for PausableExEx {
+    fn id(&self) -> &'static str {
+        "PausableExEx"
+    }
+
+    fn pause_policy(&self) -> PausePolicy {
+        self.policy
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        self.recording.handle_notification(notification, node_info)
+    }
+}
+
+/// Plugin which writes handled block ranges to its dedicated log.
+#[derive(Debug, Default)]
+struct LoggingExEx {
+    log: Option<PluginLog>,
+}
+
+impl ExExPlugin for LoggingExEx {
+    fn id(&self) -> &'static str {
+        "LoggingExEx"
+    }
+
+    fn on_log(&mut self, log: PluginLog) {
+        log.eprintln("starting");
+        self.log = Some(log);
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let log = self.log.as_ref().ok_or_else(|| eyre::eyre!("no log"))?;
+            let range = notification.committed_chain().map(|chain| chain.range());
+            log.println(format!("handled {range:?}"));
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which records tips it's notified of.
+#[derive(Debug, Default)]
+struct TipExEx {
+    tips: Arc<Mutex<Vec<u64>>>,
+    recording: RecordingExExPlugin,
+}
+
+impl ExExPlugin for TipExEx {
+    fn id(&self) -> &'static str {
+        "TipExEx"
+    }
+
+    fn on_tip(&self, tip: BlockNumHash) {
+        self.tips.lock().unwrap().push(tip.number);
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        self.recording.handle_notification(notification, node_info)
+    }
+}
+
+/// Plugin which records numbers of blocks it was notified as finalized.
+#[derive(Debug, Default)]
+struct FinalizedExEx {
+    finalized: Arc<Mutex<Vec<u64>>>,
+}
+
+impl ExExPlugin for FinalizedExEx {
+    fn id(&self) -> &'static str {
+        "FinalizedExEx"
+    }
+
+    fn on_finalized(&self, block: BlockNumHash) {
+        self.finalized.lock().unwrap().push(block.number);
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Plugin which panics on every notification.
+#[derive(Debug)]
+struct PanickingExEx;
+
+impl ExExPlugin for PanickingExEx {
+    fn id(&self) -> &'static str {
+        "PanickingExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async { panic!("plugin bug") })
+    }
+}
+
+/// Plugin which records names of threads handling notifications.
+#[derive(Debug, Default)]
+struct ThreadNameExEx {
+    threads: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl ExExPlugin for ThreadNameExEx {
+    fn id(&self) -> &'static str {
+        "ThreadNameExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let name = std::thread::current().name().map(str::to_owned);
+            self.threads.lock().unwrap().push(name);
+            Ok(())
+        })
+    }
+}
+
+/// Plugin whose handler blocks its thread until it's released, counting handled notifications.
+#[derive(Debug)]
+struct BlockedExEx {
+    blocking: bool,
+    gate: Mutex<std::sync::mpsc::Receiver<()>>,
+    handled: Arc<AtomicU64>,
+}
+
+impl BlockedExEx {
+    /// Returns the plugin with a sender releasing its handlers, one per sent message.
+    fn new(blocking: bool) -> (Self, std::sync::mpsc::Sender<()>) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        (Self { blocking, gate: Mutex::new(rx), handled: Arc::default() }, tx)
+    }
+}
+
+impl ExExPlugin for BlockedExEx {
+    fn id(&self) -> &'static str {
+        "BlockedExEx"
+    }
+
+    fn is_blocking(&self) -> bool {
+        self.blocking
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            self.gate.lock().unwrap().recv()?;
+            self.handled.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which records tips of committed chains above its reconfigurable threshold.
+#[derive(Debug, Default)]
+struct ThresholdExEx {
+    threshold: AtomicU64,
+    recorded: Arc<Mutex<Vec<u64>>>,
+}
+
+impl ExExPlugin for ThresholdExEx {
+    fn id(&self) -> &'static str {
+        "ThresholdExEx"
+    }
+
+    fn reconfigure(&self, config: serde_json::Value) -> Result<()> {
+        if config["panic"].as_bool() == Some(true) {
+            panic!("reconfiguration panicked");
+        }
+        let threshold = config["threshold"].as_u64().ok_or_eyre("missing threshold")?;
+        self.threshold.store(threshold, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            if let Some(committed) = notification.committed_chain() {
+                if committed.tip().number > self.threshold.load(Ordering::Relaxed) {
+                    self.recorded.lock().unwrap().push(committed.tip().number);
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which writes numbers of committed blocks into its sharded output.
+#[derive(Debug, Default)]
+struct ShardingExEx {
+    sink: Option<ShardedSink>,
+}
+
+impl ExExPlugin for ShardingExEx {
+    fn id(&self) -> &'static str {
+        "ShardingExEx"
+    }
+
+    fn on_sink(&mut self, sink: ShardedSink) {
+        self.sink = Some(sink);
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let sink = self.sink.as_ref().ok_or_eyre("no sink")?;
+            for block in notification.committed_chain().iter().flat_map(|chain| chain.blocks_iter())
+            {
+                sink.write(block.number, &block.hash())?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which records sequence numbers of handled notifications.
+#[derive(Debug, Default)]
+struct SequenceExEx {
+    sequences: Arc<Mutex<Vec<u64>>>,
+}
+
+impl ExExPlugin for SequenceExEx {
+    fn id(&self) -> &'static str {
+        "SequenceExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            self.sequences.lock().unwrap().push(node_info.sequence);
+            Ok(())
+        })
+    }
+}
+
+/// Plugin whose handler waits until it's released, counting handled notifications.
+#[derive(Debug, Default)]
+struct GatedExEx {
+    gate: Arc<tokio::sync::Notify>,
+    handled: Arc<AtomicU64>,
+}
+
+impl ExExPlugin for GatedExEx {
+    fn id(&self) -> &'static str {
+        "GatedExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            self.gate.notified().await;
+            self.handled.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    }
+}
+
+/// Plugin which records coalesced notifications.
+#[derive(Debug, Default)]
+struct CoalescingExEx {
+    recording: RecordingExExPlugin,
+}
+
+impl ExExPlugin for CoalescingExEx {
+    fn id(&self) -> &'static str {
+        "CoalescingExEx"
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        &[COALESCE_CAPABILITY]
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        self.recording.handle_notification(notification, node_info)
+    }
+}
+
+/// Creates a plugin manager on top of a test Execution Extension context
+async fn plugin_manager(
+) -> Result<(ExExPluginManager<Adapter>, TestExExHandle, mpsc::UnboundedSender<RpcRequest>)> {
+    let (rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, exex_handle) = test_exex_context().await?;
+    Ok((ExExPluginManager::new(exex_ctx, rpc_request_rx), exex_handle, rpc_request_tx))
+}
+
+/// Genesis chain committed notification
+fn genesis_committed(exex_handle: &TestExExHandle) -> ExExNotification {
+    let chain = Chain::from_block(exex_handle.genesis.clone(), ExecutionOutcome::default(), None);
+    ExExNotification::ChainCommitted { new: chain.into() }
+}
+
+#[tokio::test]
+async fn should_not_count_failures_during_warmup() -> Result<()> {
+    let (mut plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let id = plugin_manager
+        .register_plugin(Box::new(FailingExEx { warmup: 2, required: false }))
+        .await?;
+
+    // Failures during warmup are not counted
+    for _ in 0..2 {
+        plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    }
+    assert_eq!(plugin_manager.plugin_failures(&id), Some(0));
+
+    // Normal error handling after warmup
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    assert_eq!(plugin_manager.plugin_failures(&id), Some(1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_skip_missing_or_corrupted_state_file() -> Result<()> {
+    let state_file = std::env::temp_dir().join("exex_plugins_corrupted_state.json");
+    let _ = std::fs::remove_file(&state_file);
+
+    // Missing state file
+    let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_state_file(&state_file);
+    assert!(unsafe { plugin_manager.load_from_state_file() }.await.is_empty());
+
+    // Corrupted state file
+    std::fs::write(&state_file, "not a json")?;
+    assert!(unsafe { plugin_manager.load_from_state_file() }.await.is_empty());
+    assert!(plugin_manager.is_empty());
+
+    std::fs::remove_file(state_file)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_pass_node_info_to_plugins() -> Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, exex_handle) = test_exex_context().await?;
+    let chain_id = exex_ctx.config.chain.chain().id();
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let plugin = NodeInfoExEx::default();
+    let last = plugin.last.clone();
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+
+    let expected = NodeInfo {
+        chain_id,
+        head_number: exex_handle.genesis.number,
+        head_hash: exex_handle.genesis.hash(),
+        sequence: 1,
+    };
+    assert_eq!(*last.lock().unwrap(), Some(expected));
+    assert_eq!(plugin_manager.node_info(), expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_hold_finished_height_on_required_plugin_failure_unless_muted() -> Result<()> {
+    let (mut plugin_manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let plugin = FailingExEx { warmup: 0, required: true };
+    let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    // Failed required plugin holds back the finished height
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    exex_handle.assert_events_empty();
+
+    // Muted plugin still handles notifications, but doesn't block the finished height
+    plugin_manager.set_plugin_blocking_muted(&id, true)?;
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    exex_handle.assert_event_finished_height(exex_handle.genesis.num_hash())?;
+    assert_eq!(plugin_manager.plugin_failures(&id), Some(2));
+
+    // Unmuted again
+    plugin_manager.set_plugin_blocking_muted(&id, false)?;
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    exex_handle.assert_events_empty();
+
+    assert!(plugin_manager.set_plugin_blocking_muted("UnknownExEx", true).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_dispatch_reorg_to_on_reorg_hook() -> Result<()> {
+    let (mut plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let plugin = ReorgExEx::default();
+    let (reorgs, notifications) = (plugin.reorgs.clone(), plugin.notifications.clone());
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    // Plugin without `on_reorg` receives reorgs by `handle_notification`
+    let node_info_plugin = NodeInfoExEx::default();
+    let last = node_info_plugin.last.clone();
+    plugin_manager.register_plugin(Box::new(node_info_plugin)).await?;
+
+    let chain = Chain::from_block(exex_handle.genesis.clone(), ExecutionOutcome::default(), None);
+    let reorg =
+        ExExNotification::ChainReorged { old: chain.clone().into(), new: chain.clone().into() };
+    plugin_manager.handle_notification(reorg).await?;
+
+    assert_eq!(*reorgs.lock().unwrap(), vec![(chain.range(), chain.range())]);
+    assert_eq!(*notifications.lock().unwrap(), 0);
+    assert!(last.lock().unwrap().is_some());
+
+    // Non-reorg notifications are not affected
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    assert_eq!(reorgs.lock().unwrap().len(), 1);
+    assert_eq!(*notifications.lock().unwrap(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_skip_notifications_at_or_below_last_processed() -> Result<()> {
+    let (mut plugin_manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let plugin =
+        IdempotentExEx { last_processed: Some(exex_handle.genesis.number), ..Default::default() };
+    let handled = plugin.handled.clone();
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    // Re-delivered commit is skipped, but the finished height still advances
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    assert_eq!(*handled.lock().unwrap(), 0);
+    exex_handle.assert_event_finished_height(exex_handle.genesis.num_hash())?;
+
+    // Reverts are always delivered
+    let chain = Chain::from_block(exex_handle.genesis.clone(), ExecutionOutcome::default(), None);
+    plugin_manager
+        .handle_notification(ExExNotification::ChainReverted { old: chain.into() })
+        .await?;
+    assert_eq!(*handled.lock().unwrap(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_route_commands_to_plugin() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let id = plugin_manager.register_plugin(Box::new(PingExEx)).await?;
+    let other_id = plugin_manager.register_plugin(Box::new(NodeInfoExEx::default())).await?;
+
+    let res = plugin_manager.plugin_command(&id, "ping".into(), serde_json::json!(1)).await?;
+    assert_eq!(res, serde_json::json!({ "pong": 1 }));
+
+    assert!(plugin_manager
+        .plugin_command(&id, "pong".into(), serde_json::Value::Null)
+        .await
+        .is_err());
+
+    // Default implementation
+    let err = plugin_manager
+        .plugin_command(&other_id, "ping".into(), serde_json::Value::Null)
+        .await
+        .err()
+        .expect("command is unsupported");
+    assert!(err.to_string().contains("unsupported command"));
+
+    // Unknown plugin
+    assert!(plugin_manager
+        .plugin_command("UnknownExEx", "ping".into(), serde_json::Value::Null)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn should_not_stall_runtime_on_blocking_plugin() -> Result<()> {
+    let (mut plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let plugin = BlockingExEx::default();
+    let (ticks, ticked_while_blocked) = (plugin.ticks.clone(), plugin.ticked_while_blocked.clone());
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    let ticker = tokio::spawn(async move {
+        loop {
+            ticks.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+
+    // Run the manager on the single worker, along with the ticker
+    let notification = genesis_committed(&exex_handle);
+    tokio::spawn(async move { plugin_manager.handle_notification(notification).await }).await??;
+    ticker.abort();
+
+    assert!(ticked_while_blocked.load(Ordering::SeqCst) > 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_dispatch_only_notifications_matching_transaction_filter() -> Result<()> {
+    let (to, topic) = (Address::with_last_byte(1), B256::with_last_byte(2));
+
+    // Genesis block with a transaction to `to`, which emits a log with `topic`
+    let (mut plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut block = exex_handle.genesis.clone();
+    block.block.body.transactions.push(TransactionSigned::from_transaction_and_signature(
+        Transaction::Legacy(TxLegacy { to: TxKind::Call(to), ..Default::default() }),
+        Signature::test_signature(),
+    ));
+    let receipt = Receipt {
+        logs: vec![Log::new_unchecked(Address::ZERO, vec![topic], Default::default())],
+        ..Default::default()
+    };
+    let execution_outcome = ExecutionOutcome {
+        receipts: vec![vec![Some(receipt)]].into(),
+        first_block: block.number,
+        ..Default::default()
+    };
+    let chain = Chain::from_block(block, execution_outcome, None);
+
+    let filters = [
+        (TxFilter::default().with_to(to), 1),
+        (TxFilter::default().with_topic(topic), 1),
+        (TxFilter::default().with_to(Address::with_last_byte(3)), 0),
+        (TxFilter::default().with_topic(B256::with_last_byte(4)), 0),
+    ];
+    for (filter, expected) in filters {
+        let plugin = FilteredExEx { filter, ..Default::default() };
+        let handled = plugin.handled.clone();
+        let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+        plugin_manager
+            .handle_notification(ExExNotification::ChainCommitted { new: chain.clone().into() })
+            .await?;
+        assert_eq!(*handled.lock().unwrap(), expected);
+
+        // Reverts are always delivered
+        plugin_manager
+            .handle_notification(ExExNotification::ChainReverted { old: chain.clone().into() })
+            .await?;
+        assert_eq!(*handled.lock().unwrap(), expected + 1);
+
+        plugin_manager.unload_plugin(&id)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn should_compute_bounded_restart_backoff() {
+    let policy = RestartPolicy {
+        max_restarts: 4,
+        initial_backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(5),
+    };
+
+    assert_eq!(policy.backoff(0), Some(Duration::from_secs(1)));
+    assert_eq!(policy.backoff(1), Some(Duration::from_secs(2)));
+    assert_eq!(policy.backoff(2), Some(Duration::from_secs(4)));
+    // Capped by the max backoff
+    assert_eq!(policy.backoff(3), Some(Duration::from_secs(5)));
+    // Exhausted
+    assert_eq!(policy.backoff(4), None);
+
+    assert_eq!(RestartPolicy::never().backoff(0), None);
+}
+
+#[tokio::test]
+async fn should_restart_manager_until_restarts_are_exhausted() -> Result<()> {
+    let (plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let chain = Chain::from_block(exex_handle.genesis.clone(), ExecutionOutcome::default(), None);
+
+    // Every commit fails the manager, since the node doesn't receive events anymore
+    drop(exex_handle.events_rx);
+    let notifications_tx = exex_handle.notifications_tx;
+    tokio::spawn(async move {
+        for _ in 0..2 {
+            let notification = ExExNotification::ChainCommitted { new: chain.clone().into() };
+            notifications_tx.send(notification).await?;
+        }
+        eyre::Ok(())
+    });
+
+    let policy = RestartPolicy { max_restarts: 1, ..RestartPolicy::never() };
+    let res =
+        tokio::time::timeout(Duration::from_secs(1), plugin_manager.run_supervised(policy)).await?;
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_rebuild_manager_state_on_restart() -> Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, mut exex_handle) = test_exex_context().await?;
+    let provider = exex_ctx.provider().clone();
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    let plugin = PanickingTipExEx::default();
+    let handled = plugin.handled.clone();
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+    // A load, which is left pending by the terminated run
+    let load = plugin_manager.spawn_register_plugin(Box::new(SlowLoadExEx {
+        load_time: Duration::from_secs(60),
+        handled: Arc::default(),
+    }));
+
+    // The first notification commits the node's head, panicking the manager mid-dispatch
+    let mut generator = NotificationGenerator::new(1);
+    let notifications = generator.by_ref().take(2).collect::<Vec<_>>();
+    provider.set_canonical_head(notifications[0].committed_chain().unwrap().tip().header.clone());
+    let notifications_tx = exex_handle.notifications_tx.clone();
+    tokio::spawn(async move {
+        for notification in notifications {
+            notifications_tx.send(notification).await?;
+        }
+        eyre::Ok(())
+    });
+    let policy = RestartPolicy { max_restarts: 1, ..RestartPolicy::never() };
+    let manager = tokio::spawn(plugin_manager.run_supervised(policy));
+
+    // The pending load is aborted on restart, while the restarted manager keeps dispatching
+    let err = tokio::time::timeout(Duration::from_secs(1), load).await??.unwrap_err();
+    assert!(err.message().contains("restarted"), "{err:?}");
+    let event = tokio::time::timeout(Duration::from_secs(1), exex_handle.events_rx.recv()).await?;
+    assert_eq!(event, Some(ExExEvent::FinishedHeight(generator.tip().unwrap())));
+    assert_eq!(handled.load(Ordering::SeqCst), 2);
+    assert!(!manager.is_finished());
+
+    manager.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_handle_notification_in_detached_task() -> Result<()> {
+    let (mut plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    plugin_manager.register_plugin(Box::new(DetachedExEx { tx })).await?;
+
+    let notification = genesis_committed(&exex_handle);
+    plugin_manager.handle_notification(notification.clone()).await?;
+
+    let received = tokio::time::timeout(Duration::from_secs(1), rx.recv()).await?;
+    assert_eq!(received.as_deref(), Some(&notification));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_toggle_plugin_notification_kinds_at_runtime() -> Result<()> {
+    let (mut plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let plugin = IdempotentExEx::default();
+    let handled = plugin.handled.clone();
+    let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    let chain = Chain::from_block(exex_handle.genesis.clone(), ExecutionOutcome::default(), None);
+    let reverted = || ExExNotification::ChainReverted { old: chain.clone().into() };
+
+    // Only reverts
+    plugin_manager.set_plugin_notification_kinds(&id, &[ChainKind::Revert])?;
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    assert_eq!(*handled.lock().unwrap(), 0);
+    plugin_manager.handle_notification(reverted()).await?;
+    assert_eq!(*handled.lock().unwrap(), 1);
+
+    // Nothing
+    plugin_manager.set_plugin_notification_kinds(&id, &[])?;
+    plugin_manager.handle_notification(reverted()).await?;
+    assert_eq!(*handled.lock().unwrap(), 1);
+
+    // All kinds again
+    plugin_manager.set_plugin_notification_kinds(&id, &ChainKind::ALL)?;
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    plugin_manager.handle_notification(reverted()).await?;
+    assert_eq!(*handled.lock().unwrap(), 3);
+
+    assert!(plugin_manager.set_plugin_notification_kinds("UnknownExEx", &[]).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_export_metrics_periodically() -> Result<()> {
+    let metrics_file = std::env::temp_dir().join("exex_plugins_metrics.json");
+    let _ = std::fs::remove_file(&metrics_file);
+
+    let (plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager =
+        plugin_manager.with_metrics_export(&metrics_file, Duration::from_millis(20));
+    let id = plugin_manager
+        .register_plugin(Box::new(FailingExEx { warmup: 0, required: false }))
+        .await?;
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+
+    let manager = tokio::spawn(plugin_manager.run());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    manager.abort();
+
+    let snapshot: MetricsSnapshot = serde_json::from_slice(&std::fs::read(&metrics_file)?)?;
+    assert_eq!(snapshot.plugins.len(), 1);
+    assert_eq!(snapshot.plugins[0].id, id);
+    assert_eq!(snapshot.plugins[0].handled, 1);
+    assert_eq!(snapshot.plugins[0].failures, 1);
+    assert_eq!(snapshot.plugins[0].last_kind, Some(ChainKind::Commit));
+
+    std::fs::remove_file(metrics_file)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_unload_dependents_before_dependencies() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let unloaded = Arc::new(Mutex::new(Vec::new()));
+    let plugin =
+        |id, depends_on| Box::new(DependentExEx { id, depends_on, unloaded: unloaded.clone() });
+
+    // B depends on A, but is loaded first
+    plugin_manager.register_plugin(plugin("B", &["A"])).await?;
+    plugin_manager.register_plugin(plugin("A", &[])).await?;
+    plugin_manager.register_plugin(plugin("C", &[])).await?;
+
+    assert_eq!(plugin_manager.unload_order(), vec!["C", "B", "A"]);
+    plugin_manager.unload_all();
+    assert_eq!(*unloaded.lock().unwrap(), vec!["C", "B", "A"]);
+
+    // Without dependencies - reverse load order
+    unloaded.lock().unwrap().clear();
+    for id in ["A", "B", "C"] {
+        plugin_manager.register_plugin(plugin(id, &[])).await?;
+    }
+    plugin_manager.unload_all();
+    assert_eq!(*unloaded.lock().unwrap(), vec!["C", "B", "A"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_report_plugin_health_with_latency() -> Result<()> {
+    let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_health_timeout(Duration::from_millis(100));
+
+    let checks = [
+        (Duration::from_millis(20), false, None),
+        (Duration::from_millis(20), true, Some("connection lost")),
+        // Slow health check
+        (Duration::from_secs(1), false, Some("timeout")),
+    ];
+    for (latency, fail, error) in checks {
+        let id = plugin_manager.register_plugin(Box::new(HealthExEx { latency, fail })).await?;
+
+        let health = plugin_manager.plugin_health(&id).await?;
+        assert_eq!(health.healthy, error.is_none());
+        assert_eq!(health.error.as_deref(), error);
+        assert!(health.latency_ms >= 20 && health.latency_ms < 1000);
+
+        plugin_manager.unload_plugin(&id)?;
+    }
+
+    assert!(plugin_manager.plugin_health("UnknownExEx").await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_not_block_notifications_on_slow_plugin_load() -> Result<()> {
+    let (mut plugin_manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let handled = Arc::new(Mutex::new(0));
+    let plugin = SlowLoadExEx { load_time: Duration::from_millis(300), handled: handled.clone() };
+    let loaded = plugin_manager.spawn_register_plugin(Box::new(plugin));
+    // Same id is rejected while loading
+    let duplicate = plugin_manager.spawn_register_plugin(Box::new(SlowLoadExEx {
+        load_time: Duration::ZERO,
+        handled: handled.clone(),
+    }));
+    assert!(duplicate.await?.is_err());
+
+    let manager = tokio::spawn(plugin_manager.run());
+
+    // Notification is processed while the plugin is still loading
+    let notification = genesis_committed(&exex_handle);
+    exex_handle.notifications_tx.send(notification).await?;
+    let event =
+        tokio::time::timeout(Duration::from_millis(200), exex_handle.events_rx.recv()).await?;
+    assert!(event.is_some());
+    assert_eq!(*handled.lock().unwrap(), 0);
+
+    // Queued notification is replayed once the plugin is loaded
+    let id = tokio::time::timeout(Duration::from_secs(1), loaded).await??;
+    assert_eq!(id.map_err(|err| eyre::eyre!("{err:?}"))?, "SlowLoadExEx");
+    assert_eq!(*handled.lock().unwrap(), 1);
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_abort_plugin_load_exceeding_its_limits() -> Result<()> {
+    for overflow in [true, false] {
+        let (plugin_manager, exex_handle, rpc_request_tx) = plugin_manager().await?;
+        let mut plugin_manager =
+            plugin_manager.with_background_load_limits(1, Duration::from_millis(100));
+        let handled = Arc::new(Mutex::new(0));
+        let plugin = SlowLoadExEx { load_time: Duration::from_secs(60), handled: handled.clone() };
+        let loaded = plugin_manager.spawn_register_plugin(Box::new(plugin));
+        if overflow {
+            // The second notification overflows the queue of the load
+            for _ in 0..2 {
+                plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+            }
+        }
+        let manager = tokio::spawn(plugin_manager.run());
+        let rpc = ExExPluginRpc::new(rpc_request_tx);
+
+        let err = tokio::time::timeout(Duration::from_secs(1), loaded).await??.unwrap_err();
+        let expected = if overflow { "too many queued notifications" } else { "timed out" };
+        assert!(err.message().contains(expected), "{err:?}");
+        assert!(rpc.list_plugins().await?.is_empty());
+        assert_eq!(*handled.lock().unwrap(), 0);
+
+        manager.abort();
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_abort_plugin_tasks_on_unload() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let (tx, mut rx) = oneshot::channel();
+    let id = plugin_manager
+        .register_plugin(Box::new(SpawningExEx { tx: Some(tx), tasks: None }))
+        .await?;
+
+    // Task is running
+    tokio::task::yield_now().await;
+    assert!(rx.try_recv().is_err_and(|err| err == oneshot::error::TryRecvError::Empty));
+
+    // Task is aborted, so its sender is dropped
+    plugin_manager.unload_plugin(&id)?;
+    assert!(tokio::time::timeout(Duration::from_secs(1), rx).await?.is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_abort_tasks_of_plugins_reloaded_between_notifications() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut generator = NotificationGenerator::new(1);
+
+    for _ in 0..10 {
+        let (tx, rx) = oneshot::channel();
+        let id = plugin_manager
+            .register_plugin(Box::new(SpawningExEx { tx: Some(tx), tasks: None }))
+            .await?;
+        plugin_manager.handle_notification(generator.commit(1)?).await?;
+
+        // Tasks of each unloaded instance are dropped
+        plugin_manager.unload_plugin(&id)?;
+        assert!(tokio::time::timeout(Duration::from_secs(1), rx).await?.is_err());
+    }
+    assert!(plugin_manager.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_ping_through_manager_loop() -> Result<()> {
+    let (plugin_manager, _exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let rpc = ExExPluginRpc::new(rpc_request_tx);
+
+    let manager = tokio::spawn(plugin_manager.run());
+    let latency = rpc.ping().await?;
+    assert!(latency < Duration::from_secs(1).as_micros() as u64);
+
+    // Loop isn't running anymore
+    manager.abort();
+    let _ = manager.await;
+    assert!(rpc.ping().await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_keep_latest_plugin_result() -> Result<()> {
+    let (mut plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let id = plugin_manager.register_plugin(Box::new(BlockCountExEx::default())).await?;
+    assert_eq!(plugin_manager.plugin_latest_result(&id)?, None);
+
+    for blocks in 1..=2 {
+        plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+        let result = plugin_manager.plugin_latest_result(&id)?;
+        assert_eq!(result, Some(serde_json::json!({ "blocks": blocks })));
+    }
+
+    // Revert doesn't return a result, so the latest one is kept
+    let chain = Chain::from_block(exex_handle.genesis.clone(), ExecutionOutcome::default(), None);
+    plugin_manager
+        .handle_notification(ExExNotification::ChainReverted { old: chain.clone().into() })
+        .await?;
+    let result = plugin_manager.plugin_latest_result(&id)?;
+    assert_eq!(result, Some(serde_json::json!({ "blocks": 2 })));
+
+    // Reorg returns a result of its committed blocks by default
+    let reorg = ExExNotification::ChainReorged { old: chain.clone().into(), new: chain.into() };
+    plugin_manager.handle_notification(reorg).await?;
+    let result = plugin_manager.plugin_latest_result(&id)?;
+    assert_eq!(result, Some(serde_json::json!({ "blocks": 3 })));
+
+    assert!(plugin_manager.plugin_latest_result("UnknownExEx").is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_emit_finished_height_with_sealed_tip_hash() -> Result<()> {
+    let (mut plugin_manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let chain = synthetic_chain(BlockRange { from: 1, to: 1_000 })?;
+    let tip = chain.tip().header.header().num_hash_slow();
+    plugin_manager
+        .handle_notification(ExExNotification::ChainCommitted { new: chain.into() })
+        .await?;
+
+    exex_handle.assert_event_finished_height(tip)?;
+    assert_eq!(plugin_manager.node_info().head_hash, tip.hash);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_clamp_finished_height_to_finalized_block() -> Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, mut exex_handle) = test_exex_context().await?;
+    let provider = exex_ctx.provider().clone();
+    let mut plugin_manager =
+        ExExPluginManager::new(exex_ctx, rpc_request_rx).with_finalized_only(true);
+
+    // No finalized block yet
+    let chain = synthetic_chain(BlockRange { from: 1, to: 10 })?;
+    let tip = chain.tip().header.num_hash();
+    let finalized = chain.blocks_iter().nth(4).unwrap().header.clone();
+    plugin_manager
+        .handle_notification(ExExNotification::ChainCommitted { new: chain.into() })
+        .await?;
+    exex_handle.assert_events_empty();
+
+    // Finalized block is below the tip
+    provider.set_finalized(finalized.clone());
+    let chain = synthetic_chain(BlockRange { from: 1, to: 10 })?;
+    plugin_manager
+        .handle_notification(ExExNotification::ChainCommitted { new: chain.into() })
+        .await?;
+    exex_handle.assert_event_finished_height(finalized.num_hash())?;
+    assert_eq!(finalized.number, 5);
+
+    // Tip is finalized
+    let chain = synthetic_chain(BlockRange { from: 1, to: 10 })?;
+    provider.set_finalized(chain.tip().header.clone());
+    plugin_manager
+        .handle_notification(ExExNotification::ChainCommitted { new: chain.into() })
+        .await?;
+    exex_handle.assert_event_finished_height(tip)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_find_plugins_by_capability() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let plugins = [("C", &["on_reorg"][..]), ("A", &["on_reorg", "commands"]), ("B", &[])];
+    for (id, capabilities) in plugins {
+        plugin_manager.register_plugin(Box::new(CapableExEx { id, capabilities })).await?;
+    }
+
+    assert_eq!(plugin_manager.find_plugins_by_capability("on_reorg"), vec!["C", "A"]);
+    assert_eq!(plugin_manager.find_plugins_by_capability("commands"), vec!["A"]);
+    assert!(plugin_manager.find_plugins_by_capability("unknown").is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_refuse_plugin_requiring_disabled_node_rpc_namespace() -> Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (mut exex_ctx, _exex_handle) = test_exex_context().await?;
+    // HTTP serves the standard namespaces, i.e. `eth`, `net` and `web3`
+    exex_ctx.config.rpc.http = true;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let plugin = RpcClientExEx { id: "Eth", namespaces: &["eth", "net"] };
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    let plugin = RpcClientExEx { id: "Debug", namespaces: &["eth", "debug"] };
+    let err = plugin_manager
+        .register_plugin(Box::new(plugin))
+        .await
+        .expect_err("expect disabled namespace error");
+    assert!(err.to_string().contains(r#"["debug"]"#), "{err}");
+    assert_eq!(plugin_manager.plugins(), vec!["Eth"]);
+
+    Ok(())
+}
+
+#[test]
+fn should_suppress_repeated_errors_within_interval() {
+    let mut sampler = ErrorLogSampler::default();
+    let interval = Duration::from_secs(60);
+    let now = Instant::now();
+
+    // First error is logged, repeated ones are suppressed within the interval
+    assert_eq!(sampler.sample("not ready", interval, now), Some(0));
+    assert_eq!(sampler.sample("not ready", interval, now + Duration::from_secs(1)), None);
+    assert_eq!(sampler.sample("not ready", interval, now + Duration::from_secs(59)), None);
+
+    // Other error is logged immediately, with a summary of suppressed ones
+    assert_eq!(sampler.sample("connection lost", interval, now + Duration::from_secs(59)), Some(2));
+
+    // Same error is logged again once the interval elapses
+    assert_eq!(sampler.sample("connection lost", interval, now + Duration::from_secs(70)), None);
+    assert_eq!(
+        sampler.sample("connection lost", interval, now + Duration::from_secs(120)),
+        Some(1)
+    );
+}
+
+#[tokio::test]
+async fn should_record_notifications_passed_to_plugin() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+
+    let plugin = RecordingExExPlugin::default();
+    let id = plugin_manager.register_plugin(Box::new(plugin.clone())).await?;
+    plugin_manager.set_plugin_notification_kinds(&id, &[ChainKind::Commit])?;
+
+    let chain = |from, to| synthetic_chain(BlockRange { from, to }).map(Arc::new);
+    let notifications = [
+        ExExNotification::ChainCommitted { new: chain(1, 2)? },
+        ExExNotification::ChainReverted { old: chain(2, 2)? },
+        ExExNotification::ChainCommitted { new: chain(2, 3)? },
+    ];
+    for notification in notifications {
+        plugin_manager.handle_notification(notification).await?;
+    }
+
+    // Revert was filtered out by manager
+    let committed = |from, to| NormalizedNotification {
+        kind: ChainKind::Commit,
+        reverted: None,
+        committed: Some(BlockRange { from, to }),
+    };
+    assert_eq!(plugin.notifications(), vec![committed(1, 2), committed(2, 3)]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_flush_buffered_output_on_drain() -> Result<()> {
+    let (mut plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let flushed = Arc::new(Mutex::new(Vec::new()));
+    let plugin = BufferingExEx { flushed: flushed.clone(), ..Default::default() };
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+    assert!(flushed.lock().unwrap().is_empty());
+
+    plugin_manager.drain().await?;
+    assert_eq!(*flushed.lock().unwrap(), vec![exex_handle.genesis.number]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_drain_plugins_once_notifications_end() -> Result<()> {
+    let (mut plugin_manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let flushed = Arc::new(Mutex::new(Vec::new()));
+    let plugin = BufferingExEx { flushed: flushed.clone(), ..Default::default() };
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    let manager = tokio::spawn(plugin_manager.run());
+    let number = exex_handle.genesis.number;
+    exex_handle.notifications_tx.send(genesis_committed(&exex_handle)).await?;
+    exex_handle.events_rx.recv().await;
+    assert!(flushed.lock().unwrap().is_empty());
+
+    // Node shuts down
+    drop(exex_handle);
+    tokio::time::timeout(Duration::from_secs(1), manager).await???;
+    assert_eq!(*flushed.lock().unwrap(), vec![number]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_dispatch_generated_blocks_in_order() -> Result<()> {
+    let (mut plugin_manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let plugin = RecordingExExPlugin::default();
+    plugin_manager.register_plugin(Box::new(plugin.clone())).await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    let mut notifications = generator.by_ref().take(3).collect::<Vec<_>>();
+    notifications.push(generator.commit(2)?);
+    notifications.push(generator.reorg(3, 3)?);
+
+    let mut hashes = std::collections::HashMap::from([(0, B256::ZERO)]);
+    for notification in notifications {
+        let new = notification.committed_chain().expect("generator doesn't revert");
+        // Blocks are linked across notifications, as well as a fork to its fork block
+        assert_eq!(Some(&new.first().parent_hash), hashes.get(&(new.first().number - 1)));
+        hashes.extend(new.blocks_iter().map(|block| (block.number, block.hash())));
+        let tip = new.tip().num_hash();
+
+        plugin_manager.handle_notification(notification).await?;
+        exex_handle.assert_event_finished_height(tip)?;
+    }
+    assert_eq!(generator.tip().map(|tip| tip.number), Some(6));
+
+    let range = |from, to| Some(BlockRange { from, to });
+    let normalized =
+        |kind, reverted, committed| NormalizedNotification { kind, reverted, committed };
+    assert_eq!(
+        plugin.notifications(),
+        vec![
+            normalized(ChainKind::Commit, None, range(1, 1)),
+            normalized(ChainKind::Commit, None, range(2, 2)),
+            normalized(ChainKind::Commit, None, range(3, 3)),
+            normalized(ChainKind::Commit, None, range(4, 5)),
+            normalized(ChainKind::Reorg, range(4, 5), range(4, 6)),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_return_redacted_plugin_config() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let config = serde_json::json!({ "endpoint": "http://localhost:8080", "api_key": "secret" });
+
+    let plugin = ConfiguredExEx::default();
+    let received = plugin.config.clone();
+    let id = plugin_manager.register_plugin_with_config(Box::new(plugin), config.clone()).await?;
+    assert_eq!(received.lock().unwrap().as_ref(), Some(&config));
+
+    // Plugin without a config
+    let ping_id = plugin_manager.register_plugin(Box::new(PingExEx)).await?;
+    assert_eq!(plugin_manager.plugin_config(&ping_id)?, None);
+
+    // Config is refused by a plugin which doesn't accept one
+    plugin_manager.unload_plugin(&ping_id)?;
+    let res = plugin_manager.register_plugin_with_config(Box::new(PingExEx), config).await;
+    assert!(res.is_err());
+
+    let manager = tokio::spawn(plugin_manager.run());
+    let rpc = ExExPluginRpc::new(rpc_request_tx);
+    let returned = rpc.plugin_config(id).await?;
+    assert_eq!(
+        returned,
+        Some(serde_json::json!({ "endpoint": "http://localhost:8080", "api_key": "<redacted>" }))
+    );
+    assert!(rpc.plugin_config("UnknownExEx".to_owned()).await.is_err());
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_run_cpu_bound_plugin_on_dedicated_pool() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let plugin = CpuBoundExEx::default();
+    let threads = plugin.threads.clone();
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    for notification in NotificationGenerator::new(1).take(3) {
+        plugin_manager.handle_notification(notification).await?;
+    }
+
+    let threads = threads.lock().unwrap();
+    assert_eq!(threads.len(), 3);
+    for name in threads.iter() {
+        let name = name.as_deref().unwrap_or_default();
+        assert!(name.starts_with("exex-plugin-CpuBoundExEx-"), "ran on {name:?}");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_report_manager_last_activity() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let status = plugin_manager.status();
+    assert_eq!((status.last_notification_ms, status.last_rpc_request_ms), (None, None));
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    plugin_manager.handle_notification(NotificationGenerator::new(1).commit(1)?).await?;
+    let status = plugin_manager.status();
+    assert!(status.uptime_ms >= 20);
+    assert!(status.last_notification_ms.is_some_and(|ms| ms < status.uptime_ms));
+    assert_eq!(status.head_number, 1);
+    assert_eq!(status.last_rpc_request_ms, None);
+
+    let manager = tokio::spawn(plugin_manager.run());
+    let rpc = ExExPluginRpc::new(rpc_request_tx);
+    rpc.ping().await?;
+    let status = rpc.manager_status().await?;
+    assert!(status.last_rpc_request_ms.is_some(), "Ping must be tracked");
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_cancel_plugin_token_on_unload_and_drain() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut generator = NotificationGenerator::new(1);
+
+    for unload in [true, false] {
+        let plugin = CancellableExEx::default();
+        let cancelled = plugin.cancelled.clone();
+        let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+        plugin_manager.handle_notification(generator.commit(1)?).await?;
+        tokio::task::yield_now().await;
+        assert!(cancelled.lock().unwrap().is_empty(), "Token is cancelled only on unload");
+
+        if unload {
+            plugin_manager.unload_plugin(&id)?;
+        } else {
+            plugin_manager.drain().await?;
+        }
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while cancelled.lock().unwrap().is_empty() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await?;
+        assert_eq!(*cancelled.lock().unwrap(), vec![generator.tip().unwrap().number]);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_cancel_plugin_token_of_reorg_handler_on_unload() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut generator = NotificationGenerator::new(1);
+
+    let plugin = CancellableExEx::default();
+    let cancelled = plugin.cancelled.clone();
+    let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+    plugin_manager.handle_notification(generator.commit(3)?).await?;
+    // Reorg goes to the default `on_reorg`, which forwards the token
+    plugin_manager.handle_notification(generator.reorg(1, 1)?).await?;
+    tokio::task::yield_now().await;
+    assert!(cancelled.lock().unwrap().is_empty(), "Token is cancelled only on unload");
+
+    plugin_manager.unload_plugin(&id)?;
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while cancelled.lock().unwrap().len() < 2 {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await?;
+    let mut tips = cancelled.lock().unwrap().clone();
+    tips.sort();
+    assert_eq!(tips, vec![2, 3]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_record_emitted_finished_heights() -> Result<()> {
+    let (manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let (audit_tx, mut audit_rx) = mpsc::unbounded_channel();
+    let mut manager = manager.with_finished_height_audit(AuditSink::Channel(audit_tx))?;
+
+    let mut generator = NotificationGenerator::new(1);
+    for notification in generator.by_ref().take(3) {
+        let tip = notification.committed_chain().unwrap().tip().num_hash();
+        manager.handle_notification(notification).await?;
+        exex_handle.assert_event_finished_height(tip)?;
+
+        let record = audit_rx.try_recv()?;
+        assert_eq!((record.number, record.hash), (tip.number, tip.hash));
+        assert!(record.timestamp_ms > 0);
+    }
+    assert!(audit_rx.try_recv().is_err(), "Each emission is recorded once");
+
+    // Records of a file are written once drained
+    let dir = tempfile::tempdir()?;
+    let (manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let audit_log = dir.path().join("audit.jsonl");
+    let mut manager = manager.with_finished_height_audit(AuditSink::File(audit_log.clone()))?;
+    for notification in generator.by_ref().take(2) {
+        manager.handle_notification(notification).await?;
+    }
+    manager.drain().await?;
+    let records = std::fs::read_to_string(&audit_log)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<FinishedHeightRecord>, _>>()?;
+    let numbers = records.iter().map(|record| record.number).collect::<Vec<_>>();
+    assert_eq!(numbers, vec![4, 5]);
+    while exex_handle.events_rx.try_recv().is_ok() {}
+
+    // Failed record doesn't hold back the event
+    let (manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let audit_log = dir.path().join("missing_dir").join("audit.jsonl");
+    let mut manager = manager.with_finished_height_audit(AuditSink::File(audit_log.clone()))?;
+    manager.handle_notification(generator.commit(1)?).await?;
+    exex_handle.assert_event_finished_height(generator.tip().unwrap())?;
+    manager.drain().await?;
+    assert!(!audit_log.exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_stream_plugin_errors_to_subscribers() -> Result<()> {
+    let (mut plugin_manager, exex_handle, rpc_request_tx) = plugin_manager().await?;
+    plugin_manager.register_plugin(Box::new(FailingExEx { warmup: 0, required: false })).await?;
+    let network = plugin_manager.network().clone();
+    let manager = tokio::spawn(plugin_manager.run());
+
+    let rpc = ExExPluginRpc::new(rpc_request_tx).into_rpc();
+    let mut failing =
+        rpc.subscribe_unbounded("exex_subscribePluginErrors", [Some("FailingExEx")]).await?;
+    let mut other =
+        rpc.subscribe_unbounded("exex_subscribePluginErrors", [Some("OtherExEx")]).await?;
+
+    let notification = genesis_committed(&exex_handle);
+    let expected = NormalizedNotification::from(&notification);
+    exex_handle.notifications_tx.send(notification).await?;
+
+    let (event, _) =
+        tokio::time::timeout(Duration::from_secs(1), failing.next::<PluginErrorEvent>())
+            .await?
+            .expect("subscription is open")?;
+    assert_eq!(
+        event,
+        PluginErrorEvent {
+            network,
+            id: "FailingExEx".to_string(),
+            notification: expected,
+            head_hash: exex_handle.genesis.hash(),
+            error: "not ready".to_string()
+        }
+    );
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), other.next::<PluginErrorEvent>())
+            .await
+            .is_err(),
+        "Errors of other plugins are filtered out"
+    );
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_reject_reorgs_deeper_than_max_depth() -> Result<()> {
+    let (manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut manager = manager.with_max_reorg_depth(4);
+    let plugin = RecordingExExPlugin::default();
+    manager.register_plugin(Box::new(plugin.clone())).await?;
+    let mut deep_reorgs = manager.subscribe_deep_reorgs();
+
+    let mut generator = NotificationGenerator::new(1);
+    manager.handle_notification(generator.commit(10)?).await?;
+    exex_handle.assert_event_finished_height(generator.tip().unwrap())?;
+
+    // Reorg of 4 blocks is dispatched
+    manager.handle_notification(generator.reorg(6, 1)?).await?;
+    exex_handle.assert_event_finished_height(generator.tip().unwrap())?;
+    assert_eq!(plugin.notifications().len(), 2);
+
+    // Deep revert is rejected and pauses plugins
+    let revert = NormalizedNotification {
+        kind: ChainKind::Revert,
+        reverted: Some(BlockRange { from: 2, to: 7 }),
+        committed: None,
+    };
+    manager.handle_notification(synthetic_notification(&revert)?).await?;
+    let event = deep_reorgs.try_recv()?;
+    assert_eq!((event.notification, event.depth), (revert, 6));
+    assert_eq!(event.paused, vec!["RecordingExEx".to_owned()]);
+    assert_eq!(plugin.notifications().len(), 2, "Deep revert isn't dispatched");
+
+    manager.handle_notification(generator.commit(1)?).await?;
+    assert_eq!(plugin.notifications().len(), 2, "Paused plugin doesn't receive notifications");
+    exex_handle.assert_event_finished_height(generator.tip().unwrap())?;
+
+    // Resumed plugin receives notifications of the kinds it had before the pause
+    manager.set_plugin_paused("RecordingExEx", false)?;
+    manager.handle_notification(generator.commit(1)?).await?;
+    assert_eq!(plugin.notifications().len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_dispatch_deep_reorgs_without_max_depth() -> Result<()> {
+    let (mut manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let plugin = RecordingExExPlugin::default();
+    manager.register_plugin(Box::new(plugin.clone())).await?;
+    let mut deep_reorgs = manager.subscribe_deep_reorgs();
+
+    let mut generator = NotificationGenerator::new(1);
+    manager.handle_notification(generator.commit(10)?).await?;
+    exex_handle.assert_event_finished_height(generator.tip().unwrap())?;
+    manager.handle_notification(generator.reorg(6, 1)?).await?;
+    exex_handle.assert_event_finished_height(generator.tip().unwrap())?;
+
+    manager.handle_notification(generator.commit(1)?).await?;
+
+    // Reorgs of any depth are dispatched, without pausing plugins
+    assert_eq!(plugin.notifications().len(), 3);
+    assert!(deep_reorgs.try_recv().is_err());
+    assert!(!manager.plugin_info("RecordingExEx")?.paused);
+
+    Ok(())
+}
+
+#[test]
+fn should_match_notifications_by_combined_subscription_filters() -> Result<()> {
+    let mut generator = NotificationGenerator::new(1);
+    let commits = generator.by_ref().take(6).collect::<Vec<_>>();
+    let reorg = generator.reorg(4, 1)?;
+    let spec = SubscriptionSpec::default()
+        .with_blocks(BlockRange { from: 3, to: 5 })
+        .with_kind(ChainKind::Commit)
+        .with_kind(ChainKind::Reorg)
+        .with_min_interval(2);
+
+    // Blocks and kinds
+    assert!(SubscriptionSpec::default().matches(&commits[0], None));
+    assert!(!spec.matches(&commits[1], None), "Block 2 is out of range");
+    assert!(spec.matches(&commits[2], None));
+    assert!(!spec.matches(&commits[5], None), "Block 6 is out of range");
+    assert!(spec.matches(&reorg, None), "Reverted blocks 5..=6 overlap the range");
+    let revert = synthetic_notification(&NormalizedNotification {
+        kind: ChainKind::Revert,
+        reverted: Some(BlockRange { from: 3, to: 5 }),
+        committed: None,
+    })?;
+    assert!(!spec.matches(&revert, None), "Reverts aren't subscribed");
+
+    // Min interval throttles commits only
+    assert!(!spec.matches(&commits[3], Some(3)));
+    assert!(spec.matches(&commits[4], Some(3)));
+    assert!(spec.matches(&reorg, Some(5)));
+
+    // Transaction filter of an empty block
+    let spec = spec.with_tx_filter(TxFilter::default().with_to(Address::with_last_byte(1)));
+    assert!(!spec.matches(&commits[2], None));
+    assert!(spec.matches(&reorg, None), "Transaction filter applies to commits only");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_dispatch_only_subscribed_notifications() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let recording = RecordingExExPlugin::default();
+    let spec = SubscriptionSpec::default()
+        .with_blocks(BlockRange { from: 2, to: u64::MAX })
+        .with_kind(ChainKind::Commit)
+        .with_min_interval(3);
+    let id = plugin_manager
+        .register_plugin(Box::new(SubscribedExEx { spec, recording: recording.clone() }))
+        .await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    for notification in generator.by_ref().take(8) {
+        plugin_manager.handle_notification(notification).await?;
+    }
+    plugin_manager.handle_notification(generator.reorg(6, 1)?).await?;
+
+    let tips = recording
+        .notifications()
+        .iter()
+        .map(|notification| notification.committed.map(|range| range.to))
+        .collect::<Vec<_>>();
+    assert_eq!(tips, vec![Some(2), Some(5), Some(8)]);
+
+    // Kinds set at runtime are matched together with the subscribed ones
+    plugin_manager.set_plugin_notification_kinds(&id, &[ChainKind::Commit, ChainKind::Reorg])?;
+    plugin_manager.handle_notification(generator.reorg(6, 1)?).await?;
+    plugin_manager.set_plugin_notification_kinds(&id, &[ChainKind::Revert])?;
+    plugin_manager.handle_notification(generator.commit(5)?).await?;
+    assert_eq!(recording.notifications().len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_report_plugin_handlers_in_flight() -> Result<()> {
+    let (mut plugin_manager, exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let plugin = GatedExEx::default();
+    let gate = plugin.gate.clone();
+    let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+    let manager = tokio::spawn(plugin_manager.run());
+    let rpc = ExExPluginRpc::new(rpc_request_tx);
+    assert_eq!(rpc.plugin_inflight(id.clone()).await?, 0);
+
+    // Read requests are answered during the dispatch
+    exex_handle.notifications_tx.send(genesis_committed(&exex_handle)).await?;
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while rpc.plugin_inflight(id.clone()).await? == 0 {
+            tokio::task::yield_now().await;
+        }
+        eyre::Ok(())
+    })
+    .await??;
+    assert_eq!(rpc.plugin_inflight(id.clone()).await?, 1);
+
+    gate.notify_one();
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while rpc.plugin_inflight(id.clone()).await? != 0 {
+            tokio::task::yield_now().await;
+        }
+        eyre::Ok(())
+    })
+    .await??;
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_return_full_status_of_all_plugins() -> Result<()> {
+    let (mut plugin_manager, exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let id = plugin_manager
+        .register_plugin(Box::new(FailingExEx { warmup: 0, required: false }))
+        .await?;
+    let notification = genesis_committed(&exex_handle);
+    let expected = NormalizedNotification::from(&notification);
+    plugin_manager.handle_notification(notification).await?;
+
+    let manager = tokio::spawn(plugin_manager.run());
+    let statuses = ExExPluginRpc::new(rpc_request_tx).plugins_full().await?;
+    assert_eq!(statuses.len(), 1);
+    let status = &statuses[0];
+    assert_eq!(status.info.id, id);
+    assert_eq!((status.info.handled, status.info.failures), (1, 1));
+    assert_eq!(status.inflight, 0);
+    assert_eq!(status.last_error.as_deref(), Some("not ready"));
+    assert_eq!(status.last_notification, Some(expected));
+    assert_eq!(status.latest_result, None);
+    assert!(status.health.healthy);
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_coalesce_commits_while_behind_tip() -> Result<()> {
+    let (manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    // Generated blocks are timestamped at the unix epoch, so the node is always behind
+    let config = CoalesceConfig { lag: Duration::from_secs(60), window: 3 };
+    let mut manager = manager.with_coalescing(config);
+    let coalescing = CoalescingExEx::default();
+    let coalesced = coalescing.recording.clone();
+    manager.register_plugin(Box::new(coalescing)).await?;
+    let recording = RecordingExExPlugin::default();
+    manager.register_plugin(Box::new(recording.clone())).await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    let mut tips = Vec::new();
+    for notification in generator.by_ref().take(7) {
+        tips.push(notification.committed_chain().unwrap().tip().num_hash());
+        manager.handle_notification(notification).await?;
+    }
+    let committed = |notifications: Vec<NormalizedNotification>| {
+        notifications.into_iter().map(|notification| notification.committed).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        committed(coalesced.notifications()),
+        vec![Some(BlockRange { from: 1, to: 3 }), Some(BlockRange { from: 4, to: 6 })]
+    );
+    assert_eq!(recording.notifications().len(), 7, "Other plugins receive every notification");
+
+    // Finished height is held back below pending blocks
+    let mut finished = Vec::new();
+    while let Ok(ExExEvent::FinishedHeight(height)) = exex_handle.events_rx.try_recv() {
+        finished.push(height);
+    }
+    assert_eq!(
+        finished.iter().map(|height| height.number).collect::<Vec<_>>(),
+        vec![0, 0, 3, 3, 3, 6, 6]
+    );
+    assert_eq!(finished[6], tips[5]);
+
+    // Reorg flushes pending commits first
+    manager.handle_notification(generator.reorg(6, 1)?).await?;
+    let notifications = coalesced.notifications();
+    assert_eq!(notifications[2].committed, Some(BlockRange { from: 7, to: 7 }));
+    assert_eq!(notifications[3].kind, ChainKind::Reorg);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_record_notification_stats_without_plugins() -> Result<()> {
+    let (mut plugin_manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    assert!(plugin_manager.is_empty());
+    assert_eq!(plugin_manager.status().notifications, NotificationStats::default());
+
+    let mut generator = NotificationGenerator::new(1);
+    plugin_manager.handle_notification(generator.commit(5)?).await?;
+    exex_handle.assert_event_finished_height(generator.tip().unwrap())?;
+    plugin_manager.handle_notification(generator.reorg(3, 1)?).await?;
+    let revert = NormalizedNotification {
+        kind: ChainKind::Revert,
+        reverted: Some(BlockRange { from: 4, to: 4 }),
+        committed: None,
+    };
+    plugin_manager.handle_notification(synthetic_notification(&revert)?).await?;
+
+    let stats = plugin_manager.status().notifications;
+    assert_eq!(
+        stats,
+        NotificationStats { commits: 1, reverts: 1, reorgs: 1, highest_block: Some(5) }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_label_responses_with_node_network() -> Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let chain = exex_ctx.config.chain.chain();
+    let plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let expected = NetworkLabel { chain_id: chain.id(), name: chain.to_string() };
+    assert_eq!(plugin_manager.network(), &expected);
+    assert_eq!(plugin_manager.status().network, expected);
+
+    let info = plugin_manager.server_info();
+    assert_eq!(info.network, expected);
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_iterate_loaded_plugins_in_load_order() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    for (id, version) in [("FirstExEx", "1.0.0"), ("SecondExEx", "2.1.0"), ("ThirdExEx", "0.3.0")] {
+        plugin_manager.register_plugin(Box::new(VersionedExEx { id, version })).await?;
+    }
+
+    let mut versions = Vec::new();
+    plugin_manager.for_each_plugin(|plugin| versions.push((plugin.id(), plugin.version())));
+    assert_eq!(
+        versions,
+        vec![("FirstExEx", "1.0.0"), ("SecondExEx", "2.1.0"), ("ThirdExEx", "0.3.0")]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_not_block_dispatch_on_queued_plugin() -> Result<()> {
+    let (mut plugin_manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let gated = GatedExEx::default();
+    let (gate, handled) = (gated.gate.clone(), gated.handled.clone());
+    plugin_manager.register_plugin(Box::new(QueuedExExPlugin::new(gated, 4))).await?;
+    let recording = RecordingExExPlugin::new("RecordingExEx");
+    plugin_manager.register_plugin(Box::new(recording.clone())).await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    let notifications = generator.by_ref().take(4).collect::<Vec<_>>();
+    let fork_block = notifications[0].committed_chain().unwrap().fork_block();
+    for notification in &notifications[..3] {
+        let dispatch = plugin_manager.handle_notification(notification.clone());
+        tokio::time::timeout(Duration::from_secs(1), dispatch).await??;
+        // held at the block the queue is processed up to
+        exex_handle.assert_event_finished_height(fork_block)?;
+    }
+    assert_eq!(recording.notifications().len(), 3, "Other plugins aren't delayed");
+
+    // The finished height follows the queue
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while handled.load(Ordering::SeqCst) < 3 {
+            gate.notify_one();
+            tokio::task::yield_now().await;
+        }
+    })
+    .await?;
+    plugin_manager.handle_notification(notifications[3].clone()).await?;
+    let processed = notifications[2].committed_chain().unwrap().tip().num_hash();
+    exex_handle.assert_event_finished_height(processed)?;
+
+    // Drain flushes the queue
+    gate.notify_one();
+    tokio::time::timeout(Duration::from_secs(1), plugin_manager.drain()).await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_flip_readiness_with_required_plugin_state() -> Result<()> {
+    let (plugin_manager, _exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_required_plugins(["ToggledExEx"]);
+    let readiness = plugin_manager.readiness().await;
+    assert!(!readiness.ready);
+    assert_eq!(readiness.reasons, vec!["ToggledExEx is not loaded"]);
+
+    let plugin = ToggledExEx::default();
+    let failing = plugin.failing.clone();
+    let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+    assert_eq!(plugin_manager.readiness().await, Readiness { ready: true, reasons: vec![] });
+
+    // Failing
+    let mut generator = NotificationGenerator::new(1);
+    failing.store(true, Ordering::SeqCst);
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    let readiness = plugin_manager.readiness().await;
+    assert!(!readiness.ready);
+    assert_eq!(
+        readiness.reasons,
+        vec![
+            "ToggledExEx failed its last notification: connection lost",
+            "ToggledExEx is unhealthy: connection lost",
+        ]
+    );
+
+    // Recovered
+    failing.store(false, Ordering::SeqCst);
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    assert!(plugin_manager.readiness().await.ready);
+
+    // Paused
+    plugin_manager.set_plugin_paused(&id, true)?;
+    let readiness = plugin_manager.readiness().await;
+    assert_eq!(readiness.reasons, vec!["ToggledExEx is paused"]);
+    assert!(plugin_manager.plugin_info(&id)?.paused);
+
+    plugin_manager.set_plugin_paused(&id, false)?;
+    let manager = tokio::spawn(plugin_manager.run());
+    let rpc = ExExPluginRpc::new(rpc_request_tx);
+    assert!(rpc.readiness().await?.ready);
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_rpc_requests_during_notifications_flood() -> Result<()> {
+    const NOTIFICATIONS: u64 = 500;
+
+    let (plugin_manager, exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_rpc_fairness(4);
+    let handled = Arc::new(AtomicU64::new(0));
+    let plugin = SlowExEx { delay: Duration::from_millis(2), handled: handled.clone() };
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+    let manager = tokio::spawn(plugin_manager.run());
+
+    // The node's notifications stream is always ready during the flood
+    let flood = tokio::spawn(async move {
+        let mut generator = NotificationGenerator::new(1);
+        for _ in 0..NOTIFICATIONS {
+            exex_handle.notifications_tx.send(generator.commit(1)?).await?;
+        }
+        eyre::Ok(exex_handle)
+    });
+    while handled.load(Ordering::SeqCst) == 0 {
+        tokio::task::yield_now().await;
+    }
+
+    let rpc = ExExPluginRpc::new(rpc_request_tx);
+    let count = tokio::time::timeout(Duration::from_millis(200), rpc.plugin_count()).await??;
+    assert_eq!(count, 1);
+    assert!(handled.load(Ordering::SeqCst) < NOTIFICATIONS, "Served before the flood ended");
+
+    flood.abort();
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_retry_queued_notifications_of_recovered_required_plugin() -> Result<()> {
+    let (plugin_manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_retry_queue(8, Duration::from_millis(20));
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let plugin =
+        RecoveringExEx { failures: AtomicU64::new(2), idempotent: false, handled: handled.clone() };
+    let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    // Failed notification is queued, holding back the finished height
+    let mut generator = NotificationGenerator::new(1);
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    exex_handle.assert_events_empty();
+    assert_eq!(plugin_manager.plugin_info(&id)?.pending_retries, 1);
+
+    // Following notifications are queued behind the failed one, while it keeps failing
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    exex_handle.assert_events_empty();
+    assert_eq!(plugin_manager.plugin_info(&id)?.pending_retries, 2);
+    assert!(handled.lock().unwrap().is_empty());
+
+    // Recovered plugin receives queued notifications in order on a scheduled retry,
+    // releasing the held back finished height
+    let manager = tokio::spawn(plugin_manager.run());
+    let event = tokio::time::timeout(Duration::from_secs(1), exex_handle.events_rx.recv()).await?;
+    let tip = generator.tip().unwrap();
+    assert_eq!(event, Some(ExExEvent::FinishedHeight(tip)));
+    assert_eq!(*handled.lock().unwrap(), vec![tip.number - 1, tip.number]);
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_roll_back_partially_loaded_plugin() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let path = std::env::temp_dir().join("exex_partial_load_plugin");
+    let hooks = Arc::new(Mutex::new(Vec::new()));
+    let plugin = PartialLoadExEx { path: path.clone(), hooks: hooks.clone() };
+
+    let err = plugin_manager.register_plugin(Box::new(plugin)).await.expect_err("load fails");
+    assert!(err.to_string().contains("connection refused"));
+    assert_eq!(*hooks.lock().unwrap(), vec!["on_load_failed"], "Unload hook isn't called");
+    assert!(!path.exists(), "Side effects of the failed load are rolled back");
+    assert!(plugin_manager.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_sign_plugin_outputs_with_manager_key() -> Result<()> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    let (plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    assert_eq!(plugin_manager.server_info().public_key, None);
+
+    let mut plugin_manager = plugin_manager.with_signing_key([7; 32]);
+    let plugin = SigningExEx::default();
+    let signed = plugin.signed.clone();
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    plugin_manager.handle_notification(genesis_committed(&exex_handle)).await?;
+
+    let public_key = plugin_manager.server_info().public_key.expect("signing key is set");
+    let public_key = VerifyingKey::from_bytes(&public_key.0)?;
+    let signed = signed.lock().unwrap().clone();
+    assert_eq!(signed.len(), 1);
+    let (record, signature) = &signed[0];
+    public_key.verify(record, &Signature::from_bytes(signature))?;
+
+    // A tampered record isn't verified
+    let mut tampered = record.clone();
+    tampered[0] ^= 1;
+    assert!(public_key.verify(&tampered, &Signature::from_bytes(signature)).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_handle_notifications_of_paused_plugin_by_its_policy() -> Result<()> {
+    let committed = |from, to| Some(BlockRange { from, to });
+    for (policy, expected) in [
+        (PausePolicy::Skip, vec![committed(1, 1), committed(5, 5)]),
+        (
+            PausePolicy::Buffer { capacity: 2 },
+            vec![committed(1, 1), committed(2, 2), committed(3, 3), committed(5, 5)],
+        ),
+        (PausePolicy::Backfill, vec![committed(1, 1), committed(2, 4), committed(5, 5)]),
+    ] {
+        let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+        let recording = RecordingExExPlugin::default();
+        let plugin = PausableExEx { policy, recording: recording.clone() };
+        let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+        let mut generator = NotificationGenerator::new(1);
+        plugin_manager.handle_notification(generator.commit(1)?).await?;
+
+        plugin_manager.set_plugin_paused(&id, true)?;
+        for _ in 0..3 {
+            plugin_manager.handle_notification(generator.commit(1)?).await?;
+        }
+        assert_eq!(recording.notifications().len(), 1, "{policy:?}: paused plugin received");
+
+        // Kept notifications are replayed on resume before the new one
+        plugin_manager.set_plugin_paused(&id, false)?;
+        plugin_manager.handle_notification(generator.commit(1)?).await?;
+        let received = recording
+            .notifications()
+            .into_iter()
+            .map(|notification| notification.committed)
+            .collect::<Vec<_>>();
+        assert_eq!(received, expected, "{policy:?}");
+        assert_eq!(plugin_manager.plugin_info(&id)?.paused_backlog, 0);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_keep_notifications_by_pause_policy_only_while_paused() -> Result<()> {
+    for policy in [PausePolicy::Buffer { capacity: 2 }, PausePolicy::Backfill] {
+        let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+        let recording = RecordingExExPlugin::default();
+        let plugin = PausableExEx { policy, recording: recording.clone() };
+        let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+        let mut generator = NotificationGenerator::new(1);
+
+        // Opting out of all kinds skips notifications instead of keeping them
+        plugin_manager.set_plugin_notification_kinds(&id, &[])?;
+        plugin_manager.handle_notification(generator.commit(1)?).await?;
+        assert_eq!(plugin_manager.plugin_info(&id)?.paused_backlog, 0, "{policy:?}");
+
+        plugin_manager.set_plugin_notification_kinds(&id, &[ChainKind::Commit])?;
+        plugin_manager.set_plugin_paused(&id, true)?;
+        plugin_manager.handle_notification(generator.commit(1)?).await?;
+        assert_eq!(plugin_manager.plugin_info(&id)?.paused_backlog, 1, "{policy:?}");
+        assert!(recording.notifications().is_empty(), "{policy:?}: paused plugin received");
+
+        // Resumed plugin keeps its kinds, receiving the kept commit but not the revert
+        plugin_manager.set_plugin_paused(&id, false)?;
+        let revert = NormalizedNotification {
+            kind: ChainKind::Revert,
+            reverted: Some(BlockRange { from: 2, to: 2 }),
+            committed: None,
+        };
+        plugin_manager.handle_notification(synthetic_notification(&revert)?).await?;
+        let received = recording
+            .notifications()
+            .into_iter()
+            .map(|notification| (notification.kind, notification.committed))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            received,
+            vec![(ChainKind::Commit, Some(BlockRange { from: 2, to: 2 }))],
+            "{policy:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_buffer_plugin_log_lines() -> Result<()> {
+    let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_plugin_log_capacity(3);
+    let id = plugin_manager.register_plugin(Box::new(LoggingExEx::default())).await?;
+    let mut subscription = plugin_manager.subscribe_plugin_logs(&id)?;
+
+    let lines = plugin_manager.plugin_logs(&id, None)?;
+    assert_eq!(lines.len(), 1);
+    assert_eq!((lines[0].stream, lines[0].message.as_str()), (LogStream::Stderr, "starting"));
+
+    let mut generator = NotificationGenerator::new(1);
+    for _ in 0..3 {
+        plugin_manager.handle_notification(generator.commit(1)?).await?;
+    }
+
+    // The oldest line is dropped from the bounded buffer
+    let messages =
+        |lines: Vec<PluginLogLine>| lines.into_iter().map(|line| line.message).collect::<Vec<_>>();
+    assert_eq!(
+        messages(plugin_manager.plugin_logs(&id, None)?),
+        vec!["handled Some(1..=1)", "handled Some(2..=2)", "handled Some(3..=3)"]
+    );
+    assert_eq!(messages(plugin_manager.plugin_logs(&id, Some(1))?), vec!["handled Some(3..=3)"]);
+
+    // Subscribers receive lines written after subscribing
+    let line = subscription.try_recv()?;
+    assert_eq!((line.stream, line.message.as_str()), (LogStream::Stdout, "handled Some(1..=1)"));
+
+    assert!(plugin_manager.plugin_logs("UnknownExEx", None).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_notify_plugin_of_tip_only_for_head_notifications() -> Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let provider = exex_ctx.provider().clone();
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    let plugin = TipExEx::default();
+    let (tips, recording) = (plugin.tips.clone(), plugin.recording.clone());
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    let notifications = generator.by_ref().take(5).collect::<Vec<_>>();
+    let head = notifications[4].committed_chain().unwrap().tip().header.clone();
+    provider.set_canonical_head(head);
+
+    // The node backfills historical blocks below its head
+    for notification in &notifications[..4] {
+        plugin_manager.handle_notification(notification.clone()).await?;
+    }
+    assert_eq!(recording.notifications().len(), 4);
+    assert!(tips.lock().unwrap().is_empty(), "Backfilled notification isn't the tip");
+
+    plugin_manager.handle_notification(notifications[4].clone()).await?;
+    assert_eq!(*tips.lock().unwrap(), vec![5]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_reject_calls_of_disabled_rpc_methods() -> Result<()> {
+    let (plugin_manager, _exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let manager = tokio::spawn(plugin_manager.run());
+    let rpc = ExExPluginRpc::new(rpc_request_tx)
+        .with_allowed_methods(["listPlugins", "managerStatus"])
+        .into_rpc();
+
+    let plugins: Vec<String> = rpc.call("exex_listPlugins", [(); 0]).await?;
+    assert!(plugins.is_empty());
+
+    let err = rpc.call::<_, String>("exex_loadPlugin", ["/tmp/libplugin.so"]).await.unwrap_err();
+    assert!(err.to_string().contains("method disabled: exex_loadPlugin"), "{err}");
+
+    // Subscriptions are rejected the same way
+    assert!(rpc.subscribe_unbounded("exex_subscribePluginErrors", [None::<String>]).await.is_err());
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_record_hook_timings_of_profiled_plugins() -> Result<()> {
+    let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_profiling(true);
+    let id = plugin_manager.register_plugin(Box::new(RecordingExExPlugin::default())).await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    for _ in 0..3 {
+        plugin_manager.handle_notification(generator.commit(1)?).await?;
+    }
+    plugin_manager.plugin_health(&id).await?;
+
+    let profile = plugin_manager.plugin_profile(&id)?;
+    assert_eq!(profile.hooks[&PluginHook::OnLoad].count, 1);
+    assert_eq!(profile.hooks[&PluginHook::Health].count, 1);
+    let handled = profile.hooks[&PluginHook::HandleNotification];
+    assert_eq!(handled.count, 3);
+    assert!(handled.p50_us <= handled.p99_us && handled.p99_us <= handled.max_us);
+    assert!(!profile.hooks.contains_key(&PluginHook::OnUnload));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_not_profile_plugins_by_default() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let id = plugin_manager.register_plugin(Box::new(RecordingExExPlugin::default())).await?;
+
+    assert!(plugin_manager.plugin_profile(&id).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_notify_plugin_of_newly_finalized_blocks() -> Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let provider = exex_ctx.provider().clone();
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    let plugin = FinalizedExEx::default();
+    let finalized = plugin.finalized.clone();
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    // No finalized block yet
+    let chain = synthetic_chain(BlockRange { from: 1, to: 10 })?;
+    let finalized_header = chain.blocks_iter().nth(4).unwrap().header.clone();
+    let tip_header = chain.tip().header.clone();
+    plugin_manager
+        .handle_notification(ExExNotification::ChainCommitted { new: chain.into() })
+        .await?;
+    assert!(finalized.lock().unwrap().is_empty());
+
+    // Fired once per finalized block
+    provider.set_finalized(finalized_header);
+    for range in [BlockRange { from: 11, to: 11 }, BlockRange { from: 12, to: 12 }] {
+        let chain = synthetic_chain(range)?;
+        plugin_manager
+            .handle_notification(ExExNotification::ChainCommitted { new: chain.into() })
+            .await?;
+    }
+    assert_eq!(*finalized.lock().unwrap(), vec![5]);
+
+    provider.set_finalized(tip_header);
+    let chain = synthetic_chain(BlockRange { from: 13, to: 13 })?;
+    plugin_manager
+        .handle_notification(ExExNotification::ChainCommitted { new: chain.into() })
+        .await?;
+    assert_eq!(*finalized.lock().unwrap(), vec![5, 10]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_isolate_panic_of_plugin_on_its_thread() -> Result<()> {
+    let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_plugin_threads(true);
+    let plugin = ThreadNameExEx::default();
+    let threads = plugin.threads.clone();
+    plugin_manager.register_plugin(Box::new(PanickingExEx)).await?;
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    for notification in NotificationGenerator::new(1).take(2) {
+        plugin_manager.handle_notification(notification).await?;
+    }
+
+    // The panicking plugin is dead and isn't called anymore
+    let info = plugin_manager.plugin_info("PanickingExEx")?;
+    assert!(info.dead);
+    assert_eq!(info.failures, 2);
+    let statuses = plugin_manager.plugins_full().await;
+    let status = statuses.iter().find(|status| status.info.id == "PanickingExEx").unwrap();
+    assert!(status.last_error.as_deref().is_some_and(|err| err.contains("dead")));
+
+    // Others continue on their own threads
+    assert!(!plugin_manager.plugin_info("ThreadNameExEx")?.dead);
+    let names = threads.lock().unwrap().clone();
+    assert_eq!(names, vec![Some("exex-plugin-ThreadNameExEx".to_owned()); 2]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_complete_handler_on_plugin_thread_after_dispatch_is_dropped() -> Result<()> {
+    let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_plugin_threads(true);
+    let (plugin, release) = BlockedExEx::new(false);
+    let handled = plugin.handled.clone();
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    // The dispatch is dropped while the handler is blocked on the plugin's thread
+    let mut generator = NotificationGenerator::new(1);
+    let dispatch = plugin_manager.handle_notification(generator.commit(1)?);
+    assert!(tokio::time::timeout(Duration::from_millis(50), dispatch).await.is_err());
+
+    // The handler owns everything it uses, so it completes once released
+    release.send(())?;
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while handled.load(Ordering::Relaxed) == 0 {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await?;
+
+    release.send(())?;
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    assert_eq!(handled.load(Ordering::Relaxed), 2);
+    assert!(!plugin_manager.plugin_info("BlockedExEx")?.dead);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_answer_read_requests_during_dispatch_of_blocking_plugin() -> Result<()> {
+    let (mut plugin_manager, exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let (plugin, release) = BlockedExEx::new(true);
+    let handled = plugin.handled.clone();
+    let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+    let manager = tokio::spawn(plugin_manager.run());
+    let rpc = ExExPluginRpc::new(rpc_request_tx);
+
+    // The handler blocks its thread, which isn't the manager's one
+    exex_handle.notifications_tx.send(genesis_committed(&exex_handle)).await?;
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while rpc.plugin_inflight(id.clone()).await? == 0 {
+            tokio::task::yield_now().await;
+        }
+        eyre::Ok(())
+    })
+    .await??;
+    assert_eq!(rpc.list_plugins().await?, vec![id.clone()]);
+    assert_eq!(handled.load(Ordering::Relaxed), 0);
+
+    release.send(())?;
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while rpc.plugin_inflight(id.clone()).await? != 0 {
+            tokio::task::yield_now().await;
+        }
+        eyre::Ok(())
+    })
+    .await??;
+    assert_eq!(handled.load(Ordering::Relaxed), 1);
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_reset_plugin_metrics_after_reading_them() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let plugin = FailingExEx { warmup: 1, required: false };
+    let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    for _ in 0..3 {
+        plugin_manager.handle_notification(generator.commit(1)?).await?;
+    }
+
+    // Pre-reset metrics are returned
+    let info = plugin_manager.reset_plugin_metrics(&id)?;
+    assert_eq!((info.handled, info.failures), (3, 2));
+    let info = plugin_manager.plugin_info(&id)?;
+    assert_eq!((info.handled, info.failures), (0, 0));
+
+    // Subsequent reads start fresh, without restarting the warmup
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    let info = plugin_manager.plugin_info(&id)?;
+    assert_eq!((info.handled, info.failures), (1, 1));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_deliver_notifications_to_each_plugin_in_chain_order() -> Result<()> {
+    let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_plugin_threads(true);
+    let recordings = ["RecordingExEx0", "RecordingExEx1", "RecordingExEx2", "RecordingExEx3"]
+        .map(RecordingExExPlugin::new);
+    for recording in &recordings {
+        plugin_manager.register_plugin(Box::new(recording.clone())).await?;
+    }
+    let queued = RecordingExExPlugin::new("QueuedRecordingExEx");
+    plugin_manager.register_plugin(Box::new(QueuedExExPlugin::new(queued.clone(), 4))).await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    let mut notifications = Vec::new();
+    for _ in 0..10 {
+        notifications.push(generator.commit(1)?);
+    }
+    notifications.push(generator.reorg(7, 3)?);
+    for _ in 0..10 {
+        notifications.push(generator.commit(2)?);
+    }
+
+    let expected = notifications.iter().map(NormalizedNotification::from).collect::<Vec<_>>();
+    for notification in notifications {
+        plugin_manager.handle_notification(notification).await?;
+    }
+    plugin_manager.drain().await?;
+
+    for recording in recordings.iter().chain([&queued]) {
+        assert_eq!(recording.notifications(), expected, "{}", recording.id());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_number_dispatched_notifications_in_sequence() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let plugin = SequenceExEx::default();
+    let sequences = plugin.sequences.clone();
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+    assert_eq!(plugin_manager.status().sequence, 0);
+
+    let mut generator = NotificationGenerator::new(1);
+    let reverted = synthetic_chain(BlockRange { from: 2, to: 2 })?;
+    let notifications = [
+        generator.commit(1)?,
+        generator.commit(1)?,
+        generator.reorg(1, 1)?,
+        ExExNotification::ChainReverted { old: reverted.into() },
+        generator.commit(1)?,
+    ];
+    for notification in notifications {
+        plugin_manager.handle_notification(notification).await?;
+    }
+
+    // Incremented across commits, reorgs and reverts, regardless of block numbers
+    assert_eq!(*sequences.lock().unwrap(), vec![1, 2, 3, 4, 5]);
+    assert_eq!(plugin_manager.status().sequence, 5);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_reconfigure_running_plugin_keeping_its_state() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let plugin = ThresholdExEx::default();
+    let recorded = plugin.recorded.clone();
+    plugin_manager.register_plugin(Box::new(plugin)).await?;
+    plugin_manager.register_plugin(Box::new(RecordingExExPlugin::default())).await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    for notification in generator.by_ref().take(2) {
+        plugin_manager.handle_notification(notification).await?;
+    }
+    assert_eq!(*recorded.lock().unwrap(), vec![1, 2]);
+
+    let config = serde_json::json!({ "threshold": 3 });
+    plugin_manager.reconfigure_plugin("ThresholdExEx", config.clone())?;
+    assert_eq!(plugin_manager.plugin_config("ThresholdExEx")?, Some(config));
+    for notification in generator.by_ref().take(3) {
+        plugin_manager.handle_notification(notification).await?;
+    }
+    // blocks before the reconfiguration are kept, the new threshold applies to next ones
+    assert_eq!(*recorded.lock().unwrap(), vec![1, 2, 4, 5]);
+
+    // an invalid config is rejected and the previous one stays in effect
+    assert!(plugin_manager.reconfigure_plugin("ThresholdExEx", serde_json::json!({})).is_err());
+    assert_eq!(
+        plugin_manager.plugin_config("ThresholdExEx")?,
+        Some(serde_json::json!({ "threshold": 3 }))
+    );
+
+    // a panicking reconfiguration keeps the plugin loaded with its previous config
+    let err = plugin_manager
+        .reconfigure_plugin("ThresholdExEx", serde_json::json!({ "panic": true }))
+        .unwrap_err();
+    assert!(err.to_string().contains("reconfiguration panicked"));
+    assert_eq!(
+        plugin_manager.plugin_config("ThresholdExEx")?,
+        Some(serde_json::json!({ "threshold": 3 }))
+    );
+
+    let err = plugin_manager
+        .reconfigure_plugin("RecordingExEx", serde_json::json!({ "threshold": 3 }))
+        .unwrap_err();
+    assert!(err.to_string().contains("reconfigure unsupported"));
+    assert!(plugin_manager.reconfigure_plugin("UnknownExEx", serde_json::json!({})).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_answer_panicking_reconfiguration_with_rpc_error() -> Result<()> {
+    let (mut plugin_manager, _exex_handle, rpc_request_tx) = plugin_manager().await?;
+    let id = plugin_manager.register_plugin(Box::new(ThresholdExEx::default())).await?;
+    let manager = tokio::spawn(plugin_manager.run());
+    let rpc = ExExPluginRpc::new(rpc_request_tx);
+
+    let err =
+        rpc.reconfigure_plugin(id.clone(), serde_json::json!({ "panic": true })).await.unwrap_err();
+    assert!(err.message().contains("reconfiguration panicked"));
+
+    // the manager keeps serving requests, with the plugin still loaded
+    assert_eq!(rpc.list_plugins().await?, vec![id.clone()]);
+    rpc.reconfigure_plugin(id.clone(), serde_json::json!({ "threshold": 3 })).await?;
+    assert!(!manager.is_finished());
+
+    manager.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_shard_plugin_output_by_block_range() -> Result<()> {
+    let dir = std::env::temp_dir().join("exex_plugins_sharded_output");
+    let _ = std::fs::remove_dir_all(&dir);
+    let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let mut plugin_manager = plugin_manager.with_sharded_output(&dir, 10);
+    plugin_manager.register_plugin(Box::new(ShardingExEx::default())).await?;
+
+    let shard_blocks = |name: &str| -> Result<Vec<u64>> {
+        let shard = std::fs::read_to_string(dir.join("ShardingExEx").join(name))?;
+        shard
+            .lines()
+            .map(|line| {
+                let line = serde_json::from_str::<serde_json::Value>(line)?;
+                line["block"].as_u64().ok_or_eyre("no block")
+            })
+            .collect()
+    };
+    let first = "000000000000-000000000009.jsonl";
+    let second = "000000000010-000000000019.jsonl";
+
+    // blocks 1..=14 are written across the shard boundary
+    let mut generator = NotificationGenerator::new(1);
+    plugin_manager.handle_notification(generator.commit(8)?).await?;
+    plugin_manager.handle_notification(generator.commit(6)?).await?;
+    assert_eq!(shard_blocks(first)?, (1..=9).collect::<Vec<_>>());
+    assert_eq!(shard_blocks(second)?, (10..=14).collect::<Vec<_>>());
+
+    // reverted blocks 13 and 14 are truncated before new ones are written
+    let reorg = generator.reorg(12, 1)?;
+    let new_tip = reorg.committed_chain().unwrap().tip().hash();
+    plugin_manager.handle_notification(reorg).await?;
+    assert_eq!(shard_blocks(second)?, vec![10, 11, 12, 13]);
+    let shard = std::fs::read_to_string(dir.join("ShardingExEx").join(second))?;
+    assert!(shard.contains(&serde_json::to_string(&new_tip)?));
+
+    // a reorg below the boundary removes the later shard
+    plugin_manager.handle_notification(generator.reorg(7, 1)?).await?;
+    assert_eq!(shard_blocks(first)?, (1..=8).collect::<Vec<_>>());
+    assert!(!dir.join("ShardingExEx").join(second).exists());
+
+    let sink = ShardedSink::new(dir.join("ShardingExEx"), 10);
+    assert_eq!(sink.prune(10)?, vec![dir.join("ShardingExEx").join(first)]);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_pass_sharded_output_to_wrapped_plugins() -> Result<()> {
+    let dir = std::env::temp_dir().join("exex_plugins_wrapped_sharded_output");
+    let wrapped: [Box<dyn ExExPlugin>; 2] = [
+        Box::new(RetryExExPlugin::new(ShardingExEx::default())),
+        Box::new(QueuedExExPlugin::new(ShardingExEx::default(), 4)),
+    ];
+    for plugin in wrapped {
+        let _ = std::fs::remove_dir_all(&dir);
+        let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+        let mut plugin_manager = plugin_manager.with_sharded_output(&dir, 10);
+        plugin_manager.register_plugin(plugin).await?;
+
+        let mut generator = NotificationGenerator::new(1);
+        plugin_manager.handle_notification(generator.commit(2)?).await?;
+        plugin_manager.drain().await?;
+        let shard = std::fs::read_to_string(
+            dir.join("ShardingExEx").join("000000000000-000000000009.jsonl"),
+        )?;
+        assert_eq!(shard.lines().count(), 2);
+    }
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_advance_finished_height_past_failures_of_idempotent_plugin() -> Result<()> {
+    let mut generator = NotificationGenerator::new(1);
+    let notifications = generator.by_ref().take(3).collect::<Vec<_>>();
+    let tip =
+        |notification: &ExExNotification| notification.committed_chain().unwrap().tip().number;
+
+    for idempotent in [false, true] {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let (manager, mut exex_handle, _rpc_request_tx) = plugin_manager().await?;
+        let mut manager = manager.with_retry_queue(8, Duration::from_secs(60));
+        let plugin =
+            RecoveringExEx { failures: AtomicU64::new(2), idempotent, handled: handled.clone() };
+        let id = manager.register_plugin(Box::new(plugin)).await?;
+
+        // Both plugins queue the failed and following notifications for a retry
+        for notification in &notifications[..2] {
+            manager.handle_notification(notification.clone()).await?;
+        }
+        assert_eq!(manager.plugin_info(&id)?.pending_retries, 2);
+        assert_eq!(manager.plugin_info(&id)?.idempotent, idempotent);
+        let mut finished = 0;
+        while let Ok(ExExEvent::FinishedHeight(height)) = exex_handle.events_rx.try_recv() {
+            finished = height.number;
+        }
+        // only the idempotent plugin doesn't hold back the finished height
+        assert_eq!(finished, if idempotent { 2 } else { 0 });
+
+        // Simulated restart: the node re-delivers notifications above the finished height
+        drop(manager);
+        let (mut manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+        let plugin =
+            RecoveringExEx { failures: AtomicU64::new(0), idempotent, handled: handled.clone() };
+        manager.register_plugin(Box::new(plugin)).await?;
+        for notification in notifications.iter().filter(|n| tip(n) > finished) {
+            manager.handle_notification(notification.clone()).await?;
+        }
+
+        // The non-idempotent plugin sees every block, while the idempotent one replays
+        // the blocks pending a retry before the restart itself
+        let expected = if idempotent { vec![3] } else { vec![1, 2, 3] };
+        assert_eq!(*handled.lock().unwrap(), expected);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_record_notifications_failed_by_all_plugins_as_dead_letters() -> Result<()> {
+    let (plugin_manager, _exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    let (dead_letter_tx, mut dead_letter_rx) = mpsc::unbounded_channel();
+    let mut plugin_manager =
+        plugin_manager.with_dead_letter_sink(DeadLetterSink::Channel(dead_letter_tx))?;
+    plugin_manager.register_plugin(Box::new(FailingExEx { warmup: 0, required: false })).await?;
+    let recovering =
+        RecoveringExEx { failures: AtomicU64::new(1), idempotent: false, handled: Arc::default() };
+    plugin_manager.register_plugin(Box::new(recovering)).await?;
+    // a plugin not receiving commits isn't interested in them
+    let recording = RecordingExExPlugin::default();
+    let recording_id = plugin_manager.register_plugin(Box::new(recording.clone())).await?;
+    plugin_manager.set_plugin_notification_kinds(&recording_id, &[ChainKind::Revert])?;
+
+    // Both interested plugins fail the first notification
+    let mut generator = NotificationGenerator::new(1);
+    let failed = generator.commit(1)?;
+    plugin_manager.handle_notification(failed.clone()).await?;
+    let mut record = dead_letter_rx.try_recv()?;
+    record.failing.sort();
+    assert_eq!(record.sequence, 1);
+    assert_eq!(record.notification, NormalizedNotification::from(&failed));
+    assert_eq!(record.failing, vec!["FailingExEx".to_owned(), "RecoveringExEx".to_owned()]);
+
+    // The recovered plugin handles the retried and the next notification, so neither of them
+    // is a dead letter
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    assert!(dead_letter_rx.try_recv().is_err());
+    assert!(recording.notifications().is_empty());
+
+    // Records of a file are written once drained
+    let dir = tempfile::tempdir()?;
+    let dead_letters = dir.path().join("dead_letters.jsonl");
+    let (manager, _exex_handle, _rpc_request_tx) = self::plugin_manager().await?;
+    let mut plugin_manager =
+        manager.with_dead_letter_sink(DeadLetterSink::File(dead_letters.clone()))?;
+    plugin_manager.register_plugin(Box::new(FailingExEx { warmup: 0, required: false })).await?;
+    for notification in generator.by_ref().take(2) {
+        plugin_manager.handle_notification(notification).await?;
+    }
+    plugin_manager.drain().await?;
+    let records = std::fs::read_to_string(&dead_letters)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<DeadLetterRecord>, _>>()?;
+    let sequences = records.iter().map(|record| record.sequence).collect::<Vec<_>>();
+    assert_eq!(sequences, vec![1, 2]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_explain_dispatch_matching_actual_dispatch() -> Result<()> {
+    let (mut plugin_manager, exex_handle, _rpc_request_tx) = plugin_manager().await?;
+    plugin_manager.register_plugin(Box::new(RecordingExExPlugin::new("AllExEx"))).await?;
+    let reverts =
+        plugin_manager.register_plugin(Box::new(RecordingExExPlugin::new("RevertsExEx"))).await?;
+    plugin_manager.set_plugin_notification_kinds(&reverts, &[ChainKind::Revert])?;
+    let paused =
+        plugin_manager.register_plugin(Box::new(RecordingExExPlugin::new("PausedExEx"))).await?;
+    plugin_manager.set_plugin_paused(&paused, true)?;
+    let spec = SubscriptionSpec::default()
+        .with_blocks(BlockRange { from: 2, to: u64::MAX })
+        .with_min_interval(3);
+    let recording = RecordingExExPlugin::default();
+    plugin_manager.register_plugin(Box::new(SubscribedExEx { spec, recording })).await?;
+    let filter = TxFilter::default().with_to(Address::with_last_byte(1));
+    plugin_manager.register_plugin(Box::new(FilteredExEx { filter, ..Default::default() })).await?;
+    let idempotent = IdempotentExEx { last_processed: Some(3), ..Default::default() };
+    plugin_manager.register_plugin(Box::new(idempotent)).await?;
+
+    let reason = |explanation: &[PluginDispatch], id: &str| {
+        explanation.iter().find(|dispatch| dispatch.id == id).unwrap().reason
+    };
+    let handled = |plugin_manager: &ExExPluginManager<Adapter>| {
+        plugin_manager
+            .plugins_info()
+            .into_iter()
+            .map(|info| (info.id, info.handled))
+            .collect::<HashMap<_, _>>()
+    };
+    let scenarios = [
+        (1, ChainKind::Commit),
+        (2, ChainKind::Commit),
+        (3, ChainKind::Commit),
+        (3, ChainKind::Revert),
+        (6, ChainKind::Reorg),
+    ];
+
+    // Explained blocks are read from the node's database, one with a transaction to the
+    // filtered address
+    let provider_rw = exex_handle.provider_factory.provider_rw()?;
+    for block in [1, 2, 3, 6] {
+        let chain = synthetic_chain(BlockRange { from: block, to: block })?;
+        provider_rw.insert_block(chain.tip().clone(), StorageLocation::Database)?;
+    }
+    let mut matching = synthetic_chain(BlockRange { from: 7, to: 7 })?.tip().clone();
+    matching.block.body.transactions.push(TransactionSigned::from_transaction_and_signature(
+        Transaction::Legacy(TxLegacy {
+            to: TxKind::Call(Address::with_last_byte(1)),
+            ..Default::default()
+        }),
+        Signature::test_signature(),
+    ));
+    provider_rw.insert_block(matching.clone(), StorageLocation::Database)?;
+    provider_rw.commit()?;
+
+    for (block, kind) in scenarios {
+        let blocks = DispatchBlocks::Range(BlockRange { from: block, to: block });
+        let explanation = plugin_manager.explain_dispatch(&blocks, kind)?;
+        assert_eq!(explanation.len(), 6);
+        match (block, kind) {
+            (1, ChainKind::Commit) => {
+                assert_eq!(reason(&explanation, "AllExEx"), None);
+                assert_eq!(reason(&explanation, "RevertsExEx"), Some(FilterReason::KindOptOut));
+                assert_eq!(reason(&explanation, "PausedExEx"), Some(FilterReason::Paused));
+                assert_eq!(reason(&explanation, "SubscribedExEx"), Some(FilterReason::BlockFilter));
+                assert_eq!(reason(&explanation, "FilteredExEx"), Some(FilterReason::TxFilter));
+                assert_eq!(
+                    reason(&explanation, "IdempotentExEx"),
+                    Some(FilterReason::AlreadyProcessed)
+                );
+            }
+            (3, ChainKind::Commit) => {
+                assert_eq!(reason(&explanation, "SubscribedExEx"), Some(FilterReason::RateLimit));
+            }
+            (3, ChainKind::Revert) => {
+                assert_eq!(reason(&explanation, "RevertsExEx"), None);
+                assert_eq!(reason(&explanation, "FilteredExEx"), None);
+                assert_eq!(reason(&explanation, "IdempotentExEx"), None);
+            }
+            _ => {}
+        }
+
+        // The explained plugins are exactly the ones the notification is dispatched to
+        let before = handled(&plugin_manager);
+        let range = Some(BlockRange { from: block, to: block });
+        let normalized = NormalizedNotification {
+            kind,
+            reverted: (kind != ChainKind::Commit).then_some(range).flatten(),
+            committed: (kind != ChainKind::Revert).then_some(range).flatten(),
+        };
+        plugin_manager.handle_notification(synthetic_notification(&normalized)?).await?;
+        let after = handled(&plugin_manager);
+        for dispatch in explanation {
+            let received = after[&dispatch.id] > before[&dispatch.id];
+            assert_eq!(dispatch.receives, received, "{} on {kind:?} of block {block}", dispatch.id);
+        }
+    }
+
+    // The transaction filter is matched against the block's transactions
+    let blocks = DispatchBlocks::Hashes(vec![matching.hash()]);
+    let explanation = plugin_manager.explain_dispatch(&blocks, ChainKind::Commit)?;
+    assert_eq!(reason(&explanation, "FilteredExEx"), None);
+    let before = handled(&plugin_manager);
+    let chain = Chain::from_block(matching, ExecutionOutcome::default(), None);
+    plugin_manager
+        .handle_notification(ExExNotification::ChainCommitted { new: chain.into() })
+        .await?;
+    assert!(handled(&plugin_manager)["FilteredExEx"] > before["FilteredExEx"]);
+
+    // Blocks missing from the node's database aren't explained
+    let blocks = DispatchBlocks::Hashes(vec![B256::with_last_byte(1)]);
+    assert!(plugin_manager.explain_dispatch(&blocks, ChainKind::Commit).is_err());
+    let blocks = DispatchBlocks::Range(BlockRange { from: 7, to: 8 });
+    assert!(plugin_manager.explain_dispatch(&blocks, ChainKind::Commit).is_err());
+
+    Ok(())
+}
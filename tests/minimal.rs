@@ -1,22 +1,39 @@
-use std::{future::Future, io, path::Path, pin::Pin};
+use std::{
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
 use jsonrpsee::types::{error::INTERNAL_ERROR_CODE, ErrorObjectOwned as RpcError};
-use reth::{
-    chainspec::Head,
-    primitives::BlockNumHash,
-    providers::{Chain, ExecutionOutcome},
+use reth::chainspec::Head;
+use reth_exex_plugin::{
+    plugin_span, testing::NotificationGenerator, ExExNotification, ExExPlugin, ExExPluginManager,
+    ManagerState, ManifestAction, ManifestReport, NodeInfo, PluginBuild, PluginDescriptor,
+    PluginLevelFilter, PluginState, RpcRequest, EXEX_PLUGIN_ABI_VERSION,
+    EXEX_PLUGIN_DESCRIPTOR_FN_NAME,
 };
-use reth_exex_plugin::{ExExPluginManager, RpcRequest};
 use reth_exex_test_utils::{test_exex_context, Adapter, PollOnce, TestExExHandle};
 
 use reth_node_api::FullNodeComponents;
-use reth_tracing::init_test_tracing;
+use reth_tracing::{
+    init_test_tracing,
+    tracing::{subscriber, Level},
+    tracing_subscriber,
+};
 use tokio::sync::{mpsc, oneshot};
 
-const MINIMAL_PLUGIN_PATH: &'static str = "examples/minimal/target/release/libminimal.dylib";
+mod common;
+
 const MINIMAL_PLUGIN_DUMMY_STORAGE_PATH: &'static str =
     "examples/minimal/assets/notifications.json";
 
+/// Path of the minimal plugin's library, built on first use.
+fn minimal_plugin_path() -> PathBuf {
+    common::example_library("minimal").expect("minimal example plugin must build")
+}
+
 /// Just a test context
 struct ExExPluginManagerContext<Node: FullNodeComponents> {
     head: Head,
@@ -28,7 +45,7 @@ impl ExExPluginManagerContext<Adapter> {
     async fn new(rpc_request_recv: mpsc::UnboundedReceiver<RpcRequest>) -> eyre::Result<Self> {
         // Initialize a test Execution Extension context with all dependencies
         let (exex_ctx, exex_handle) = test_exex_context().await?;
-        // Save the current head of the chain to check the finished height against it later
+        // Save the current head of the chain to generate notifications from it
         let head = exex_ctx.head;
         // Initialize the Execution Extension plugin manager
         let plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_recv);
@@ -40,7 +57,84 @@ impl ExExPluginManagerContext<Adapter> {
     }
 }
 
+/// In-process plugin, which is replaced by the minimal plugin on reload
+#[derive(Debug)]
+struct OtherExEx;
+
+impl ExExPlugin for OtherExEx {
+    fn id(&self) -> &'static str {
+        "OtherExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<()>> + Send + 'b>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Response receiver of the minimal plugin load.
+type LoadResponse = oneshot::Receiver<Result<String, RpcError>>;
+
+/// In-process plugin, which loads the minimal plugin over RPC while handling a notification
+#[derive(Debug)]
+struct ReentrantExEx {
+    rpc_request_tx: mpsc::UnboundedSender<RpcRequest>,
+    /// Plugins listed by the plugin during its dispatch
+    listed: Arc<Mutex<Vec<String>>>,
+    loaded: Arc<Mutex<Option<LoadResponse>>>,
+}
+
+impl ExExPlugin for ReentrantExEx {
+    fn id(&self) -> &'static str {
+        "ReentrantExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let send = |req| self.rpc_request_tx.send(req).map_err(|_| eyre::eyre!("no manager"));
+
+            let (tx, rx) = oneshot::channel();
+            send(RpcRequest::LoadPlugin {
+                plugin_path: minimal_plugin_path(),
+                log_level: None,
+                config: None,
+                expected_id: None,
+                tx,
+            })?;
+            *self.loaded.lock().unwrap() = Some(rx);
+
+            // Read-only request is answered during dispatch
+            let (tx, rx) = oneshot::channel();
+            send(RpcRequest::ListPlugins { tx })?;
+            *self.listed.lock().unwrap() = rx.await?.map_err(|err| eyre::eyre!("{err:?}"))?;
+            Ok(())
+        })
+    }
+}
+
 /// Helper to check a dummy JSON minimal plugin storage
+/// Polls the Execution Extension until a response is received.
+async fn poll_until<T>(
+    plugin_exex_fut: &mut Pin<Box<dyn Future<Output = eyre::Result<()>> + Send>>,
+    mut rx: oneshot::Receiver<T>,
+) -> eyre::Result<T> {
+    loop {
+        plugin_exex_fut.poll_once().await?;
+        match rx.try_recv() {
+            Ok(res) => return Ok(res),
+            Err(oneshot::error::TryRecvError::Empty) => tokio::task::yield_now().await,
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
 fn is_file_empty<P: AsRef<Path>>(path: P) -> io::Result<bool> {
     let metadata = std::fs::metadata(&path)?;
     Ok(metadata.len() == 0)
@@ -57,21 +151,14 @@ async fn plugin_manager_receive_notifications() -> eyre::Result<()> {
     let mut exex_handle = std::mem::take(&mut ctx.exex_handle).unwrap();
 
     // Send a notification to the Execution Extension that the chain has been committed
-    let genesis = exex_handle.genesis.clone();
-    exex_handle
-        .send_notification_chain_committed(Chain::from_block(
-            genesis,
-            ExecutionOutcome::default(),
-            None,
-        ))
-        .await?;
+    let mut generator = NotificationGenerator::new(head.number);
+    exex_handle.notifications_tx.send(generator.commit(1)?).await?;
 
     exex_handle.assert_events_empty();
 
     ctx.plugin_exex_fut().poll_once().await?;
 
-    exex_handle
-        .assert_event_finished_height(BlockNumHash { number: head.number, hash: head.hash })?;
+    exex_handle.assert_event_finished_height(generator.tip().unwrap())?;
 
     Ok(())
 }
@@ -87,6 +174,7 @@ async fn should_exec_minimal_plugin() -> eyre::Result<()> {
         is_file_empty(MINIMAL_PLUGIN_DUMMY_STORAGE_PATH)?,
         "For test JSON storage of minimal plugin must be empty"
     );
+    let plugin_path = minimal_plugin_path();
 
     // Initialize a test Execution Extension context with all dependencies
     let mut ctx = ExExPluginManagerContext::new(rpc_request_rx).await?;
@@ -94,17 +182,23 @@ async fn should_exec_minimal_plugin() -> eyre::Result<()> {
     let mut exex_handle = std::mem::take(&mut ctx.exex_handle).unwrap();
 
     // Send a notification to the Execution Extension that the chain has been committed
-    let genesis = exex_handle.genesis.clone();
+    let mut generator = NotificationGenerator::new(head.number);
 
     let mut plugin_exex_fut = ctx.plugin_exex_fut();
 
     // Load a plugin
     let (tx, rx) = oneshot::channel();
-    let load_plugin_req = RpcRequest::LoadPlugin { plugin_path: MINIMAL_PLUGIN_PATH.into(), tx };
+    let load_plugin_req = RpcRequest::LoadPlugin {
+        plugin_path: plugin_path.clone(),
+        log_level: None,
+        config: None,
+        expected_id: None,
+        tx,
+    };
     let _ = rpc_request_tx.send(load_plugin_req);
-    // Poll the Execution Extension once to process incoming notifications or RPC requests
-    plugin_exex_fut.poll_once().await?;
-    assert_eq!(rx.await??.as_str(), "MinimalExEx");
+    // Poll the Execution Extension until the plugin is loaded in background
+    let id = poll_until(&mut plugin_exex_fut, rx).await?;
+    assert_eq!(id?.as_str(), "MinimalExEx");
 
     // Check a plugin list contains element
     let (tx, rx) = oneshot::channel();
@@ -114,24 +208,34 @@ async fn should_exec_minimal_plugin() -> eyre::Result<()> {
     plugin_exex_fut.poll_once().await?;
     assert_eq!(rx.await??, vec!["MinimalExEx"], "List of plugins must contain a plugin name");
 
+    // Check a plugin count tracks the load
+    let (tx, rx) = oneshot::channel();
+    let _ = rpc_request_tx.send(RpcRequest::PluginCount { tx });
+    // Poll the Execution Extension once to process incoming notifications or RPC requests
+    plugin_exex_fut.poll_once().await?;
+    assert_eq!(rx.await??, 1);
+
     // Load the same plugin - error
     let (tx, rx) = oneshot::channel();
-    let load_plugin_req = RpcRequest::LoadPlugin { plugin_path: MINIMAL_PLUGIN_PATH.into(), tx };
+    let load_plugin_req = RpcRequest::LoadPlugin {
+        plugin_path: plugin_path.clone(),
+        log_level: None,
+        config: None,
+        expected_id: None,
+        tx,
+    };
     let _ = rpc_request_tx.send(load_plugin_req);
     // Poll the Execution Extension once to process incoming notifications or RPC requests
     plugin_exex_fut.poll_once().await?;
     let err = rx.await?.err().expect("expect load already presented plugin error");
     assert_eq!(err.code(), INTERNAL_ERROR_CODE);
     dbg!(&err);
-    assert!(err.message().contains("failed to load exex plugin: Plugin with id: `\"MinimalExEx\"` is already presented on manager."));
+    assert!(err.message().contains(&format!(
+        "failed to load exex plugin: Plugin with id: `\"MinimalExEx\"` is already presented on manager, loaded from {}.",
+        plugin_path.display()
+    )));
 
-    exex_handle
-        .send_notification_chain_committed(Chain::from_block(
-            genesis,
-            ExecutionOutcome::default(),
-            None,
-        ))
-        .await?;
+    exex_handle.notifications_tx.send(generator.commit(1)?).await?;
 
     // Receive a notification
     // Check that the Execution Extension did not emit any events until we polled it
@@ -160,10 +264,647 @@ async fn should_exec_minimal_plugin() -> eyre::Result<()> {
     plugin_exex_fut.poll_once().await?;
     assert!(rx.await??.is_empty());
 
+    // Check a plugin count tracks the unload
+    let (tx, rx) = oneshot::channel();
+    let _ = rpc_request_tx.send(RpcRequest::PluginCount { tx });
+    // Poll the Execution Extension once to process incoming notifications or RPC requests
+    plugin_exex_fut.poll_once().await?;
+    assert_eq!(rx.await??, 0);
+
     // Check that the Execution Extension emitted a `FinishedHeight` event with the correct
     // height
-    exex_handle
-        .assert_event_finished_height(BlockNumHash { number: head.number, hash: head.hash })?;
+    exex_handle.assert_event_finished_height(generator.tip().unwrap())?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_reject_plugin_exceeding_max_size() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager =
+        ExExPluginManager::new(exex_ctx, rpc_request_rx).with_max_plugin_size(8);
+    assert!(plugin_manager.is_empty());
+
+    // A dummy "library" which is bigger than the configured limit
+    let plugin_path = std::env::temp_dir().join("exex_plugin_oversized.so");
+    std::fs::write(&plugin_path, [0u8; 16])?;
+
+    let err = unsafe { plugin_manager.load_plugin(&plugin_path, None) }
+        .await
+        .expect_err("expect oversized plugin error");
+    assert!(err.to_string().contains("exceeds the maximum of 8 bytes"));
+    assert_eq!(plugin_manager.len(), 0, "Rejected plugin must not be counted");
+
+    std::fs::remove_file(plugin_path)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_load_plugin_only_with_matching_expected_id() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    let plugin_path = minimal_plugin_path();
+
+    let err =
+        unsafe { plugin_manager.load_plugin_with_expected_id(&plugin_path, None, "OtherExEx") }
+            .await
+            .expect_err("expect expected id mismatch error");
+    assert!(err.to_string().contains("doesn't match expected `\"OtherExEx\"`"), "{err}");
+    assert!(plugin_manager.is_empty(), "Mismatching plugin must not be registered");
+
+    let id =
+        unsafe { plugin_manager.load_plugin_with_expected_id(&plugin_path, None, "MinimalExEx") }
+            .await?;
+    assert_eq!(id, "MinimalExEx");
+    assert_eq!(plugin_manager.plugins(), vec![id.clone()]);
+
+    plugin_manager.unload_plugin(&id)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_explain_why_file_is_not_loadable() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let dir = std::env::temp_dir().join("exex_plugins_not_loadable");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // ELF shared object header for another machine
+    let mut foreign = b"\x7fELF\x02\x01\x01".to_vec();
+    foreign.resize(16, 0);
+    foreign.extend_from_slice(&3u16.to_le_bytes());
+    let machine = if std::env::consts::ARCH == "riscv64" { 62u16 } else { 243 };
+    foreign.extend_from_slice(&machine.to_le_bytes());
+    foreign.resize(64, 0);
+
+    let files: [(&str, &[u8], &str); 3] = [
+        ("text.so", b"not a library", "Not a shared library"),
+        ("archive.so", b"!<arch>\n", "is a static archive"),
+        ("foreign.so", &foreign, "Architecture mismatch"),
+    ];
+    for (name, content, error) in files {
+        let path = dir.join(name);
+        std::fs::write(&path, content)?;
+
+        let err = unsafe { plugin_manager.load_plugin(&path, None) }
+            .await
+            .expect_err("expect not loadable plugin error");
+        assert!(err.to_string().contains(error), "{name}: {err}");
+    }
+
+    // Library without executable permission
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("non_executable.so");
+        std::fs::copy(minimal_plugin_path(), &path)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644))?;
+
+        let err = unsafe { plugin_manager.load_plugin(&path, None) }
+            .await
+            .expect_err("expect non executable plugin error");
+        assert!(err.to_string().contains("Permission denied"), "{err}");
+    }
+    assert!(plugin_manager.is_empty());
+
+    std::fs::remove_dir_all(dir)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_load_plugin_built_with_host_toolchain_in_strict_mode() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager =
+        ExExPluginManager::new(exex_ctx, rpc_request_rx).with_strict_build(true);
+
+    let id = unsafe { plugin_manager.load_plugin(minimal_plugin_path(), None) }.await?;
+    assert_eq!(id, "MinimalExEx");
+    plugin_manager.unload_plugin(&id)?;
+
+    Ok(())
+}
+
+#[test]
+fn should_reject_plugin_build_mismatching_host() {
+    let host = PluginBuild::host();
+    assert!(host.check(&host).is_ok());
+
+    let other_rustc = PluginBuild {
+        rustc_version: "rustc 1.0.0 (a59807616 2015-05-13)".to_owned(),
+        ..host.clone()
+    };
+    let err = other_rustc.check(&host).expect_err("expect rustc mismatch");
+    assert!(err.to_string().contains("rustc 1.0.0"));
+
+    let other_target = PluginBuild { target: "wasm32-unknown-unknown".to_owned(), ..host.clone() };
+    let err = other_target.check(&host).expect_err("expect target mismatch");
+    assert!(err.to_string().contains("wasm32-unknown-unknown"));
+}
+
+#[test]
+fn plugin_descriptor_should_keep_c_layout() {
+    // Layout is a part of the ABI contract, any change requires an ABI version bump
+    assert_eq!(EXEX_PLUGIN_ABI_VERSION, 1);
+    assert_eq!(std::mem::offset_of!(PluginDescriptor, abi_version), 0);
+    assert_eq!(std::mem::offset_of!(PluginDescriptor, flags), 4);
+    assert_eq!(std::mem::offset_of!(PluginDescriptor, plugin), 8);
+    assert_eq!(std::mem::size_of::<PluginDescriptor>(), 8 + std::mem::size_of::<usize>());
+}
+
+#[test]
+fn should_read_plugin_descriptor_before_trusting_plugin() -> eyre::Result<()> {
+    type ExExPluginDescriptorCreate = unsafe extern "C" fn() -> PluginDescriptor;
+
+    let lib = unsafe { libloading::Library::new(minimal_plugin_path()) }?;
+    let constructor =
+        unsafe { lib.get::<ExExPluginDescriptorCreate>(EXEX_PLUGIN_DESCRIPTOR_FN_NAME) }?;
+    let descriptor = unsafe { constructor() };
+    assert_eq!(descriptor.abi_version, EXEX_PLUGIN_ABI_VERSION);
+    assert_eq!(descriptor.flags, 0);
+
+    let plugin = unsafe { descriptor.into_plugin() }?;
+    assert_eq!(plugin.id(), "MinimalExEx");
+    drop(plugin);
+
+    // Descriptor of other ABI version or with unknown flags is refused
+    let mut descriptor = PluginDescriptor::new(Box::new(OtherExEx));
+    descriptor.abi_version += 1;
+    assert!(descriptor.check().is_err());
+    let mut descriptor = PluginDescriptor::new(Box::new(OtherExEx));
+    descriptor.flags = 1 << 31;
+    let err = unsafe { descriptor.into_plugin() }.err().expect("unknown flags must be refused");
+    assert!(err.to_string().contains("unknown flags"));
+
+    Ok(())
+}
+
+#[test]
+fn plugin_span_should_carry_log_level() {
+    subscriber::with_default(tracing_subscriber::registry(), || {
+        let span = plugin_span("MinimalExEx", Level::DEBUG);
+        let metadata = span.metadata().expect("span must be enabled");
+
+        assert_eq!(metadata.name(), "exex_plugin");
+        assert_eq!(*metadata.level(), Level::DEBUG);
+        assert!(metadata.fields().field("log_level").is_some());
+    });
+}
+
+/// Layer recording levels of events, and whether they're inside of a plugin's span.
+#[derive(Clone, Default)]
+struct RecordingLayer(Arc<Mutex<Vec<(Level, bool)>>>);
+
+impl<S> tracing_subscriber::Layer<S> for RecordingLayer
+where
+    S: subscriber::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn on_event(
+        &self,
+        event: &reth_tracing::tracing::Event<'_>,
+        cx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let in_plugin = cx.event_span(event).is_some();
+        self.0.lock().unwrap().push((*event.metadata().level(), in_plugin));
+    }
+}
+
+#[test]
+fn should_drop_plugin_events_below_its_level() {
+    use reth_tracing::tracing::{debug, info, warn};
+    use tracing_subscriber::{layer::SubscriberExt, Layer};
+
+    let recording = RecordingLayer::default();
+    let layer = recording.clone().with_filter(PluginLevelFilter);
+    subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+        plugin_span("MinimalExEx", Level::INFO).in_scope(|| {
+            debug!("dropped");
+            info!("recorded");
+            // nested spans of the plugin inherit its level
+            reth_tracing::tracing::info_span!("nested").in_scope(|| {
+                debug!("dropped");
+                warn!("recorded");
+            });
+        });
+        plugin_span("MinimalExEx", Level::DEBUG).in_scope(|| debug!("recorded"));
+        // events outside of plugins are left to other filters
+        debug!("recorded");
+    });
+
+    assert_eq!(
+        *recording.0.lock().unwrap(),
+        vec![(Level::INFO, true), (Level::WARN, true), (Level::DEBUG, true), (Level::DEBUG, false)]
+    );
+}
+
+#[tokio::test]
+async fn should_restore_plugins_from_state_file() -> eyre::Result<()> {
+    let state_file = std::env::temp_dir().join("exex_plugins_state.json");
+    let _ = std::fs::remove_file(&state_file);
+
+    // Loaded plugin is persisted
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager =
+        ExExPluginManager::new(exex_ctx, rpc_request_rx).with_state_file(&state_file);
+    unsafe { plugin_manager.load_plugin(minimal_plugin_path(), Some(Level::DEBUG)) }.await?;
+
+    let state = ManagerState::read(&state_file)?.expect("state file must be written");
+    assert_eq!(
+        state.plugins,
+        vec![PluginState {
+            path: minimal_plugin_path(),
+            log_level: Some("DEBUG".into()),
+            priority: 0,
+            config: None,
+            chains: None,
+        }]
+    );
+
+    // Unload all on shutdown keeps the state
+    plugin_manager.unload_all();
+    assert_eq!(ManagerState::read(&state_file)?, Some(state));
+
+    // Fresh manager restores the plugin
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager =
+        ExExPluginManager::new(exex_ctx, rpc_request_rx).with_state_file(&state_file);
+    let restored = unsafe { plugin_manager.load_from_state_file() }.await;
+    assert_eq!(restored, vec!["MinimalExEx"]);
+    assert_eq!(plugin_manager.plugins(), vec!["MinimalExEx"]);
+
+    // Explicit unload is persisted
+    plugin_manager.unload_plugin("MinimalExEx")?;
+    assert_eq!(ManagerState::read(&state_file)?, Some(ManagerState::default()));
+
+    std::fs::remove_file(state_file)?;
+
+    Ok(())
+}
+
+#[test]
+fn should_order_plugins_load_by_priority() -> eyre::Result<()> {
+    let plugin = |path: &str, priority| PluginState {
+        path: path.into(),
+        log_level: None,
+        priority,
+        config: None,
+        chains: None,
+    };
+    let state: ManagerState = serde_json::from_str(
+        r#"{"plugins": [
+            {"path": "c.so", "log_level": null, "priority": 10},
+            {"path": "b.so", "log_level": null},
+            {"path": "a.so", "log_level": null, "priority": 10},
+            {"path": "d.so", "log_level": null, "priority": -1}
+        ]}"#,
+    )?;
+
+    assert_eq!(
+        state.load_order(),
+        vec![&plugin("d.so", -1), &plugin("b.so", 0), &plugin("a.so", 10), &plugin("c.so", 10)]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_converge_plugins_to_manifest() -> eyre::Result<()> {
+    let manifest_file = std::env::temp_dir().join("exex_plugins_manifest.json");
+    let write_manifest = |log_levels: &[Option<&str>]| {
+        let plugins = log_levels
+            .iter()
+            .map(|log_level| PluginState {
+                path: minimal_plugin_path(),
+                log_level: log_level.map(str::to_owned),
+                priority: 0,
+                config: None,
+                chains: None,
+            })
+            .collect();
+        ManagerState { plugins }.write(&manifest_file)
+    };
+    let report = |action| {
+        vec![ManifestReport { id: "MinimalExEx".to_owned(), path: minimal_plugin_path(), action }]
+    };
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    unsafe { plugin_manager.load_plugin(minimal_plugin_path(), None) }.await?;
+
+    // Same plugin with other log level
+    write_manifest(&[Some("DEBUG")])?;
+    let reports = unsafe { plugin_manager.apply_manifest(&manifest_file) }.await?;
+    assert_eq!(reports, report(ManifestAction::Reloaded));
+    assert_eq!(plugin_manager.state().plugins[0].log_level.as_deref(), Some("DEBUG"));
+
+    let reports = unsafe { plugin_manager.apply_manifest(&manifest_file) }.await?;
+    assert_eq!(reports, report(ManifestAction::Unchanged));
+
+    // Duplicate plugin fails the whole manifest, keeping loaded plugins
+    write_manifest(&[None, None])?;
+    assert!(unsafe { plugin_manager.apply_manifest(&manifest_file) }.await.is_err());
+    assert_eq!(plugin_manager.state().plugins[0].log_level.as_deref(), Some("DEBUG"));
+
+    write_manifest(&[])?;
+    let reports = unsafe { plugin_manager.apply_manifest(&manifest_file) }.await?;
+    assert_eq!(reports, report(ManifestAction::Removed));
+    assert!(plugin_manager.is_empty());
+
+    write_manifest(&[None])?;
+    let reports = unsafe { plugin_manager.apply_manifest(&manifest_file) }.await?;
+    assert_eq!(reports, report(ManifestAction::Added));
+    assert_eq!(plugin_manager.plugins(), vec!["MinimalExEx"]);
+
+    plugin_manager.unload_plugin("MinimalExEx")?;
+    std::fs::remove_file(manifest_file)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_apply_manifest_on_reload_trigger() -> eyre::Result<()> {
+    let manifest_file = std::env::temp_dir().join("exex_plugins_reload_manifest.json");
+    let plugins = vec![PluginState {
+        path: minimal_plugin_path(),
+        log_level: None,
+        priority: 0,
+        config: None,
+        chains: None,
+    }];
+    ManagerState { plugins }.write(&manifest_file)?;
+
+    let (rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (reload_tx, reload_rx) = mpsc::channel(1);
+    let mut ctx = ExExPluginManagerContext::new(rpc_request_rx).await?;
+    let _exex_handle = std::mem::take(&mut ctx.exex_handle);
+    ctx.plugin_manager =
+        ctx.plugin_manager.with_reload_trigger(reload_rx).with_reload_manifest(&manifest_file);
+    let mut plugin_exex_fut = ctx.plugin_exex_fut();
+
+    // Simulate a signal, which triggers the manifest to be applied
+    reload_tx.send(()).await?;
+    let mut plugins = Vec::new();
+    for _ in 0..100 {
+        let (tx, rx) = oneshot::channel();
+        let _ = rpc_request_tx.send(RpcRequest::ListPlugins { tx });
+        plugins = poll_until(&mut plugin_exex_fut, rx).await??;
+        if !plugins.is_empty() {
+            break;
+        }
+    }
+    assert_eq!(plugins, vec!["MinimalExEx"], "Reload must apply the manifest");
+
+    let (tx, rx) = oneshot::channel();
+    let _ = rpc_request_tx.send(RpcRequest::UnloadPlugin { id: "MinimalExEx".to_owned(), tx });
+    poll_until(&mut plugin_exex_fut, rx).await??;
+    std::fs::remove_file(manifest_file)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_defer_plugin_load_requested_during_dispatch() -> eyre::Result<()> {
+    let (rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let mut ctx = ExExPluginManagerContext::new(rpc_request_rx).await?;
+    let exex_handle = std::mem::take(&mut ctx.exex_handle).unwrap();
+    let listed = Arc::new(Mutex::new(Vec::new()));
+    let loaded = Arc::new(Mutex::new(None));
+    let plugin = ReentrantExEx {
+        rpc_request_tx: rpc_request_tx.clone(),
+        listed: listed.clone(),
+        loaded: loaded.clone(),
+    };
+    ctx.plugin_manager.register_plugin(Box::new(plugin)).await?;
+    let mut generator = NotificationGenerator::new(ctx.head.number);
+    let mut plugin_exex_fut = ctx.plugin_exex_fut();
+
+    exex_handle.notifications_tx.send(generator.commit(1)?).await?;
+    plugin_exex_fut.poll_once().await?;
+
+    // The plugin set didn't change during dispatch
+    assert_eq!(*listed.lock().unwrap(), vec!["ReentrantExEx"]);
+
+    // Deferred load is processed after dispatch
+    let rx = loaded.lock().unwrap().take().expect("load must be requested");
+    let id = poll_until(&mut plugin_exex_fut, rx).await??;
+    assert_eq!(id, "MinimalExEx");
+
+    let (tx, rx) = oneshot::channel();
+    let _ = rpc_request_tx.send(RpcRequest::UnloadPlugin { id, tx });
+    poll_until(&mut plugin_exex_fut, rx).await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_reload_plugin_only_with_matching_id() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    // Matching id - reloaded from the path it was loaded from
+    unsafe { plugin_manager.load_plugin(minimal_plugin_path(), None) }.await?;
+    let id = unsafe { plugin_manager.reload_plugin("MinimalExEx", None, false) }.await?;
+    assert_eq!(id, "MinimalExEx");
+    assert_eq!(plugin_manager.plugins(), vec!["MinimalExEx"]);
+    plugin_manager.unload_plugin("MinimalExEx")?;
+
+    // Mismatching id - old plugin is kept
+    plugin_manager.register_plugin(Box::new(OtherExEx)).await?;
+    let err =
+        unsafe { plugin_manager.reload_plugin("OtherExEx", Some(minimal_plugin_path()), false) }
+            .await
+            .expect_err("expect mismatching id error");
+    assert!(err.to_string().contains("doesn't match"));
+    assert_eq!(plugin_manager.plugins(), vec!["OtherExEx"]);
+
+    // Mismatching id is explicitly allowed
+    let id =
+        unsafe { plugin_manager.reload_plugin("OtherExEx", Some(minimal_plugin_path()), true) }
+            .await?;
+    assert_eq!(id, "MinimalExEx");
+    assert_eq!(plugin_manager.plugins(), vec!["MinimalExEx"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_discover_plugins_in_dir() -> eyre::Result<()> {
+    let dir = std::env::temp_dir().join("exex_plugins_discovery");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    let ext = std::env::consts::DLL_EXTENSION;
+    let good = dir.join(format!("libminimal.{ext}"));
+    let bad = dir.join(format!("libbad.{ext}"));
+    std::fs::copy(minimal_plugin_path(), &good)?;
+    std::fs::write(&bad, "not a library")?;
+    std::fs::write(dir.join("README.md"), "not a plugin")?;
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let discovered = unsafe { plugin_manager.discover_plugins(&dir) }?;
+    assert_eq!(discovered.len(), 2);
+
+    let bad_plugin = &discovered[0];
+    assert_eq!(bad_plugin.path, bad);
+    assert!(!bad_plugin.loadable);
+    assert!(bad_plugin.id.is_none());
+    assert!(bad_plugin.error.is_some());
+
+    let good_plugin = &discovered[1];
+    assert_eq!(good_plugin.path, good);
+    assert!(good_plugin.loadable);
+    assert_eq!(good_plugin.id.as_deref(), Some("MinimalExEx"));
+    assert!(good_plugin.version.is_some());
+    assert!(good_plugin.error.is_none());
+
+    // Nothing is registered on manager
+    assert!(plugin_manager.is_empty());
+
+    std::fs::remove_dir_all(dir)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_return_plugin_library_info() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let plugin_path = minimal_plugin_path();
+    unsafe { plugin_manager.load_plugin(&plugin_path, None) }.await?;
+    let info = plugin_manager.plugin_info("MinimalExEx")?;
+    assert_eq!(info.path.as_deref(), Some(plugin_path.as_path()));
+    assert_eq!(info.canonical_path, Some(std::fs::canonicalize(&plugin_path)?));
+    assert!(info.modified.is_some_and(|modified| modified > 0));
+
+    // In-process plugin is not backed by a library
+    plugin_manager.register_plugin(Box::new(OtherExEx)).await?;
+    let info = plugin_manager.plugin_info("OtherExEx")?;
+    assert!(info.canonical_path.is_none());
+    assert!(info.modified.is_none());
+
+    assert!(plugin_manager.plugin_info("UnknownExEx").is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_collect_orphaned_temp_files() -> eyre::Result<()> {
+    let dir = std::env::temp_dir().join("exex_plugins_gc");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    let in_use = dir.join(format!("libminimal.{}", std::env::consts::DLL_EXTENSION));
+    let orphaned = dir.join("libleaked.download");
+    std::fs::copy(minimal_plugin_path(), &in_use)?;
+    std::fs::write(&orphaned, "leaked by a crashed load")?;
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    assert!(plugin_manager.gc_temp_files().is_err(), "Temp directory isn't set");
+
+    let mut plugin_manager = plugin_manager.with_temp_dir(&dir);
+    unsafe { plugin_manager.load_plugin(&in_use, None) }.await?;
+
+    assert_eq!(plugin_manager.gc_temp_files()?, 1);
+    assert!(!orphaned.exists());
+    assert!(in_use.exists(), "Library of the loaded plugin is retained");
+
+    // Library is collected once the plugin is unloaded
+    plugin_manager.unload_plugin("MinimalExEx")?;
+    assert_eq!(plugin_manager.gc_temp_files()?, 1);
+    assert!(!in_use.exists());
+
+    std::fs::remove_dir_all(dir)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_suffix_colliding_plugin_ids() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager =
+        ExExPluginManager::new(exex_ctx, rpc_request_rx).with_id_suffixes(true);
+
+    let plugin_path = minimal_plugin_path();
+    let first = unsafe { plugin_manager.load_plugin(&plugin_path, None) }.await?;
+    let second = unsafe { plugin_manager.load_plugin(&plugin_path, None) }.await?;
+    assert_eq!(first, "MinimalExEx");
+    assert_eq!(second, "MinimalExEx#2");
+    assert_eq!(plugin_manager.len(), 2);
+
+    let info = plugin_manager.plugin_info(&second)?;
+    assert_eq!(info.id, second);
+    assert_eq!(info.reported_id, "MinimalExEx");
+
+    // Effective id is used for lookups, so the freed suffix is taken again
+    plugin_manager.unload_plugin(&second)?;
+    assert!(plugin_manager.plugin_info(&first).is_ok());
+    let third = unsafe { plugin_manager.load_plugin(&plugin_path, None) }.await?;
+    assert_eq!(third, "MinimalExEx#2");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_load_manifest_plugins_only_on_their_chains() -> eyre::Result<()> {
+    let dir = std::env::temp_dir().join("exex_plugins_chains");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+    let ext = std::env::consts::DLL_EXTENSION;
+    let (matching, other) =
+        (dir.join(format!("libmatching.{ext}")), dir.join(format!("libother.{ext}")));
+    std::fs::copy(minimal_plugin_path(), &matching)?;
+    std::fs::copy(minimal_plugin_path(), &other)?;
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    let chain_id = plugin_manager.network().chain_id;
+
+    let entry = |path: &PathBuf, chains| PluginState {
+        path: path.clone(),
+        log_level: None,
+        priority: 0,
+        config: None,
+        chains: Some(chains),
+    };
+    let manifest_file = dir.join("manifest.json");
+    let plugins = vec![entry(&matching, vec![chain_id]), entry(&other, vec![chain_id + 1])];
+    ManagerState { plugins }.write(&manifest_file)?;
+
+    let reports = unsafe { plugin_manager.apply_manifest(&manifest_file) }.await?;
+    assert_eq!(
+        reports,
+        vec![ManifestReport {
+            id: "MinimalExEx".to_owned(),
+            path: matching.clone(),
+            action: ManifestAction::Added,
+        }]
+    );
+    assert_eq!(plugin_manager.plugin_info("MinimalExEx")?.path, Some(matching));
+
+    plugin_manager.unload_all();
+    std::fs::remove_dir_all(dir)?;
 
     Ok(())
 }
@@ -0,0 +1,53 @@
+//! Minimal plugin with gzip compressed output, selected by the plugin's config.
+
+use std::io::{BufRead, BufReader};
+
+use flate2::read::MultiGzDecoder;
+use reth::providers::{Chain, ExecutionOutcome};
+use reth_exex_plugin::{ExExNotification, ExExPluginManager};
+use reth_exex_test_utils::test_exex_context;
+use tokio::sync::mpsc;
+
+mod common;
+
+const MINIMAL_PLUGIN_GZ_STORAGE_PATH: &str = "examples/minimal/assets/notifications.jsonl.gz";
+
+#[tokio::test]
+async fn should_append_compressed_notifications() -> eyre::Result<()> {
+    let _ = std::fs::remove_file(MINIMAL_PLUGIN_GZ_STORAGE_PATH);
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    let plugin_path = common::example_library("minimal")?;
+    let config = serde_json::json!({ "output": "jsonl.gz" });
+    unsafe { plugin_manager.load_plugin_with_config(plugin_path, None, config) }.await?;
+
+    let chain = Chain::from_block(exex_handle.genesis.clone(), ExecutionOutcome::default(), None);
+    let number = exex_handle.genesis.number;
+    plugin_manager
+        .handle_notification(ExExNotification::ChainCommitted { new: chain.clone().into() })
+        .await?;
+    plugin_manager
+        .handle_notification(ExExNotification::ChainReverted { old: chain.into() })
+        .await?;
+
+    // Every notification is appended as a separate gzip member
+    let file = std::fs::File::open(MINIMAL_PLUGIN_GZ_STORAGE_PATH)?;
+    let lines = BufReader::new(MultiGzDecoder::new(file))
+        .lines()
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect::<eyre::Result<Vec<serde_json::Value>>>()?;
+    assert_eq!(
+        lines,
+        vec![
+            serde_json::json!({ "Commit": { "from": number, "to": number } }),
+            serde_json::json!({ "Revert": { "from": number, "to": number } }),
+        ]
+    );
+
+    plugin_manager.unload_plugin("MinimalExEx")?;
+    std::fs::remove_file(MINIMAL_PLUGIN_GZ_STORAGE_PATH)?;
+
+    Ok(())
+}
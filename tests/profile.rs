@@ -0,0 +1,68 @@
+use eyre::Result;
+use reth::primitives::B256;
+use reth_exex_plugin::{
+    BlockRange, ChainKind, FieldCase, NetworkLabel, NormalizedNotification, PluginErrorEvent,
+    SerializationProfile,
+};
+use serde_json::json;
+
+/// Sample plugin error event of a reorg.
+fn sample_event() -> PluginErrorEvent {
+    PluginErrorEvent {
+        network: NetworkLabel { chain_id: 1, name: "mainnet".to_string() },
+        id: "FailingExEx".to_string(),
+        notification: NormalizedNotification {
+            kind: ChainKind::Reorg,
+            reverted: Some(BlockRange { from: 4, to: 5 }),
+            committed: Some(BlockRange { from: 4, to: 6 }),
+        },
+        head_hash: B256::repeat_byte(0xab),
+        error: "not ready".to_string(),
+    }
+}
+
+#[test]
+fn should_serialize_snake_case_with_hashes_by_default() -> Result<()> {
+    let event = sample_event();
+    let value = SerializationProfile::default().to_value(&event)?;
+    assert_eq!(value, serde_json::to_value(&event)?);
+    assert_eq!(value["network"]["chain_id"], json!(1));
+    assert_eq!(value["head_hash"], json!(B256::repeat_byte(0xab)));
+
+    Ok(())
+}
+
+#[test]
+fn should_serialize_camel_case() -> Result<()> {
+    let profile = SerializationProfile::default().with_case(FieldCase::Camel);
+    let value = profile.to_value(&sample_event())?;
+    assert_eq!(
+        value,
+        json!({
+            "network": { "chainId": 1, "name": "mainnet" },
+            "id": "FailingExEx",
+            "notification": {
+                "kind": "reorg",
+                "reverted": { "from": 4, "to": 5 },
+                "committed": { "from": 4, "to": 6 },
+            },
+            "headHash": B256::repeat_byte(0xab),
+            "error": "not ready",
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn should_exclude_hashes() -> Result<()> {
+    for case in [FieldCase::Snake, FieldCase::Camel] {
+        let profile = SerializationProfile::default().with_case(case).with_hashes(false);
+        let value = profile.to_value(&sample_event())?;
+        let object = value.as_object().unwrap();
+        assert!(!object.contains_key("head_hash") && !object.contains_key("headHash"));
+        assert_eq!(object.len(), 4, "Only hashes are excluded");
+    }
+
+    Ok(())
+}
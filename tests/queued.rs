@@ -0,0 +1,234 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use eyre::Result;
+use reth::primitives::{BlockNumHash, B256};
+use reth_exex_plugin::{
+    testing::{NotificationGenerator, RecordingExExPlugin},
+    BlockRange, ChainKind, ExExNotification, ExExPlugin, NodeInfo, NormalizedNotification,
+    QueueFullPolicy, QueuedExExPlugin,
+};
+
+/// Plugin which waits for a gate on every notification, recording its hooks and unload.
+#[derive(Debug, Default)]
+struct GatedExEx {
+    gate: Arc<tokio::sync::Notify>,
+    started: AtomicBool,
+    unloaded: Arc<AtomicBool>,
+    handled: AtomicU64,
+    /// Blocks of fired hooks, with a number of notifications handled before them.
+    hooks: Mutex<Vec<(&'static str, u64, u64)>>,
+}
+
+impl ExExPlugin for GatedExEx {
+    fn id(&self) -> &'static str {
+        "GatedExEx"
+    }
+
+    fn on_unload(&mut self) -> Result<()> {
+        self.unloaded.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            self.started.store(true, Ordering::SeqCst);
+            self.gate.notified().await;
+            self.handled.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    }
+
+    fn on_tip(&self, tip: BlockNumHash) {
+        let handled = self.handled.load(Ordering::SeqCst);
+        self.hooks.lock().unwrap().push(("tip", tip.number, handled));
+    }
+
+    fn on_finalized(&self, block: BlockNumHash) {
+        let handled = self.handled.load(Ordering::SeqCst);
+        self.hooks.lock().unwrap().push(("finalized", block.number, handled));
+    }
+}
+
+/// Dispatches a commit notification to the plugin.
+async fn handle(plugin: &dyn ExExPlugin, generator: &mut NotificationGenerator) -> Result<()> {
+    let node_info = NodeInfo { chain_id: 1, head_number: 0, head_hash: B256::ZERO, sequence: 1 };
+    plugin.handle_notification(Arc::new(generator.commit(1)?), &node_info).await
+}
+
+/// Loads a gated plugin with a queue of a given capacity and waits until its first
+/// notification is taken from the queue.
+async fn gated(
+    capacity: usize,
+    policy: QueueFullPolicy,
+    generator: &mut NotificationGenerator,
+) -> Result<QueuedExExPlugin<GatedExEx>> {
+    let mut plugin = QueuedExExPlugin::new(GatedExEx::default(), capacity).with_policy(policy);
+    plugin.on_load().await?;
+
+    handle(&plugin, generator).await?;
+    while !plugin.inner().started.load(Ordering::SeqCst) {
+        tokio::task::yield_now().await;
+    }
+    Ok(plugin)
+}
+
+#[tokio::test]
+async fn should_handle_queued_notifications_in_order() -> Result<()> {
+    let recording = RecordingExExPlugin::new("RecordingExEx");
+    let mut plugin = QueuedExExPlugin::new(recording.clone(), 8);
+    plugin.on_load().await?;
+
+    let mut generator = NotificationGenerator::new(1);
+    for _ in 0..3 {
+        handle(&plugin, &mut generator).await?;
+    }
+    plugin.flush().await?;
+    assert_eq!(plugin.pending(), 0);
+
+    let committed = |number| NormalizedNotification {
+        kind: ChainKind::Commit,
+        reverted: None,
+        committed: Some(BlockRange { from: number, to: number }),
+    };
+    assert_eq!(recording.notifications(), vec![committed(1), committed(2), committed(3)]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_drop_notifications_on_full_queue() -> Result<()> {
+    let mut generator = NotificationGenerator::new(1);
+    let plugin = gated(1, QueueFullPolicy::Drop, &mut generator).await?;
+
+    // The first notification is being handled, the second one waits in the queue
+    handle(&plugin, &mut generator).await?;
+    let err = handle(&plugin, &mut generator).await.unwrap_err();
+    assert!(err.to_string().contains("queue is full"), "{err}");
+    assert_eq!((plugin.pending(), plugin.dropped()), (2, 1));
+
+    plugin.inner().gate.notify_one();
+    plugin.inner().gate.notify_one();
+    plugin.flush().await?;
+    assert_eq!(plugin.pending(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_block_dispatch_on_full_queue() -> Result<()> {
+    let mut generator = NotificationGenerator::new(1);
+    let plugin = gated(1, QueueFullPolicy::Block, &mut generator).await?;
+    handle(&plugin, &mut generator).await?;
+
+    let blocked = tokio::time::timeout(Duration::from_millis(50), handle(&plugin, &mut generator));
+    assert!(blocked.await.is_err(), "Dispatch must wait for a free slot");
+    assert_eq!((plugin.pending(), plugin.dropped()), (2, 0), "Dropped dispatch isn't counted");
+
+    plugin.inner().gate.notify_one();
+    tokio::time::timeout(Duration::from_secs(1), handle(&plugin, &mut generator)).await??;
+    plugin.inner().gate.notify_one();
+    plugin.inner().gate.notify_one();
+    plugin.flush().await?;
+    assert_eq!(plugin.pending(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_unload_wrapped_plugin_once_queue_is_drained() -> Result<()> {
+    let mut generator = NotificationGenerator::new(1);
+    let mut plugin = gated(4, QueueFullPolicy::Block, &mut generator).await?;
+    let (gate, unloaded) = (plugin.inner().gate.clone(), plugin.inner().unloaded.clone());
+    handle(&plugin, &mut generator).await?;
+
+    // The queue is closed, while its notifications are still handled
+    plugin.on_unload()?;
+    assert!(handle(&plugin, &mut generator).await.is_err());
+    gate.notify_one();
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while plugin.pending() > 1 {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await?;
+    assert!(!unloaded.load(Ordering::SeqCst));
+
+    // The wrapped plugin is unloaded once the queue is drained and the decorator is dropped
+    gate.notify_one();
+    tokio::time::timeout(Duration::from_secs(1), plugin.flush()).await??;
+    assert!(!unloaded.load(Ordering::SeqCst));
+    drop(plugin);
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while !unloaded.load(Ordering::SeqCst) {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_report_height_queue_is_processed_up_to() -> Result<()> {
+    let mut generator = NotificationGenerator::new(1);
+    let plugin = gated(4, QueueFullPolicy::Block, &mut generator).await?;
+    handle(&plugin, &mut generator).await?;
+
+    // The first block is being handled
+    let number = |height: Option<BlockNumHash>| height.map(|height| height.number);
+    assert_eq!(number(plugin.processed_height()), Some(0));
+
+    plugin.inner().gate.notify_one();
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while plugin.pending() > 1 {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await?;
+    assert_eq!(number(plugin.processed_height()), Some(1));
+
+    // A processed queue doesn't hold the height
+    plugin.inner().gate.notify_one();
+    plugin.flush().await?;
+    assert_eq!(plugin.processed_height(), None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_fire_hooks_after_queued_notifications() -> Result<()> {
+    let block = |number| BlockNumHash { number, hash: B256::ZERO };
+    for capacity in [4, 1] {
+        let mut generator = NotificationGenerator::new(1);
+        let plugin = gated(capacity, QueueFullPolicy::Block, &mut generator).await?;
+        handle(&plugin, &mut generator).await?;
+
+        // Hooks wait behind the queued notifications, even once they don't fit into the queue
+        plugin.on_tip(block(2));
+        plugin.on_finalized(block(1));
+        assert!(plugin.inner().hooks.lock().unwrap().is_empty(), "capacity {capacity}");
+
+        plugin.inner().gate.notify_one();
+        plugin.inner().gate.notify_one();
+        tokio::time::timeout(Duration::from_secs(1), plugin.flush()).await??;
+        assert_eq!(
+            *plugin.inner().hooks.lock().unwrap(),
+            vec![("tip", 2, 2), ("finalized", 1, 2)],
+            "capacity {capacity}"
+        );
+    }
+
+    Ok(())
+}
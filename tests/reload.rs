@@ -0,0 +1,558 @@
+//! Reload of minimal plugins, loaded from copies of the library.
+//!
+//! Runs in its own test binary, since the plugins' ids and schema versions are set by
+//! environment variables.
+
+use std::{
+    future::Future,
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use reth_exex_plugin::{
+    testing::NotificationGenerator, ExExPluginManager, ManagerState, ManifestAction,
+    ManifestReport, NormalizedNotification, PluginState, RpcRequest,
+};
+use reth_exex_test_utils::test_exex_context;
+use reth_tracing::{
+    tracing::{subscriber, Level},
+    tracing_subscriber,
+};
+use tokio::sync::{mpsc, oneshot};
+
+mod common;
+
+/// Writer of the captured logs.
+#[derive(Debug, Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl CapturedLogs {
+    /// Captures logs of a given future.
+    async fn capture<F: Future>(&self, fut: F) -> F::Output {
+        let writer = self.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = subscriber::set_default(subscriber);
+        fut.await
+    }
+
+    /// Returns `true` if any of the captured logs contains a given text.
+    fn contains(&self, text: &str) -> bool {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).contains(text)
+    }
+
+    /// Returns the maximum number of reloads in flight, logged on each reload start.
+    fn max_in_flight(&self) -> usize {
+        String::from_utf8_lossy(&self.0.lock().unwrap())
+            .split("in_flight=")
+            .skip(1)
+            .filter_map(|rest| rest.split_whitespace().next()?.parse().ok())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Serializes tests setting the environment variables, which are read by plugins on load.
+static ENV_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Copies the minimal plugin library into `count` files, each loaded under its own id.
+fn plugin_copies(name: &str, count: usize) -> eyre::Result<Vec<(String, PathBuf)>> {
+    let library = common::example_library("minimal")?;
+    let ext = std::env::consts::DLL_EXTENSION;
+    (0..count)
+        .map(|i| {
+            let path = std::env::temp_dir().join(format!("libminimal_{name}_{i}.{ext}"));
+            std::fs::copy(&library, &path)?;
+            Ok((format!("MinimalExEx{i}"), path))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn should_bound_number_of_concurrent_reloads() -> eyre::Result<()> {
+    let _env = ENV_LOCK.lock().await;
+    let plugins = plugin_copies("reload", 4)?;
+
+    for limit in [1, 2] {
+        let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+        let (exex_ctx, _exex_handle) = test_exex_context().await?;
+        let mut plugin_manager =
+            ExExPluginManager::new(exex_ctx, rpc_request_rx).with_reload_concurrency(limit);
+        for (id, path) in &plugins {
+            std::env::set_var("MINIMAL_EXEX_ID", id);
+            unsafe { plugin_manager.load_plugin(path, None) }.await?;
+        }
+
+        let logs = CapturedLogs::default();
+        let reloaded = logs.capture(unsafe { plugin_manager.reload_all() }).await;
+
+        let ids = plugins.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>();
+        let mut sorted = reloaded.clone();
+        sorted.sort();
+        assert_eq!(sorted, ids, "All plugins must be reloaded");
+        assert_eq!(logs.max_in_flight(), limit, "Reloads in flight must be bounded by the limit");
+
+        for id in reloaded {
+            plugin_manager.unload_plugin(&id)?;
+        }
+    }
+
+    for (_, path) in plugins {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_warn_on_schema_version_change() -> eyre::Result<()> {
+    let _env = ENV_LOCK.lock().await;
+    let (_, path) = plugin_copies("schema", 1)?.remove(0);
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    std::env::set_var("MINIMAL_EXEX_SCHEMA_VERSION", "1");
+    let id = unsafe { plugin_manager.load_plugin(&path, None) }.await?;
+    assert_eq!(plugin_manager.plugin_info(&id)?.schema_version, 1);
+
+    // Same schema version isn't reported
+    let logs = CapturedLogs::default();
+    logs.capture(unsafe { plugin_manager.reload_plugin(&id, None, false) }).await?;
+    assert!(!logs.contains("changed its schema version"));
+
+    std::env::set_var("MINIMAL_EXEX_SCHEMA_VERSION", "2");
+    let logs = CapturedLogs::default();
+    logs.capture(unsafe { plugin_manager.reload_plugin(&id, None, false) }).await?;
+    assert!(logs.contains("changed its schema version"));
+    assert!(logs.contains("old_schema_version=1 new_schema_version=2"));
+    assert_eq!(plugin_manager.plugin_info(&id)?.schema_version, 2);
+
+    plugin_manager.unload_plugin(&id)?;
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_dispatch_notifications_to_plugin_shadow() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let _env = ENV_LOCK.lock().await;
+    let [(id, path), (_, shadow_path)]: [_; 2] =
+        plugin_copies("shadow", 2)?.try_into().expect("two copies");
+    std::env::set_var("MINIMAL_EXEX_ID", &id);
+    unsafe { plugin_manager.load_plugin(&path, None) }.await?;
+    let shadow_id = unsafe { plugin_manager.shadow_load(&id, &shadow_path) }.await?;
+    assert_eq!(shadow_id, format!("{id}@shadow"));
+    assert_eq!(plugin_manager.plugins(), vec![id.clone()], "Shadow isn't a loaded plugin");
+
+    // Both plugins receive the notification, the shadow's info is tagged
+    let mut generator = NotificationGenerator::new(1);
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    let info = plugin_manager.plugin_info(&id)?;
+    assert_eq!((info.id, info.handled), (id.clone(), 1));
+    let shadow_info = plugin_manager.plugin_info(&shadow_id)?;
+    assert_eq!((shadow_info.id.as_str(), shadow_info.handled), (shadow_id.as_str(), 1));
+    assert_eq!(shadow_info.path.as_deref(), Some(shadow_path.as_path()));
+
+    // Shadow of mismatching id is refused
+    let (_, other_path) = plugin_copies("shadow_other", 1)?.remove(0);
+    let other_id = "OtherMinimalExEx";
+    std::env::set_var("MINIMAL_EXEX_ID", other_id);
+    unsafe { plugin_manager.load_plugin(&other_path, None) }.await?;
+    let err = unsafe { plugin_manager.shadow_load(other_id, &shadow_path) }
+        .await
+        .expect_err("expect mismatching id error");
+    assert!(err.to_string().contains("doesn't match"));
+
+    // Promoted shadow replaces the plugin
+    assert_eq!(plugin_manager.promote_shadow(&id)?, id);
+    let info = plugin_manager.plugin_info(&id)?;
+    assert_eq!(info.path.as_deref(), Some(shadow_path.as_path()));
+    assert!(plugin_manager.plugin_info(&shadow_id).is_err());
+    assert!(plugin_manager.promote_shadow(&id).is_err(), "Shadow is promoted once");
+
+    plugin_manager.unload_all();
+    for path in [path, shadow_path, other_path] {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_quarantine_diverged_plugin_shadow() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager =
+        ExExPluginManager::new(exex_ctx, rpc_request_rx).with_shadow_tolerance(1.0);
+
+    let _env = ENV_LOCK.lock().await;
+    let [(id, path), (_, shadow_path), (_, diverging_path)]: [_; 3] =
+        plugin_copies("divergence", 3)?.try_into().expect("three copies");
+    std::env::set_var("MINIMAL_EXEX_ID", &id);
+    std::env::set_var("MINIMAL_EXEX_RESULT_OFFSET", "0");
+    unsafe { plugin_manager.load_plugin(&path, None) }.await?;
+
+    // Results within the tolerance don't diverge
+    std::env::set_var("MINIMAL_EXEX_RESULT_OFFSET", "1");
+    unsafe { plugin_manager.shadow_load(&id, &shadow_path) }.await?;
+    let mut generator = NotificationGenerator::new(1);
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    assert_eq!(plugin_manager.shadow_divergence(&id)?, None);
+
+    // Shadow diverging beyond the tolerance is quarantined at the first diverging block
+    std::env::set_var("MINIMAL_EXEX_RESULT_OFFSET", "2");
+    unsafe { plugin_manager.shadow_load(&id, &diverging_path) }.await?;
+    let notification = generator.commit(1)?;
+    let block = notification.committed_chain().expect("commit").tip().number;
+    plugin_manager.handle_notification(notification).await?;
+    plugin_manager.handle_notification(generator.commit(1)?).await?;
+    std::env::remove_var("MINIMAL_EXEX_RESULT_OFFSET");
+
+    let divergence = plugin_manager.shadow_divergence(&id)?.expect("shadow diverged");
+    assert_eq!(divergence.block, block);
+    assert_eq!(divergence.production, Some(serde_json::json!({ "tip": block })));
+    assert_eq!(divergence.shadow, Some(serde_json::json!({ "tip": block + 2 })));
+    let err = plugin_manager.promote_shadow(&id).expect_err("expect diverged shadow error");
+    assert!(err.to_string().contains("diverged"));
+
+    plugin_manager.unload_all();
+    for path in [path, shadow_path, diverging_path] {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_not_miss_errors_of_plugin_loaded_and_subscribed_at_once() -> eyre::Result<()> {
+    let (rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, exex_handle) = test_exex_context().await?;
+    let manager = tokio::spawn(ExExPluginManager::new(exex_ctx, rpc_request_rx).run());
+
+    let _env = ENV_LOCK.lock().await;
+    let (id, path) = plugin_copies("subscribe", 1)?.remove(0);
+    std::env::set_var("MINIMAL_EXEX_ID", &id);
+    std::env::set_var("MINIMAL_EXEX_FAIL", "1");
+    let (tx, loaded) = oneshot::channel();
+    rpc_request_tx.send(RpcRequest::LoadAndSubscribe {
+        plugin_path: path.clone(),
+        log_level: None,
+        config: None,
+        tx,
+    })?;
+    // the load is started once the following request is answered
+    let (tx, ping) = oneshot::channel();
+    rpc_request_tx.send(RpcRequest::Ping { tx })?;
+    ping.await?.map_err(|err| eyre::eyre!("{err:?}"))?;
+
+    // The first notification arrives while the plugin may be still loading
+    let mut generator = NotificationGenerator::new(1);
+    let notification = generator.commit(1)?;
+    let expected = NormalizedNotification::from(&notification);
+    exex_handle.notifications_tx.send(notification).await?;
+
+    let (loaded_id, mut errors) = loaded.await?.map_err(|err| eyre::eyre!("{err:?}"))?;
+    std::env::remove_var("MINIMAL_EXEX_FAIL");
+    assert_eq!(loaded_id, id);
+    let event = tokio::time::timeout(Duration::from_secs(1), errors.recv()).await??;
+    assert_eq!((event.id, event.notification), (id, expected));
+
+    manager.abort();
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_restore_plugins_snapshot_onto_new_manager() -> eyre::Result<()> {
+    let _env = ENV_LOCK.lock().await;
+    let plugins = plugin_copies("snapshot", 3)?;
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    for (id, path) in &plugins {
+        std::env::set_var("MINIMAL_EXEX_ID", id);
+        unsafe { plugin_manager.load_plugin(path, Some(Level::DEBUG)) }.await?;
+    }
+    let mut snapshot = plugin_manager.snapshot();
+    snapshot.plugins[0].priority = -1;
+    snapshot.plugins[1].log_level = None;
+
+    // Libraries are kept open by the first manager, so the copies keep their ids
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut restored = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    let reports = unsafe { restored.restore(snapshot.clone()) }.await?;
+    assert_eq!(
+        reports.iter().map(|report| (report.id.as_str(), report.action)).collect::<Vec<_>>(),
+        plugins.iter().map(|(id, _)| (id.as_str(), ManifestAction::Added)).collect::<Vec<_>>()
+    );
+    let mut restored_snapshot = restored.snapshot();
+    restored_snapshot.plugins.sort_by(|a, b| a.path.cmp(&b.path));
+    assert_eq!(restored_snapshot, snapshot);
+
+    // Restoring the same snapshot again is a no-op
+    let reports = unsafe { restored.restore(snapshot) }.await?;
+    assert!(reports.iter().all(|report| report.action == ManifestAction::Unchanged));
+
+    restored.unload_all();
+    plugin_manager.unload_all();
+    for (_, path) in plugins {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_check_plugin_dependencies_without_loading() -> eyre::Result<()> {
+    let _env = ENV_LOCK.lock().await;
+    let plugins = plugin_copies("dependencies", 1)?;
+    let dependent = common::fixture_library("dependent")?;
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let check = unsafe { plugin_manager.check_dependencies(&dependent) }?;
+    assert_eq!(check.id, "DependentExEx");
+    assert!(check.loaded.is_empty());
+    assert_eq!(check.missing, vec!["MinimalExEx".to_owned()]);
+    assert!(!check.is_satisfied());
+
+    let (_, path) = &plugins[0];
+    std::env::set_var("MINIMAL_EXEX_ID", "MinimalExEx");
+    unsafe { plugin_manager.load_plugin(path, None) }.await?;
+
+    let check = unsafe { plugin_manager.check_dependencies(&dependent) }?;
+    assert_eq!(check.loaded, vec!["MinimalExEx".to_owned()]);
+    assert!(check.missing.is_empty());
+    assert!(check.is_satisfied());
+    assert_eq!(plugin_manager.plugins(), vec!["MinimalExEx".to_owned()], "Candidate isn't loaded");
+
+    // Library without metadata can't be checked
+    let err = unsafe { plugin_manager.check_dependencies(path) }.expect_err("expect no metadata");
+    assert!(err.to_string().contains("doesn't export its metadata"), "{err:?}");
+
+    plugin_manager.unload_all();
+    for (_, path) in plugins {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_suffix_colliding_plugin_ids_of_manifest() -> eyre::Result<()> {
+    let _env = ENV_LOCK.lock().await;
+    let plugins = plugin_copies("manifest_suffixes", 3)?;
+    std::env::set_var("MINIMAL_EXEX_ID", "MinimalExEx");
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager =
+        ExExPluginManager::new(exex_ctx, rpc_request_rx).with_id_suffixes(true);
+    unsafe { plugin_manager.load_plugin(&plugins[0].1, None) }.await?;
+
+    // Entries collide with the loaded plugin and with each other
+    let manifest_file = std::env::temp_dir().join("exex_plugins_manifest_suffixes.json");
+    let entry = |path: &PathBuf| PluginState {
+        path: path.clone(),
+        log_level: None,
+        priority: 0,
+        config: None,
+        chains: None,
+    };
+    ManagerState { plugins: plugins.iter().map(|(_, path)| entry(path)).collect() }
+        .write(&manifest_file)?;
+    let actions = |reports: Vec<ManifestReport>| {
+        reports.into_iter().map(|report| (report.id, report.action)).collect::<Vec<_>>()
+    };
+    let reports = unsafe { plugin_manager.apply_manifest(&manifest_file) }.await?;
+    assert_eq!(
+        actions(reports),
+        vec![
+            ("MinimalExEx".to_owned(), ManifestAction::Unchanged),
+            ("MinimalExEx#2".to_owned(), ManifestAction::Added),
+            ("MinimalExEx#3".to_owned(), ManifestAction::Added),
+        ]
+    );
+
+    // Reloaded entries keep their suffixed ids
+    ManagerState {
+        plugins: plugins
+            .iter()
+            .map(|(_, path)| PluginState { priority: 1, ..entry(path) })
+            .collect(),
+    }
+    .write(&manifest_file)?;
+    let reports = unsafe { plugin_manager.apply_manifest(&manifest_file) }.await?;
+    assert_eq!(
+        actions(reports),
+        vec![
+            ("MinimalExEx".to_owned(), ManifestAction::Reloaded),
+            ("MinimalExEx#2".to_owned(), ManifestAction::Reloaded),
+            ("MinimalExEx#3".to_owned(), ManifestAction::Reloaded),
+        ]
+    );
+    assert_eq!(plugin_manager.plugin_info("MinimalExEx#3")?.path, Some(plugins[2].1.clone()));
+
+    plugin_manager.unload_all();
+    std::fs::remove_file(manifest_file)?;
+    for (_, path) in plugins {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_refuse_manifest_plugin_requiring_disabled_node_rpc_namespace() -> eyre::Result<()> {
+    let _env = ENV_LOCK.lock().await;
+    let plugins = plugin_copies("manifest_namespaces", 1)?;
+    let dependent = common::fixture_library("dependent")?;
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (mut exex_ctx, _exex_handle) = test_exex_context().await?;
+    // HTTP serves the standard namespaces, i.e. `eth`, `net` and `web3`
+    exex_ctx.config.rpc.http = true;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let manifest_file = std::env::temp_dir().join("exex_plugins_manifest_namespaces.json");
+    let write_manifest = |paths: &[&PathBuf]| {
+        let plugins = paths
+            .iter()
+            .map(|path| PluginState {
+                path: path.to_path_buf(),
+                log_level: None,
+                priority: 0,
+                config: None,
+                chains: None,
+            })
+            .collect();
+        ManagerState { plugins }.write(&manifest_file)
+    };
+
+    let (_, path) = &plugins[0];
+    std::env::set_var("MINIMAL_EXEX_ID", "MinimalExEx");
+    write_manifest(&[path])?;
+    unsafe { plugin_manager.apply_manifest(&manifest_file) }.await?;
+
+    // The whole manifest fails, keeping loaded plugins
+    write_manifest(&[path, &dependent])?;
+    let err = unsafe { plugin_manager.apply_manifest(&manifest_file) }
+        .await
+        .expect_err("expect disabled namespace error");
+    assert!(format!("{err:?}").contains(r#"["debug"]"#), "{err:?}");
+    assert_eq!(plugin_manager.plugins(), vec!["MinimalExEx".to_owned()]);
+
+    plugin_manager.unload_all();
+    std::fs::remove_file(manifest_file)?;
+    for (_, path) in plugins {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn should_keep_libraries_open_while_loading_dispatching_and_unloading_concurrently(
+) -> eyre::Result<()> {
+    let _env = ENV_LOCK.lock().await;
+    let plugins = plugin_copies("concurrent", 3)?;
+
+    let (rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, exex_handle) = test_exex_context().await?;
+    let manager = tokio::spawn(ExExPluginManager::new(exex_ctx, rpc_request_rx).run());
+
+    let mut libraries = Vec::new();
+    for (id, path) in &plugins {
+        std::env::set_var("MINIMAL_EXEX_ID", id);
+        let (tx, rx) = oneshot::channel();
+        let plugin_path = path.clone();
+        let req = RpcRequest::LoadPlugin {
+            plugin_path,
+            log_level: None,
+            config: None,
+            expected_id: None,
+            tx,
+        };
+        rpc_request_tx.send(req)?;
+        rx.await?.map_err(|err| eyre::eyre!("{err:?}"))?;
+        // keeps the id of the copy, which is read on the library's first load
+        libraries.push(unsafe { libloading::Library::new(path) }?);
+    }
+
+    let notifications_tx = exex_handle.notifications_tx.clone();
+    let dispatch = tokio::spawn(async move {
+        for notification in NotificationGenerator::new(1).take(50) {
+            notifications_tx.send(notification).await?;
+            tokio::task::yield_now().await;
+        }
+        eyre::Ok(())
+    });
+    let reloads = plugins.iter().cloned().map(|(id, plugin_path)| {
+        let rpc_request_tx = rpc_request_tx.clone();
+        tokio::spawn(async move {
+            for _ in 0..10 {
+                let (tx, rx) = oneshot::channel();
+                rpc_request_tx.send(RpcRequest::UnloadPlugin { id: id.clone(), tx })?;
+                rx.await?.map_err(|err| eyre::eyre!("{err:?}"))?;
+
+                let (tx, rx) = oneshot::channel();
+                let plugin_path = plugin_path.clone();
+                let req = RpcRequest::LoadPlugin {
+                    plugin_path,
+                    log_level: None,
+                    config: None,
+                    expected_id: Some(id.clone()),
+                    tx,
+                };
+                rpc_request_tx.send(req)?;
+                rx.await?.map_err(|err| eyre::eyre!("{err:?}"))?;
+            }
+            eyre::Ok(())
+        })
+    });
+    for reload in reloads.collect::<Vec<_>>() {
+        reload.await??;
+    }
+    dispatch.await??;
+
+    let (tx, rx) = oneshot::channel();
+    rpc_request_tx.send(RpcRequest::ListPlugins { tx })?;
+    let mut loaded = rx.await?.map_err(|err| eyre::eyre!("{err:?}"))?;
+    loaded.sort();
+    assert_eq!(loaded, plugins.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>());
+    assert!(!manager.is_finished());
+
+    manager.abort();
+    drop(libraries);
+    for (_, path) in plugins {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
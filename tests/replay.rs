@@ -0,0 +1,92 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use eyre::Result;
+use reth_exex_plugin::{
+    testing::{replay_from_file, synthetic_notification},
+    BlockRange, ChainKind, ExExNotification, ExExPlugin, NodeInfo, NormalizedNotification,
+};
+
+const REPLAY_FIXTURE_PATH: &str = "tests/fixtures/replay.jsonl";
+
+/// Plugin which records normalized notifications with the head block number after each.
+#[derive(Debug, Default)]
+struct RecordingExEx {
+    recorded: Arc<Mutex<Vec<(NormalizedNotification, u64)>>>,
+}
+
+impl ExExPlugin for RecordingExEx {
+    fn id(&self) -> &'static str {
+        "RecordingExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        notification: Arc<ExExNotification>,
+        node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            let normalized = NormalizedNotification::from(notification.as_ref());
+            self.recorded.lock().unwrap().push((normalized, node_info.head_number));
+            Ok(())
+        })
+    }
+}
+
+/// Creates a normalized notification of a given kind with given ranges.
+fn normalized(
+    kind: ChainKind,
+    reverted: Option<BlockRange>,
+    committed: Option<BlockRange>,
+) -> NormalizedNotification {
+    NormalizedNotification { kind, reverted, committed }
+}
+
+#[tokio::test]
+async fn should_replay_recorded_notifications_in_order() -> Result<()> {
+    let plugin = RecordingExEx::default();
+    let replayed = replay_from_file(REPLAY_FIXTURE_PATH, &plugin).await?;
+    assert_eq!(replayed, 4);
+
+    let range = |from, to| Some(BlockRange { from, to });
+    assert_eq!(
+        *plugin.recorded.lock().unwrap(),
+        vec![
+            (normalized(ChainKind::Commit, None, range(0, 2)), 2),
+            (normalized(ChainKind::Commit, None, range(3, 3)), 3),
+            (normalized(ChainKind::Reorg, range(2, 3), range(2, 4)), 4),
+            (normalized(ChainKind::Revert, range(4, 4), None), 3),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn should_reject_invalid_recorded_notifications() {
+    let empty = normalized(ChainKind::Commit, None, None);
+    assert!(synthetic_notification(&empty).is_err());
+
+    let inverted = normalized(ChainKind::Commit, None, Some(BlockRange { from: 2, to: 1 }));
+    assert!(synthetic_notification(&inverted).is_err());
+
+    let mismatched = normalized(ChainKind::Revert, None, Some(BlockRange { from: 1, to: 2 }));
+    assert!(synthetic_notification(&mismatched).is_err());
+}
+
+#[test]
+fn should_serialize_chain_kind() -> Result<()> {
+    assert_eq!(serde_json::to_string(&ChainKind::ALL)?, r#"["commit","revert","reorg"]"#);
+    assert_eq!(serde_json::from_str::<ChainKind>(r#""reorg""#)?, ChainKind::Reorg);
+    assert!(serde_json::from_str::<ChainKind>(r#""Reorg""#).is_err());
+
+    let revert = normalized(ChainKind::Revert, Some(BlockRange { from: 4, to: 4 }), None);
+    let json = r#"{"kind":"revert","reverted":{"from":4,"to":4},"committed":null}"#;
+    assert_eq!(serde_json::to_string(&revert)?, json);
+    assert_eq!(serde_json::from_str::<NormalizedNotification>(json)?, revert);
+
+    Ok(())
+}
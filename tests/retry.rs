@@ -0,0 +1,128 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use eyre::Result;
+use reth::primitives::B256;
+use reth_exex_plugin::{
+    testing::synthetic_chain, BlockRange, ExExNotification, ExExPlugin, NodeInfo, RetryExExPlugin,
+    RetryPolicy,
+};
+
+/// Plugin which fails first `failures` notifications with a given error.
+#[derive(Debug)]
+struct FlakyExEx {
+    failures: u32,
+    attempts: AtomicU32,
+    error: fn() -> eyre::Report,
+}
+
+impl ExExPlugin for FlakyExEx {
+    fn id(&self) -> &'static str {
+        "FlakyExEx"
+    }
+
+    fn handle_notification<'a: 'b, 'b>(
+        &'a self,
+        _notification: Arc<ExExNotification>,
+        _node_info: &'a NodeInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>> {
+        Box::pin(async move {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err((self.error)());
+            }
+            Ok(())
+        })
+    }
+}
+
+fn flaky(failures: u32, error: fn() -> eyre::Report) -> RetryExExPlugin<FlakyExEx> {
+    RetryExExPlugin::new(FlakyExEx { failures, attempts: AtomicU32::new(0), error }).with_policy(
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_millis(80),
+        },
+    )
+}
+
+fn timed_out() -> eyre::Report {
+    std::io::Error::from(std::io::ErrorKind::TimedOut).into()
+}
+
+/// Dispatches a commit notification to the plugin.
+async fn handle(plugin: &dyn ExExPlugin) -> Result<()> {
+    let node_info = NodeInfo { chain_id: 1, head_number: 0, head_hash: B256::ZERO, sequence: 1 };
+    let committed = synthetic_chain(BlockRange { from: 1, to: 1 })?;
+    let notification = ExExNotification::ChainCommitted { new: committed.into() };
+    plugin.handle_notification(Arc::new(notification), &node_info).await
+}
+
+/// Advances the paused clock by a given number of milliseconds, returning a number of attempts
+/// made by then.
+async fn advance(plugin: &RetryExExPlugin<FlakyExEx>, millis: u64) -> u32 {
+    tokio::time::advance(Duration::from_millis(millis)).await;
+    tokio::task::yield_now().await;
+    plugin.inner().attempts.load(Ordering::SeqCst)
+}
+
+#[tokio::test(start_paused = true)]
+async fn should_retry_transient_failures_with_backoff() -> Result<()> {
+    let plugin = Arc::new(flaky(2, timed_out));
+    let handled = tokio::spawn({
+        let plugin = plugin.clone();
+        async move { handle(plugin.as_ref()).await }
+    });
+
+    // Two retries with doubled backoff, capped by the max one
+    assert_eq!(advance(&plugin, 0).await, 1);
+    assert_eq!(advance(&plugin, 49).await, 1);
+    assert_eq!(advance(&plugin, 1).await, 2);
+    assert_eq!(advance(&plugin, 79).await, 2);
+    assert_eq!(advance(&plugin, 1).await, 3);
+    handled.await??;
+
+    // Retries are exhausted
+    let plugin = flaky(u32::MAX, timed_out);
+    assert!(handle(&plugin).await.is_err());
+    assert_eq!(plugin.inner().attempts.load(Ordering::SeqCst), 4);
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn should_not_retry_non_retryable_failures() -> Result<()> {
+    let plugin = flaky(1, || eyre::eyre!("invalid block"));
+    assert!(handle(&plugin).await.is_err());
+    assert_eq!(plugin.inner().attempts.load(Ordering::SeqCst), 1);
+
+    // Retried with a custom predicate
+    let plugin = flaky(1, || eyre::eyre!("invalid block"))
+        .with_retryable(|err| err.to_string().contains("invalid"));
+    handle(&plugin).await?;
+    assert_eq!(plugin.inner().attempts.load(Ordering::SeqCst), 2);
+
+    Ok(())
+}
+
+#[test]
+fn should_compute_bounded_retry_backoff() {
+    let policy = RetryPolicy {
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(300),
+    };
+
+    assert_eq!(policy.backoff(0), Some(Duration::from_millis(100)));
+    assert_eq!(policy.backoff(1), Some(Duration::from_millis(200)));
+    // Capped by the max backoff
+    assert_eq!(policy.backoff(2), Some(Duration::from_millis(300)));
+    // Exhausted
+    assert_eq!(policy.backoff(3), None);
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use reth_exex_plugin::sender::Sender;
+use tokio::sync::mpsc;
+
+#[tokio::test]
+async fn should_await_capacity_in_bounded_mode() -> eyre::Result<()> {
+    let (tx, mut rx) = mpsc::channel(1);
+    let sender = Sender::bounded(tx);
+
+    sender.send_async(1).await?;
+
+    // channel is full, so the send waits until the message is received
+    let pending = tokio::spawn({
+        let sender = sender.clone();
+        async move { sender.send_async(2).await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!pending.is_finished());
+
+    assert_eq!(rx.recv().await, Some(1));
+    tokio::time::timeout(Duration::from_secs(1), pending).await???;
+    assert_eq!(rx.recv().await, Some(2));
+    assert!(!sender.receiver_dropped());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_return_error_on_closed_channel() -> eyre::Result<()> {
+    let (tx, rx) = mpsc::channel(1);
+    let sender = Sender::bounded(tx);
+    drop(rx);
+
+    let err = sender.send_async(1).await.err().expect("receiver is dropped");
+    assert_eq!(err.0, 1);
+    assert!(sender.receiver_dropped());
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let sender = Sender::new(tx);
+    drop(rx);
+
+    assert!(sender.send_async(1).await.is_err());
+    assert!(sender.receiver_dropped());
+
+    Ok(())
+}
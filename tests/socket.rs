@@ -0,0 +1,86 @@
+#![cfg(unix)]
+
+use std::{path::PathBuf, sync::Arc};
+
+use reth::providers::{Chain, ExecutionOutcome};
+use reth_exex_plugin::{
+    BlockRange, ChainKind, ExExNotification, ExExPlugin, ExExPluginManager, NodeInfo,
+    NormalizedNotification, SocketExExPlugin, SOCKET_EXEX_PLUGIN_ID,
+};
+use reth_exex_test_utils::test_exex_context;
+use tokio::{
+    io::AsyncReadExt,
+    net::{UnixListener, UnixStream},
+};
+
+/// Helper to read a single length-prefixed JSON frame
+async fn read_frame(conn: &mut UnixStream) -> eyre::Result<NormalizedNotification> {
+    let len = conn.read_u32().await?;
+    let mut payload = vec![0u8; len as usize];
+    conn.read_exact(&mut payload).await?;
+    Ok(serde_json::from_slice(&payload)?)
+}
+
+fn socket_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn should_stream_notifications_and_reconnect() -> eyre::Result<()> {
+    let (_exex_ctx, exex_handle) = test_exex_context().await?;
+    let notification = Arc::new(ExExNotification::ChainCommitted {
+        new: Chain::from_block(exex_handle.genesis.clone(), ExecutionOutcome::default(), None)
+            .into(),
+    });
+    let expected = NormalizedNotification {
+        kind: ChainKind::Commit,
+        reverted: None,
+        committed: Some(BlockRange { from: 0, to: 0 }),
+    };
+
+    let node_info = NodeInfo {
+        chain_id: 1,
+        head_number: 0,
+        head_hash: exex_handle.genesis.hash(),
+        sequence: 1,
+    };
+
+    let path = socket_path("exex_socket_plugin.sock");
+    let listener = UnixListener::bind(&path)?;
+    let plugin = SocketExExPlugin::new(&path);
+
+    // First notification opens a connection
+    plugin.handle_notification(notification.clone(), &node_info).await?;
+    let (mut conn, _) = listener.accept().await?;
+    assert_eq!(read_frame(&mut conn).await?, expected);
+
+    // Peer disconnects, the next notification must be delivered over a new connection
+    drop(conn);
+    plugin.handle_notification(notification.clone(), &node_info).await?;
+    let (mut conn, _) = listener.accept().await?;
+    assert_eq!(read_frame(&mut conn).await?, expected);
+
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_register_socket_plugin() -> eyre::Result<()> {
+    let (_rpc_request_tx, rpc_request_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+
+    let plugin = SocketExExPlugin::new(socket_path("exex_socket_plugin_register.sock"));
+    let id = plugin_manager.register_plugin(Box::new(plugin)).await?;
+
+    assert_eq!(id, SOCKET_EXEX_PLUGIN_ID);
+    assert_eq!(plugin_manager.plugins(), vec![SOCKET_EXEX_PLUGIN_ID]);
+
+    plugin_manager.unload_plugin(&id)?;
+    assert!(plugin_manager.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use std::{sync::Arc, time::Duration};
+
+use reth::primitives::B256;
+use reth_exex_plugin::{
+    testing::NotificationGenerator, ExExPlugin, ExExPluginManager, NodeInfo,
+    NormalizedNotification, WebhookConfig, WebhookExExPlugin, WEBHOOK_EXEX_PLUGIN_ID,
+};
+use reth_exex_test_utils::test_exex_context;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+    sync::mpsc,
+};
+
+/// Mock HTTP endpoint, which answers requests with given statuses in order, sending
+/// bodies of received requests.
+async fn mock_endpoint(statuses: Vec<u16>) -> eyre::Result<(String, mpsc::Receiver<Vec<u8>>)> {
+    mock_endpoint_at("127.0.0.1:0", statuses).await
+}
+
+/// Mock HTTP endpoint like [`mock_endpoint`], listening on a given address.
+async fn mock_endpoint_at(
+    addr: &str,
+    statuses: Vec<u16>,
+) -> eyre::Result<(String, mpsc::Receiver<Vec<u8>>)> {
+    let listener = TcpListener::bind(addr).await?;
+    let url = format!("http://{}/blocks", listener.local_addr()?);
+    let (tx, rx) = mpsc::channel(statuses.len().max(1));
+
+    tokio::spawn(async move {
+        for status in statuses {
+            let (conn, _) = listener.accept().await?;
+            let mut conn = BufReader::new(conn);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                conn.read_line(&mut line).await?;
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(len) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse()?;
+                }
+            }
+            let mut body = vec![0u8; content_length];
+            conn.read_exact(&mut body).await?;
+            tx.send(body).await?;
+
+            let response = format!("HTTP/1.1 {status} Status\r\nContent-Length: 0\r\n\r\n");
+            conn.write_all(response.as_bytes()).await?;
+        }
+        eyre::Ok(())
+    });
+
+    Ok((url, rx))
+}
+
+/// Loads a webhook plugin with a given config, outside of a manager.
+async fn load(config: WebhookConfig) -> eyre::Result<WebhookExExPlugin> {
+    let mut plugin = WebhookExExPlugin::new(config);
+    plugin.on_load().await?;
+    Ok(plugin)
+}
+
+/// Dispatches a commit notification to the plugin.
+async fn handle(plugin: &WebhookExExPlugin, number: u64) -> eyre::Result<()> {
+    let node_info = NodeInfo { chain_id: 1, head_number: 0, head_hash: B256::ZERO, sequence: 1 };
+    let notification = NotificationGenerator::new(number).commit(1)?;
+    plugin.handle_notification(Arc::new(notification), &node_info).await
+}
+
+#[tokio::test]
+async fn should_post_notifications_retrying_non_2xx_responses() -> eyre::Result<()> {
+    let (url, mut bodies) = mock_endpoint(vec![503, 200]).await?;
+
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    let config = serde_json::json!({ "url": url, "initial_backoff_ms": 10 });
+    let id = plugin_manager
+        .register_plugin_with_config(Box::new(WebhookExExPlugin::default()), config)
+        .await?;
+    assert_eq!(id, WEBHOOK_EXEX_PLUGIN_ID);
+
+    let notification = NotificationGenerator::new(1).commit(2)?;
+    let expected = NormalizedNotification::from(&notification);
+    plugin_manager.handle_notification(notification).await?;
+    plugin_manager.drain().await?;
+    assert_eq!(plugin_manager.plugin_failures(&id), Some(0), "Retried request succeeds");
+
+    // The notification is posted twice, since the first request is answered with 503
+    for _ in 0..2 {
+        let body = tokio::time::timeout(Duration::from_secs(1), bodies.recv()).await?;
+        let posted: NormalizedNotification = serde_json::from_slice(&body.expect("posted"))?;
+        assert_eq!(posted, expected);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_drop_notification_once_retries_are_exhausted() -> eyre::Result<()> {
+    let (url, _bodies) = mock_endpoint(vec![500, 500, 200]).await?;
+
+    let config =
+        WebhookConfig { max_retries: 1, initial_backoff_ms: 10, ..WebhookConfig::new(url) };
+    let plugin = load(config).await?;
+
+    // Dispatch completes once queued, the request is retried in background
+    handle(&plugin, 1).await?;
+    handle(&plugin, 2).await?;
+    plugin.flush().await?;
+    assert_eq!(plugin.pending(), 0);
+    assert_eq!(plugin.failed(), 1, "First notification is dropped after a retry");
+
+    // Unsupported URL is refused on registration
+    let (_rpc_request_tx, rpc_request_rx) = mpsc::unbounded_channel();
+    let (exex_ctx, _exex_handle) = test_exex_context().await?;
+    let mut plugin_manager = ExExPluginManager::new(exex_ctx, rpc_request_rx);
+    let config = serde_json::json!({ "url": "https://example.com" });
+    let plugin = WebhookExExPlugin::default().with_id("SecureWebhookExEx");
+    assert!(plugin_manager.register_plugin_with_config(Box::new(plugin), config).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_fail_notification_once_queue_is_full() -> eyre::Result<()> {
+    // Endpoint accepts connections, but never answers
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/blocks", listener.local_addr()?);
+
+    let config = WebhookConfig { queue_capacity: 1, ..WebhookConfig::new(url) };
+    let plugin = load(config).await?;
+
+    // The sending task doesn't run before the test yields, so the first notification stays queued
+    handle(&plugin, 1).await?;
+    let err = handle(&plugin, 2).await.expect_err("queue is full");
+    assert!(err.to_string().contains("full"), "{err}");
+    assert_eq!(plugin.pending(), 1);
+    assert_eq!(plugin.failed(), 0, "Refused notification isn't counted as dropped");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_post_notifications_to_ipv6_host() -> eyre::Result<()> {
+    // The port follows the closing bracket of the host, e.g. `http://[::1]:8080/blocks`
+    let (url, mut bodies) = mock_endpoint_at("[::1]:0", vec![200]).await?;
+    assert!(url.starts_with("http://[::1]:"), "{url}");
+
+    let plugin = load(WebhookConfig::new(url)).await?;
+    handle(&plugin, 1).await?;
+    plugin.flush().await?;
+    assert_eq!(plugin.failed(), 0);
+    let body = tokio::time::timeout(Duration::from_secs(1), bodies.recv()).await?;
+    assert!(body.is_some(), "Notification is posted");
+
+    // Hosts with malformed ports are refused on load
+    for url in [
+        "http://::1/blocks",
+        "http://[::1/blocks",
+        "http://[::1]8080/blocks",
+        "http://[::1]:port/blocks",
+        "http://localhost:/blocks",
+    ] {
+        assert!(load(WebhookConfig::new(url)).await.is_err(), "{url} is refused");
+    }
+
+    Ok(())
+}